@@ -0,0 +1,42 @@
+// The hash functions of HashML-DSA, by their ACVP names
+
+use ml_dsa::{Prehash, prehash};
+use sha2::Digest;
+use sha3::digest::{ExtendableOutput, Update};
+
+use crate::{Error, Result};
+
+fn digest<D: Digest>(message: &[u8]) -> Vec<u8> {
+    D::digest(message).to_vec()
+}
+
+fn xof<D: Default + Update + ExtendableOutput>(message: &[u8], len: usize) -> Vec<u8> {
+    let mut digest = vec![0; len];
+    D::default().chain(message).finalize_xof_into(&mut digest);
+    digest
+}
+
+/// The DER-encoded OID of the function named `name`, and the digest of `message` under it
+pub(crate) fn prehash(name: &str, message: &[u8]) -> Result<(&'static [u8], Vec<u8>)> {
+    Ok(match name {
+        "SHA2-224" => (prehash::Sha224::OID, digest::<sha2::Sha224>(message)),
+        "SHA2-256" => (prehash::Sha256::OID, digest::<sha2::Sha256>(message)),
+        "SHA2-384" => (prehash::Sha384::OID, digest::<sha2::Sha384>(message)),
+        "SHA2-512" => (prehash::Sha512::OID, digest::<sha2::Sha512>(message)),
+        "SHA2-512/224" => (
+            prehash::Sha512_224::OID,
+            digest::<sha2::Sha512_224>(message),
+        ),
+        "SHA2-512/256" => (
+            prehash::Sha512_256::OID,
+            digest::<sha2::Sha512_256>(message),
+        ),
+        "SHA3-224" => (prehash::Sha3_224::OID, digest::<sha3::Sha3_224>(message)),
+        "SHA3-256" => (prehash::Sha3_256::OID, digest::<sha3::Sha3_256>(message)),
+        "SHA3-384" => (prehash::Sha3_384::OID, digest::<sha3::Sha3_384>(message)),
+        "SHA3-512" => (prehash::Sha3_512::OID, digest::<sha3::Sha3_512>(message)),
+        "SHAKE-128" => (prehash::Shake128::OID, xof::<sha3::Shake128>(message, 32)),
+        "SHAKE-256" => (prehash::Shake256::OID, xof::<sha3::Shake256>(message, 64)),
+        other => return Err(Error(format!("unsupported hashAlg {other:?}"))),
+    })
+}
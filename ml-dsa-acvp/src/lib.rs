@@ -0,0 +1,443 @@
+#![doc = include_str!("../README.md")]
+#![warn(clippy::pedantic)] // Be pedantic by default
+#![allow(non_snake_case)] // Allow notation matching the spec
+#![deny(missing_docs)] // Require all public interfaces to be documented
+
+mod hash;
+
+use core::fmt;
+use core::str::FromStr;
+
+use ml_dsa::{
+    AesXof, B32, B64, Blake3Xof, EncodedSignature, EncodedSigningKey, EncodedVerifyingKey, KeyPair,
+    MlDsa44, MlDsa65, MlDsa87, MlDsaParams, ShakeXof, Signature, SigningKey, VerifyingKey, Xof,
+};
+use serde_json::{Map, Value};
+use sha3::{Digest, Sha3_256};
+
+/// An error in a prompt, naming the group, test and field that could not be processed
+#[derive(Debug)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+type Result<T> = core::result::Result<T, Error>;
+
+macro_rules! bail {
+    ($($arg:tt)*) => {
+        return Err(Error(format!($($arg)*)))
+    };
+}
+
+/// The hash functions to run the tests with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// SHAKE, as FIPS 204 specifies.  Only these results can be submitted to ACVP.
+    #[default]
+    Shake,
+
+    /// BLAKE3, as in [`Blake3Xof`]
+    Blake3,
+
+    /// AES-128 in counter mode, as in [`AesXof`]
+    Aes,
+}
+
+impl FromStr for Backend {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "shake" => Ok(Self::Shake),
+            "blake3" => Ok(Self::Blake3),
+            "aes" => Ok(Self::Aes),
+            _ => bail!("unknown backend {s:?}, expected shake, blake3 or aes"),
+        }
+    }
+}
+
+/// What to write for each test
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Output {
+    /// A response, with only the identifiers and results of the tests
+    #[default]
+    Response,
+
+    /// Expected results, with the inputs of each test next to its results.  With a non-standard
+    /// backend, the keys and signatures of the prompt are replaced by ones for that backend, so
+    /// that the output is a self-contained vector set for it.
+    ExpectedResults,
+}
+
+/// Process a prompt, which may be either a bare vector set or one in an array with the ACVP
+/// version, as the server sends it.
+///
+/// # Errors
+///
+/// Returns an error if the prompt is not an ML-DSA vector set, or if a test is missing a field or
+/// has a malformed one.
+pub fn process(prompt: &Value, backend: Backend, output: Output) -> Result<Value> {
+    let vector_set = match prompt {
+        Value::Array(elements) => elements
+            .iter()
+            .find(|element| element.get("testGroups").is_some())
+            .ok_or_else(|| Error("the prompt has no vector set".into()))?,
+        other => other,
+    };
+
+    let algorithm = string(vector_set, "algorithm")?;
+    if algorithm != "ML-DSA" {
+        bail!("unsupported algorithm {algorithm:?}");
+    }
+
+    let mode = match string(vector_set, "mode")? {
+        "keyGen" => Mode::KeyGen,
+        "sigGen" => Mode::SigGen,
+        "sigVer" => Mode::SigVer,
+        other => bail!("unsupported mode {other:?}"),
+    };
+
+    let mut result = Map::new();
+    for name in ["vsId", "algorithm", "mode", "revision", "isSample"] {
+        if let Some(value) = vector_set.get(name) {
+            result.insert(name.into(), value.clone());
+        }
+    }
+
+    let groups = list(vector_set, "testGroups")?
+        .iter()
+        .map(|group| {
+            Group::new(group, mode, output)
+                .and_then(|group| dispatch(&group, backend))
+                .map_err(|Error(e)| Error(format!("tgId {}: {e}", field(group, "tgId"))))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    result.insert("testGroups".into(), groups.into());
+
+    Ok(result.into())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    KeyGen,
+    SigGen,
+    SigVer,
+}
+
+// A test group, with the settings that apply to all of its tests
+struct Group<'a> {
+    value: &'a Value,
+    mode: Mode,
+    output: Output,
+    interface: Interface,
+    deterministic: bool,
+}
+
+// How the input of the internal functions is made from a test
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Interface {
+    // The message is M'
+    Internal,
+    // The test has μ instead of a message
+    ExternalMu,
+    // ML-DSA.Sign with a context
+    Pure,
+    // HashML-DSA.Sign with a context and a hash function
+    PreHash,
+}
+
+impl<'a> Group<'a> {
+    fn new(value: &'a Value, mode: Mode, output: Output) -> Result<Self> {
+        let flag = |name| value.get(name).and_then(Value::as_bool).unwrap_or(false);
+
+        // Vector sets from before the external interface was tested have no interface
+        let interface = match value.get("signatureInterface").and_then(Value::as_str) {
+            None | Some("internal") if flag("externalMu") => Interface::ExternalMu,
+            None | Some("internal") => Interface::Internal,
+            Some("external") => match value.get("preHash").and_then(Value::as_str) {
+                None | Some("pure") => Interface::Pure,
+                Some("preHash") => Interface::PreHash,
+                Some(other) => bail!("unknown preHash {other:?}"),
+            },
+            Some(other) => bail!("unknown signatureInterface {other:?}"),
+        };
+
+        Ok(Self {
+            value,
+            mode,
+            output,
+            interface,
+            deterministic: flag("deterministic"),
+        })
+    }
+
+    // The input to sign or verify: M' for the internal functions, or a precomputed μ
+    fn input(&self, test: &Value) -> Result<Input> {
+        let message = match self.interface {
+            Interface::ExternalMu => return Ok(Input::Mu(array(test, "mu")?)),
+            Interface::Internal => return Ok(Input::Message(bytes(test, "message")?)),
+            Interface::Pure | Interface::PreHash => bytes(test, "message")?,
+        };
+
+        // Algorithm 2 ML-DSA.Sign and Algorithm 4 HashML-DSA.Sign
+        let context = match test.get("context") {
+            Some(_) => bytes(test, "context")?,
+            None => Vec::new(),
+        };
+        let Ok(context_len) = u8::try_from(context.len()) else {
+            bail!("the context is longer than 255 bytes");
+        };
+
+        let mut Mp = Vec::new();
+        if self.interface == Interface::PreHash {
+            let (oid, digest) = hash::prehash(string(test, "hashAlg")?, &message)?;
+            Mp.extend([1, context_len]);
+            Mp.extend(context);
+            Mp.extend(oid);
+            Mp.extend(digest);
+        } else {
+            Mp.extend([0, context_len]);
+            Mp.extend(context);
+            Mp.extend(message);
+        }
+        Ok(Input::Message(Mp))
+    }
+}
+
+enum Input {
+    Message(Vec<u8>),
+    Mu(B64),
+}
+
+fn dispatch(group: &Group<'_>, backend: Backend) -> Result<Value> {
+    macro_rules! dispatch {
+        ($($name:literal => $P:ty),*) => {
+            match (string(group.value, "parameterSet")?, backend) {
+                $(
+                    ($name, Backend::Shake) => run_group::<$P, ShakeXof>(group),
+                    ($name, Backend::Blake3) => run_group::<$P, Blake3Xof>(group),
+                    ($name, Backend::Aes) => run_group::<$P, AesXof>(group),
+                )*
+                (other, _) => bail!("unsupported parameterSet {other:?}"),
+            }
+        };
+    }
+
+    dispatch!("ML-DSA-44" => MlDsa44, "ML-DSA-65" => MlDsa65, "ML-DSA-87" => MlDsa87)
+}
+
+fn run_group<P: MlDsaParams, X: Xof>(group: &Group<'_>) -> Result<Value> {
+    let mut result = match group.output {
+        Output::Response => Map::from_iter([("tgId".into(), field(group.value, "tgId").clone())]),
+        Output::ExpectedResults => object(group.value)?.clone(),
+    };
+
+    // With a non-standard backend, the keys of the prompt are replaced.  A key that the whole
+    // group shares is replaced once, in the group.
+    let replace_keys = group.output == Output::ExpectedResults && X::ALGORITHM_ARC.is_some();
+    let group_keys = match group.mode {
+        Mode::SigGen if replace_keys && group.value.get("sk").is_some() => {
+            Some(replacement_key_pair::<P, X>(&bytes(group.value, "sk")?))
+        }
+        Mode::SigVer if replace_keys && group.value.get("pk").is_some() => {
+            Some(replacement_key_pair::<P, X>(&bytes(group.value, "pk")?))
+        }
+        _ => None,
+    };
+    if let Some(kp) = &group_keys {
+        insert_key_pair(&mut result, kp);
+    }
+
+    let tests = list(group.value, "tests")?
+        .iter()
+        .map(|test| {
+            let mut outputs = match group.mode {
+                Mode::KeyGen => key_gen::<P, X>(test),
+                Mode::SigGen => sig_gen::<P, X>(group, test, replace_keys, group_keys.as_ref()),
+                Mode::SigVer => sig_ver::<P, X>(group, test, replace_keys, group_keys.as_ref()),
+            }
+            .map_err(|Error(e)| Error(format!("tcId {}: {e}", field(test, "tcId"))))?;
+
+            let mut result = match group.output {
+                Output::Response => Map::from_iter([("tcId".into(), field(test, "tcId").clone())]),
+                Output::ExpectedResults => object(test)?.clone(),
+            };
+            result.append(&mut outputs);
+            Ok(result.into())
+        })
+        .collect::<Result<Vec<Value>>>()?;
+    result.insert("tests".into(), tests.into());
+
+    Ok(result.into())
+}
+
+// Algorithm 6 ML-DSA.KeyGen_internal
+fn key_gen<P: MlDsaParams, X: Xof>(test: &Value) -> Result<Map<String, Value>> {
+    let kp = KeyPair::<P, X>::from_seed(&array(test, "seed")?);
+
+    let mut outputs = Map::new();
+    insert_key_pair(&mut outputs, &kp);
+    Ok(outputs)
+}
+
+// Algorithm 7 ML-DSA.Sign_internal
+fn sig_gen<P: MlDsaParams, X: Xof>(
+    group: &Group<'_>,
+    test: &Value,
+    replace_keys: bool,
+    group_keys: Option<&KeyPair<P, X>>,
+) -> Result<Map<String, Value>> {
+    let mut outputs = Map::new();
+
+    let sk = if let Some(kp) = group_keys {
+        kp.signing_key().clone()
+    } else if replace_keys {
+        let kp = replacement_key_pair::<P, X>(&bytes(test, "sk")?);
+        insert_key_pair(&mut outputs, &kp);
+        kp.signing_key().clone()
+    } else {
+        let sk: EncodedSigningKey<P> = array(test, "sk").or_else(|_| array(group.value, "sk"))?;
+        SigningKey::decode(&sk)
+    };
+
+    let rnd = if group.deterministic {
+        B32::default()
+    } else {
+        array(test, "rnd")?
+    };
+
+    let sig = sign(&sk, &group.input(test)?, &rnd)?;
+    outputs.insert("signature".into(), hex(&sig.encode()));
+    Ok(outputs)
+}
+
+// Algorithm 8 ML-DSA.Verify_internal
+fn sig_ver<P: MlDsaParams, X: Xof>(
+    group: &Group<'_>,
+    test: &Value,
+    replace_keys: bool,
+    group_keys: Option<&KeyPair<P, X>>,
+) -> Result<Map<String, Value>> {
+    let mut outputs = Map::new();
+    let input = group.input(test)?;
+    let pk: EncodedVerifyingKey<P> = array(test, "pk").or_else(|_| array(group.value, "pk"))?;
+    let sig: EncodedSignature<P> = array(test, "signature")?;
+
+    if !replace_keys {
+        let passed = verify(&VerifyingKey::<P, X>::decode(&pk), &input, &sig);
+        outputs.insert("testPassed".into(), passed.into());
+        return Ok(outputs);
+    }
+
+    // The verdict of the standard functions on the prompt is kept, by signing with the replacement
+    // key and modifying the commitment of the signatures that should fail
+    let passed = verify(&VerifyingKey::<P, ShakeXof>::decode(&pk), &input, &sig);
+    let replacement;
+    let kp = if let Some(kp) = group_keys {
+        kp
+    } else {
+        replacement = replacement_key_pair::<P, X>(&pk);
+        insert_key_pair(&mut outputs, &replacement);
+        &replacement
+    };
+
+    let mut sig = sign(kp.signing_key(), &input, &B32::default())?.encode();
+    if !passed {
+        sig[0] ^= 1;
+    }
+    debug_assert_eq!(verify(kp.verifying_key(), &input, &sig), passed);
+
+    let reason = if passed {
+        "no modification"
+    } else {
+        "modified signature - commitment"
+    };
+    outputs.insert("signature".into(), hex(&sig));
+    outputs.insert("testPassed".into(), passed.into());
+    outputs.insert("reason".into(), reason.into());
+    Ok(outputs)
+}
+
+fn sign<P: MlDsaParams, X: Xof>(
+    sk: &SigningKey<P, X>,
+    input: &Input,
+    rnd: &B32,
+) -> Result<Signature<P>> {
+    let sig = match input {
+        Input::Message(Mp) => sk.sign_internal(&[Mp], rnd),
+        Input::Mu(mu) => sk.sign_mu_internal(mu, rnd),
+    };
+    sig.map_err(|e| Error(format!("signing failed: {e}")))
+}
+
+fn verify<P: MlDsaParams, X: Xof>(
+    vk: &VerifyingKey<P, X>,
+    input: &Input,
+    sig: &EncodedSignature<P>,
+) -> bool {
+    // A signature that does not decode fails verification
+    let Some(sig) = Signature::<P>::decode(sig) else {
+        return false;
+    };
+
+    match input {
+        Input::Message(Mp) => vk.verify_internal(&[Mp], &sig),
+        Input::Mu(mu) => vk.verify_mu(mu, &sig),
+    }
+}
+
+// The key pair that takes the place of a key from the prompt, generated from a hash of that key
+fn replacement_key_pair<P: MlDsaParams, X: Xof>(key: &[u8]) -> KeyPair<P, X> {
+    let seed = Sha3_256::digest(key);
+    KeyPair::from_seed(&B32::try_from(seed.as_slice()).expect("SHA3-256 output is 32 bytes"))
+}
+
+fn insert_key_pair<P: MlDsaParams, X: Xof>(map: &mut Map<String, Value>, kp: &KeyPair<P, X>) {
+    map.insert("pk".into(), hex(&kp.verifying_key().encode()));
+    map.insert("sk".into(), hex(&kp.signing_key().encode()));
+}
+
+fn hex(bytes: &[u8]) -> Value {
+    hex::encode_upper(bytes).into()
+}
+
+fn field<'a>(value: &'a Value, name: &str) -> &'a Value {
+    value.get(name).unwrap_or(&Value::Null)
+}
+
+fn object(value: &Value) -> Result<&Map<String, Value>> {
+    value
+        .as_object()
+        .ok_or_else(|| Error("expected an object".into()))
+}
+
+fn list<'a>(value: &'a Value, name: &str) -> Result<&'a Vec<Value>> {
+    match value.get(name) {
+        Some(Value::Array(list)) => Ok(list),
+        Some(_) => bail!("{name} is not an array"),
+        None => bail!("missing {name}"),
+    }
+}
+
+fn string<'a>(value: &'a Value, name: &str) -> Result<&'a str> {
+    match value.get(name) {
+        Some(Value::String(s)) => Ok(s),
+        Some(_) => bail!("{name} is not a string"),
+        None => bail!("missing {name}"),
+    }
+}
+
+fn bytes(value: &Value, name: &str) -> Result<Vec<u8>> {
+    hex::decode(string(value, name)?).map_err(|e| Error(format!("{name}: {e}")))
+}
+
+fn array<A: for<'a> TryFrom<&'a [u8]>>(value: &Value, name: &str) -> Result<A> {
+    let bytes = bytes(value, name)?;
+    A::try_from(&bytes).map_err(|_| Error(format!("{name} has the wrong length ({})", bytes.len())))
+}
@@ -0,0 +1,56 @@
+//! Process an ACVP ML-DSA prompt; see the README
+
+use std::fs;
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use ml_dsa_acvp::{Backend, Output, process};
+
+const USAGE: &str =
+    "usage: ml-dsa-acvp [--backend shake|blake3|aes] [--expected] prompt.json [response.json]";
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let mut backend = Backend::default();
+    let mut output = Output::default();
+    let mut paths = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--backend" => backend = args.next().ok_or(USAGE)?.parse()?,
+            "--expected" => output = Output::ExpectedResults,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ if arg.starts_with('-') => return Err(USAGE.into()),
+            _ => paths.push(arg),
+        }
+    }
+
+    let (prompt, response) = match paths.as_slice() {
+        [prompt] => (prompt, None),
+        [prompt, response] => (prompt, Some(response)),
+        _ => return Err(USAGE.into()),
+    };
+
+    let prompt = serde_json::from_str(&fs::read_to_string(prompt)?)?;
+    let mut json = serde_json::to_string_pretty(&process(&prompt, backend, output)?)?;
+    json.push('\n');
+
+    match response {
+        Some(path) => fs::write(path, json)?,
+        None => io::stdout().write_all(json.as_bytes())?,
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("ml-dsa-acvp: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use ml_dsa::{EncodedSigningKey, MlDsa44, MuBuilder, SigningKey};
+use ml_dsa_acvp::{Backend, Output, process};
+use serde_json::{Value, json};
+
+use std::{fs::read_to_string, path::PathBuf};
+
+// The vector sets of the ml-dsa tests, which have the expected results next to the inputs
+fn load(name: &str) -> Value {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../ml-dsa/tests");
+    p.push(name);
+    serde_json::from_str(&read_to_string(p).unwrap()).unwrap()
+}
+
+// Remove the results from the tests of a vector set, leaving a prompt
+fn prompt(mut vector_set: Value, results: &[&str]) -> Value {
+    for group in vector_set["testGroups"].as_array_mut().unwrap() {
+        for test in group["tests"].as_array_mut().unwrap() {
+            for name in results {
+                test.as_object_mut().unwrap().remove(*name);
+            }
+        }
+    }
+    vector_set
+}
+
+// Check that each test of `response` has the results that `expected` lists for it
+fn check(expected: &Value, response: &Value, results: &[&str]) {
+    let groups = expected["testGroups"].as_array().unwrap();
+    assert_eq!(
+        response["testGroups"].as_array().unwrap().len(),
+        groups.len()
+    );
+
+    for (tg, rg) in groups
+        .iter()
+        .zip(response["testGroups"].as_array().unwrap())
+    {
+        assert_eq!(tg["tgId"], rg["tgId"]);
+        for (tc, rc) in tg["tests"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .zip(rg["tests"].as_array().unwrap())
+        {
+            assert_eq!(tc["tcId"], rc["tcId"]);
+            for name in results {
+                assert_eq!(
+                    tc[name], rc[name],
+                    "tgId {} tcId {} {name}",
+                    tg["tgId"], tc["tcId"]
+                );
+            }
+        }
+    }
+}
+
+fn round_trip(file: &str, results: &[&str]) {
+    let expected = load(file);
+    let response = process(
+        &prompt(expected.clone(), results),
+        Backend::Shake,
+        Output::Response,
+    )
+    .unwrap();
+    check(&expected, &response, results);
+
+    // Responses only have the identifiers and results
+    let test = &response["testGroups"][0]["tests"][0];
+    assert_eq!(test.as_object().unwrap().len(), results.len() + 1);
+}
+
+#[test]
+fn key_gen() {
+    round_trip("key-gen.json", &["pk", "sk"]);
+}
+
+#[test]
+fn sig_gen() {
+    round_trip("sig-gen.json", &["signature"]);
+}
+
+#[test]
+fn sig_ver() {
+    round_trip("sig-ver.json", &["testPassed"]);
+}
+
+#[test]
+fn hash_sig_gen() {
+    round_trip("hash-sig-gen.json", &["signature"]);
+}
+
+#[test]
+fn server_prompt() {
+    // Prompts from the server are wrapped in an array with the protocol version
+    let vector_set = prompt(load("key-gen.json"), &["pk", "sk"]);
+    let wrapped = json!([{ "acvVersion": "1.0" }, vector_set]);
+    assert_eq!(
+        process(&wrapped, Backend::Shake, Output::Response).unwrap(),
+        process(&vector_set, Backend::Shake, Output::Response).unwrap()
+    );
+
+    let mut unknown = vector_set;
+    unknown["testGroups"][1]["parameterSet"] = "ML-DSA-66".into();
+    let error = process(&unknown, Backend::Shake, Output::Response).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "tgId 2: unsupported parameterSet \"ML-DSA-66\""
+    );
+}
+
+#[test]
+fn external_mu() {
+    let vector_set = load("sig-gen.json");
+    let group = &vector_set["testGroups"][0];
+    let test = &group["tests"][0];
+    assert_eq!(group["parameterSet"], "ML-DSA-44");
+
+    let sk = hex::decode(test["sk"].as_str().unwrap()).unwrap();
+    let sk = EncodedSigningKey::<MlDsa44>::try_from(sk.as_slice()).unwrap();
+    let vk = SigningKey::<MlDsa44>::decode(&sk).verifying_key();
+    let message = hex::decode(test["message"].as_str().unwrap()).unwrap();
+    let mut mu = MuBuilder::new(&vk, &[]).unwrap();
+    mu.update(&message);
+    let mu = mu.finalize();
+
+    // Signing μ gives the same signature as the external interface with an empty context
+    let prompt = json!({
+        "algorithm": "ML-DSA",
+        "mode": "sigGen",
+        "testGroups": [
+            {
+                "tgId": 1,
+                "parameterSet": "ML-DSA-44",
+                "deterministic": true,
+                "signatureInterface": "internal",
+                "externalMu": true,
+                "tests": [{ "tcId": 1, "sk": test["sk"], "mu": hex::encode_upper(mu) }],
+            },
+            {
+                "tgId": 2,
+                "parameterSet": "ML-DSA-44",
+                "deterministic": true,
+                "signatureInterface": "external",
+                "preHash": "pure",
+                "tests": [{ "tcId": 2, "sk": test["sk"], "message": test["message"], "context": "" }],
+            },
+        ],
+    });
+    let response = process(&prompt, Backend::Shake, Output::Response).unwrap();
+    let signature = &response["testGroups"][0]["tests"][0]["signature"];
+    assert_eq!(
+        signature,
+        &response["testGroups"][1]["tests"][0]["signature"]
+    );
+
+    // The signature verifies against μ
+    let prompt = json!({
+        "algorithm": "ML-DSA",
+        "mode": "sigVer",
+        "testGroups": [{
+            "tgId": 1,
+            "parameterSet": "ML-DSA-44",
+            "signatureInterface": "internal",
+            "externalMu": true,
+            "pk": hex::encode_upper(vk.encode()),
+            "tests": [{ "tcId": 1, "mu": hex::encode_upper(mu), "signature": signature }],
+        }],
+    });
+    let response = process(&prompt, Backend::Shake, Output::Response).unwrap();
+    assert_eq!(response["testGroups"][0]["tests"][0]["testPassed"], true);
+}
+
+#[test]
+fn expected_results() {
+    for backend in [Backend::Blake3, Backend::Aes] {
+        for (file, results) in [
+            ("key-gen.json", &["pk", "sk"][..]),
+            ("sig-gen.json", &["signature"]),
+            ("hash-sig-gen.json", &["signature"]),
+            ("sig-ver.json", &["testPassed"]),
+        ] {
+            let original = load(file);
+            let expected = process(
+                &prompt(original.clone(), results),
+                backend,
+                Output::ExpectedResults,
+            )
+            .unwrap();
+
+            // The expected results are reproducible from their own inputs
+            let response = process(
+                &prompt(expected.clone(), results),
+                backend,
+                Output::Response,
+            )
+            .unwrap();
+            check(&expected, &response, results);
+
+            // The keys are replaced, and sigVer keeps the verdicts of the standard functions
+            if file == "sig-ver.json" {
+                let group = &expected["testGroups"][0];
+                assert_ne!(group["pk"], original["testGroups"][0]["pk"]);
+                check(&original, &expected, &["testPassed"]);
+            } else if file != "key-gen.json" {
+                let test = &expected["testGroups"][0]["tests"][0];
+                assert_ne!(test["sk"], original["testGroups"][0]["tests"][0]["sk"]);
+            }
+        }
+    }
+}
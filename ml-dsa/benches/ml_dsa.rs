@@ -1,6 +1,11 @@
 use criterion::{Criterion, criterion_group, criterion_main};
 use hybrid_array::{Array, ArraySize};
-use ml_dsa::{B32, B256, KeyGen, MlDsa65, Signature, SigningKey, VerifyingKey};
+use ml_dsa::{
+    AesXof, B32, B256, Blake3Xof, CompactSigningKey, KeyPair, MlDsa65, ShakeXof, Signature,
+    SigningKey, VerifyingKey, Xof,
+};
+#[cfg(feature = "std")]
+use ml_dsa::{Blake3OptimizedXof, VerifyingKeyCache};
 use rand::CryptoRng;
 
 pub fn rand<L: ArraySize, R: CryptoRng + ?Sized>(rng: &mut R) -> Array<u8, L> {
@@ -9,7 +14,7 @@ pub fn rand<L: ArraySize, R: CryptoRng + ?Sized>(rng: &mut R) -> Array<u8, L> {
     val
 }
 
-fn criterion_benchmark(c: &mut Criterion) {
+fn bench_xof<X: Xof>(c: &mut Criterion, name: &str) {
     // CD: Removed RNG to make benchmarks deterministic
     // let mut rng = rand::rng();
     // let xi: B32 = rand(&mut rng);
@@ -24,7 +29,7 @@ fn criterion_benchmark(c: &mut Criterion) {
     let mut ctx: B32 = Array::default();
     ctx.as_mut_slice().fill(0x33);
 
-    let kp = MlDsa65::key_gen_internal(&xi);
+    let kp = KeyPair::<MlDsa65, X>::from_seed(&xi);
     let sk = kp.signing_key();
     let vk = kp.verifying_key();
     let sig = sk.sign_deterministic(&m, &ctx).unwrap();
@@ -33,40 +38,89 @@ fn criterion_benchmark(c: &mut Criterion) {
     let vk_bytes = vk.encode();
     let sig_bytes = sig.encode();
 
+    let mut group = c.benchmark_group(name);
+
     // Key generation
-    c.bench_function("keygen", |b| {
+    group.bench_function("keygen", |b| {
         b.iter(|| {
-            let kp = MlDsa65::key_gen_internal(&xi);
+            let kp = KeyPair::<MlDsa65, X>::from_seed(&xi);
             let _sk_bytes = kp.signing_key().encode();
             let _vk_bytes = kp.verifying_key().encode();
         })
     });
 
     // Signing
-    c.bench_function("sign", |b| {
+    group.bench_function("sign", |b| {
         b.iter(|| {
-            let sk = SigningKey::<MlDsa65>::decode(&sk_bytes);
+            let sk = SigningKey::<MlDsa65, X>::decode(&sk_bytes);
             let _sig = sk.sign_deterministic(&m, &ctx);
         })
     });
 
+    // Signing with a key that is already expanded, and with a compact key that is expanded for
+    // each signature.  See `key_sizes` for the memory each one takes.
+    group.bench_function("sign_expanded", |b| {
+        b.iter(|| {
+            let _sig = sk.sign_deterministic(&m, &ctx);
+        })
+    });
+
+    let compact = CompactSigningKey::<MlDsa65, X>::from_seed(&xi);
+    group.bench_function("sign_compact", |b| {
+        b.iter(|| {
+            let _sig = compact.sign_deterministic(&m, &ctx);
+        })
+    });
+
     // Verifying
-    c.bench_function("verify", |b| {
+    group.bench_function("verify", |b| {
         b.iter(|| {
-            let vk = VerifyingKey::<MlDsa65>::decode(&vk_bytes);
+            let vk = VerifyingKey::<MlDsa65, X>::decode(&vk_bytes);
             let sig = Signature::<MlDsa65>::decode(&sig_bytes).unwrap();
             let _ver = vk.verify_with_context(&m, &ctx, &sig);
         })
     });
 
+    // Verifying with keys decoded through a cache, which expands each key only once
+    #[cfg(feature = "std")]
+    {
+        let cache = VerifyingKeyCache::<MlDsa65, X>::new(16);
+        group.bench_function("verify_cached", |b| {
+            b.iter(|| {
+                let vk = cache.decode(&vk_bytes);
+                let sig = Signature::<MlDsa65>::decode(&sig_bytes).unwrap();
+                let _ver = vk.verify_with_context(&m, &ctx, &sig);
+            })
+        });
+    }
+
     // Round trip
-    c.bench_function("round_trip", |b| {
+    group.bench_function("round_trip", |b| {
         b.iter(|| {
-            let kp = MlDsa65::key_gen_internal(&xi);
+            let kp = KeyPair::<MlDsa65, X>::from_seed(&xi);
             let sig = kp.signing_key().sign_deterministic(&m, &ctx).unwrap();
             let _ver = kp.verifying_key().verify_with_context(&m, &ctx, &sig);
         })
     });
+
+    group.finish();
+}
+
+fn key_sizes() {
+    println!(
+        "MlDsa65 key sizes: SigningKey {} bytes, CompactSigningKey {} bytes",
+        size_of::<SigningKey<MlDsa65>>(),
+        size_of::<CompactSigningKey<MlDsa65>>(),
+    );
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    key_sizes();
+    bench_xof::<ShakeXof>(c, "shake");
+    bench_xof::<AesXof>(c, "aes");
+    bench_xof::<Blake3Xof>(c, "blake3-niave");
+    #[cfg(feature = "std")]
+    bench_xof::<Blake3OptimizedXof>(c, "blake3-optimized");
 }
 
 criterion_group!(benches, criterion_benchmark);
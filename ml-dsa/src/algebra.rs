@@ -5,6 +5,7 @@ use hybrid_array::{
     typenum::{Shleft, U1, U13, Unsigned},
 };
 
+use crate::avx2::Avx2;
 use crate::define_field;
 use crate::module_lattice::algebra;
 
@@ -139,6 +140,10 @@ impl AlgebraExt for Polynomial {
     }
 
     fn infinity_norm(&self) -> u32 {
+        if let Some(avx2) = Avx2::detect() {
+            return avx2.infinity_norm(self);
+        }
+
         self.0.iter().map(AlgebraExt::infinity_norm).max().unwrap()
     }
 
@@ -154,6 +159,10 @@ impl AlgebraExt for Polynomial {
     }
 
     fn high_bits<TwoGamma2: Unsigned>(&self) -> Self {
+        if let Some((r1, _)) = Avx2::detect().and_then(|avx2| avx2.decompose::<TwoGamma2>(self)) {
+            return r1;
+        }
+
         Self(
             self.0
                 .iter()
@@ -163,6 +172,10 @@ impl AlgebraExt for Polynomial {
     }
 
     fn low_bits<TwoGamma2: Unsigned>(&self) -> Self {
+        if let Some((_, r0)) = Avx2::detect().and_then(|avx2| avx2.decompose::<TwoGamma2>(self)) {
+            return r0;
+        }
+
         Self(
             self.0
                 .iter()
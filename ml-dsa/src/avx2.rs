@@ -0,0 +1,802 @@
+// An AVX2 backend for the NTT and the other hot loops over polynomials.
+//
+// This is the only module in the crate that uses SIMD intrinsics.  Its entry points are methods of
+// an `Avx2` token, which can only be obtained by checking that the CPU supports AVX2, so the
+// `#[target_feature]` functions below are only ever called where they are sound.  Callers try
+// the token first and fall back to the scalar code:
+//
+//     if let Some(avx2) = Avx2::detect() {
+//         return avx2.ntt(self);
+//     }
+//
+// On other architectures, and on x86 without `std` (where there is no runtime detection) unless
+// AVX2 is enabled at compile time, `Avx2` is uninhabited and these branches compile away.
+//
+// Coefficients are kept in [0, q), as in the scalar code.  Products are reduced with Montgomery
+// reduction rather than Barrett reduction, since it is cheaper in 32-bit lanes.  Both produce the
+// canonical representative, so the results are bit-for-bit the same as those of the scalar code.
+
+pub(crate) use imp::Avx2;
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    any(feature = "std", target_feature = "avx2")
+))]
+mod imp {
+    // Newer versions of Rust allow calling most intrinsics outside of `unsafe` blocks within
+    // `#[target_feature]` functions, but the MSRV does not.
+    #![allow(unused_unsafe)]
+    // The `Avx2` token is passed by value only to show that AVX2 is available
+    #![allow(clippy::unused_self)]
+
+    use arch::{
+        __m256i, _mm_cvtsi32_si128, _mm256_add_epi32, _mm256_add_epi64, _mm256_and_si256,
+        _mm256_andnot_si256, _mm256_blend_epi32, _mm256_blendv_epi8, _mm256_cmpeq_epi32,
+        _mm256_cmpgt_epi32, _mm256_loadu_si256, _mm256_max_epu32, _mm256_min_epu32,
+        _mm256_mul_epu32, _mm256_mullo_epi32, _mm256_or_si256, _mm256_permute2x128_si256,
+        _mm256_set1_epi32, _mm256_set1_epi64x, _mm256_setzero_si256, _mm256_sll_epi64,
+        _mm256_slli_epi64, _mm256_srl_epi64, _mm256_srli_epi64, _mm256_storeu_si256,
+        _mm256_sub_epi32, _mm256_unpackhi_epi64, _mm256_unpacklo_epi64, _mm256_xor_si256,
+    };
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86 as arch;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64 as arch;
+
+    use hybrid_array::{Array, typenum::Unsigned};
+
+    use crate::algebra::{BarrettReduce, BaseField, Elem, Field, NttPolynomial, Polynomial};
+    use crate::ntt::ZETA_POW_BITREV;
+    use crate::secret::Secret;
+
+    const Q: u32 = BaseField::Q;
+
+    // -q^{-1} mod 2^32
+    const NEG_QINV: u32 = 4_236_238_847;
+    const _: () = assert!(Q.wrapping_mul(NEG_QINV) == u32::MAX);
+
+    // 2^64 mod q, which takes a Montgomery product back to the plain product
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::as_conversions)]
+    #[allow(clippy::integer_division_remainder_used)]
+    const R2: u32 = ((1u128 << 64) % (Q as u128)) as u32;
+
+    // 256^{-1} in Montgomery form
+    const INVERSE_256_MONT: u32 = to_mont(8_347_681);
+
+    // The tables of `ntt.rs`, in Montgomery form so that a Montgomery product with an entry is a
+    // plain product with the corresponding power of zeta
+    const ZETA_MONT: [u32; 256] = zeta_table(false);
+    const NEG_ZETA_MONT: [u32; 256] = zeta_table(true);
+
+    // The Keccak-f[1600] round constants, for the iota step
+    const KECCAK_ROUND_CONSTANTS: [u64; 24] = [
+        0x0000_0000_0000_0001,
+        0x0000_0000_0000_8082,
+        0x8000_0000_0000_808a,
+        0x8000_0000_8000_8000,
+        0x0000_0000_0000_808b,
+        0x0000_0000_8000_0001,
+        0x8000_0000_8000_8081,
+        0x8000_0000_0000_8009,
+        0x0000_0000_0000_008a,
+        0x0000_0000_0000_0088,
+        0x0000_0000_8000_8009,
+        0x0000_0000_8000_000a,
+        0x0000_0000_8000_808b,
+        0x8000_0000_0000_008b,
+        0x8000_0000_0000_8089,
+        0x8000_0000_0000_8003,
+        0x8000_0000_0000_8002,
+        0x8000_0000_0000_0080,
+        0x0000_0000_0000_800a,
+        0x8000_0000_8000_000a,
+        0x8000_0000_8000_8081,
+        0x8000_0000_0000_8080,
+        0x0000_0000_8000_0001,
+        0x8000_0000_8000_8008,
+    ];
+
+    // The rotation of word x + 5y in the rho step
+    const KECCAK_RHO: [i32; 25] = [
+        0, 1, 62, 28, 27, //
+        36, 44, 6, 55, 20, //
+        3, 10, 43, 25, 39, //
+        41, 45, 15, 21, 8, //
+        18, 2, 61, 56, 14,
+    ];
+
+    // Where the pi step moves word x + 5y: to y + 5(2x + 3y mod 5)
+    const KECCAK_PI: [usize; 25] = keccak_pi();
+
+    // (x + 1) mod 5 and (x + 4) mod 5, for the theta and chi steps
+    const NEXT: [usize; 5] = [1, 2, 3, 4, 0];
+    const PREV: [usize; 5] = [4, 0, 1, 2, 3];
+
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::as_conversions)]
+    #[allow(clippy::integer_division_remainder_used)]
+    const fn to_mont(x: u32) -> u32 {
+        (((x as u64) << 32) % (Q as u64)) as u32
+    }
+
+    #[allow(clippy::integer_division_remainder_used)]
+    const fn keccak_pi() -> [usize; 25] {
+        let mut table = [0; 25];
+        let mut i = 0;
+        while i < 25 {
+            let (x, y) = (i % 5, i / 5);
+            table[i] = y + 5 * ((2 * x + 3 * y) % 5);
+            i += 1;
+        }
+        table
+    }
+
+    const fn zeta_table(negate: bool) -> [u32; 256] {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let z = ZETA_POW_BITREV[i].0;
+            table[i] = to_mont(if negate && z != 0 { Q - z } else { z });
+            i += 1;
+        }
+        table
+    }
+
+    /// Proof that the CPU supports AVX2
+    #[derive(Clone, Copy)]
+    pub(crate) struct Avx2(());
+
+    impl Avx2 {
+        pub(crate) fn detect() -> Option<Self> {
+            #[cfg(target_feature = "avx2")]
+            let detected = true;
+            #[cfg(not(target_feature = "avx2"))]
+            let detected = std::is_x86_feature_detected!("avx2");
+
+            detected.then_some(Self(()))
+        }
+
+        // The working copies are wiped, since the polynomials may be secret
+        pub(crate) fn ntt(self, f: &Polynomial) -> NttPolynomial {
+            let mut w = Secret::new(coefficients(&f.0));
+            // SAFETY: `self` shows that the CPU supports AVX2
+            unsafe { ntt_avx2(&mut w) };
+            NttPolynomial::new(elems(&w))
+        }
+
+        pub(crate) fn ntt_inverse(self, f_hat: &NttPolynomial) -> Polynomial {
+            let mut w = Secret::new(coefficients(&f_hat.0));
+            // SAFETY: `self` shows that the CPU supports AVX2
+            unsafe { ntt_inverse_avx2(&mut w) };
+            Polynomial::new(elems(&w))
+        }
+
+        pub(crate) fn multiply_ntts(
+            self,
+            f_hat: &NttPolynomial,
+            g_hat: &NttPolynomial,
+        ) -> NttPolynomial {
+            let mut w = Secret::new(coefficients(&f_hat.0));
+            let g = Secret::new(coefficients(&g_hat.0));
+            // SAFETY: `self` shows that the CPU supports AVX2
+            unsafe { multiply_avx2(&mut w, &g) };
+            NttPolynomial::new(elems(&w))
+        }
+
+        pub(crate) fn infinity_norm(self, f: &Polynomial) -> u32 {
+            // SAFETY: `self` shows that the CPU supports AVX2
+            unsafe { infinity_norm_avx2(&coefficients(&f.0)) }
+        }
+
+        /// Decompose every coefficient of `f`, returning the high and low parts.  Returns `None`
+        /// for moduli that the vectorized reduction does not support, which the scalar code
+        /// handles instead.
+        #[allow(clippy::integer_division_remainder_used)]
+        pub(crate) fn decompose<TwoGamma2: Unsigned>(
+            self,
+            f: &Polynomial,
+        ) -> Option<(Polynomial, Polynomial)> {
+            let modulus = TwoGamma2::U32;
+            let multiplier = u32::try_from(TwoGamma2::MULTIPLIER).ok()?;
+            let shift = i32::try_from(TwoGamma2::SHIFT).ok()?;
+            if (Q - 1) % modulus != 0 || shift >= 64 {
+                return None;
+            }
+
+            let w = coefficients(&f.0);
+            let (mut r1, mut r0) = ([0; 256], [0; 256]);
+            // SAFETY: `self` shows that the CPU supports AVX2
+            unsafe {
+                decompose_avx2(&w, modulus, multiplier, shift, &mut r1, &mut r0);
+            }
+            Some((Polynomial::new(elems(&r1)), Polynomial::new(elems(&r0))))
+        }
+
+        /// Apply Keccak-f[1600] to four interleaved states, as laid out by `ShakeX4`
+        pub(crate) fn keccak_f1600_x4(self, state: &mut [[u64; 4]; 25]) {
+            // SAFETY: `self` shows that the CPU supports AVX2
+            unsafe { keccak_f1600_x4_avx2(state) };
+        }
+    }
+
+    fn coefficients(f: &Array<Elem, hybrid_array::typenum::U256>) -> [u32; 256] {
+        core::array::from_fn(|i| f[i].0)
+    }
+
+    fn elems(w: &[u32; 256]) -> Array<Elem, hybrid_array::typenum::U256> {
+        Array::from_fn(|i| Elem::new(w[i]))
+    }
+
+    const fn lane(x: u32) -> i32 {
+        i32::from_ne_bytes(x.to_ne_bytes())
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn splat(x: u32) -> __m256i {
+        unsafe { _mm256_set1_epi32(lane(x)) }
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn load(w: &[u32], i: usize) -> __m256i {
+        let w = &w[i..i + 8];
+        // SAFETY: `w` holds eight `u32`s, and the load is unaligned
+        #[allow(clippy::cast_ptr_alignment)]
+        unsafe {
+            _mm256_loadu_si256(w.as_ptr().cast())
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn store(w: &mut [u32], i: usize, x: __m256i) {
+        let w = &mut w[i..i + 8];
+        // SAFETY: `w` holds eight `u32`s, and the store is unaligned
+        #[allow(clippy::cast_ptr_alignment)]
+        unsafe {
+            _mm256_storeu_si256(w.as_mut_ptr().cast(), x);
+        }
+    }
+
+    /// Load the entries of `table` at `indices`
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn gather(table: &[u32; 256], indices: [usize; 8]) -> __m256i {
+        unsafe { load(&indices.map(|i| table[i]), 0) }
+    }
+
+    /// Reduce lanes in [0, 2q) to [0, q)
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn csub(x: __m256i) -> __m256i {
+        unsafe { _mm256_min_epu32(x, _mm256_sub_epi32(x, splat(Q))) }
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn add(a: __m256i, b: __m256i) -> __m256i {
+        unsafe { csub(_mm256_add_epi32(a, b)) }
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn sub(a: __m256i, b: __m256i) -> __m256i {
+        unsafe { csub(_mm256_sub_epi32(_mm256_add_epi32(a, splat(Q)), b)) }
+    }
+
+    /// Montgomery reduction of the 64-bit lanes of `x`, each less than 2^32 q.  The result, in
+    /// [0, 2q), is in the low half of each lane.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn mont_reduce(x: __m256i) -> __m256i {
+        unsafe {
+            let m = _mm256_mul_epu32(x, splat(NEG_QINV));
+            let x = _mm256_add_epi64(x, _mm256_mul_epu32(m, splat(Q)));
+            _mm256_srli_epi64::<32>(x)
+        }
+    }
+
+    /// a * b * 2^{-32} mod q
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn mont_mul(a: __m256i, b: __m256i) -> __m256i {
+        unsafe {
+            let even = mont_reduce(_mm256_mul_epu32(a, b));
+            let odd = mont_reduce(_mm256_mul_epu32(
+                _mm256_srli_epi64::<32>(a),
+                _mm256_srli_epi64::<32>(b),
+            ));
+            csub(_mm256_blend_epi32::<0xAA>(
+                even,
+                _mm256_slli_epi64::<32>(odd),
+            ))
+        }
+    }
+
+    /// The butterfly of Algorithm 41 NTT, with `z` in Montgomery form
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn ct_butterfly(a: __m256i, b: __m256i, z: __m256i) -> (__m256i, __m256i) {
+        unsafe {
+            let t = mont_mul(b, z);
+            (add(a, t), sub(a, t))
+        }
+    }
+
+    /// The butterfly of Algorithm 42 NTT^{-1}, with `z` in Montgomery form
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn gs_butterfly(a: __m256i, b: __m256i, z: __m256i) -> (__m256i, __m256i) {
+        unsafe { (add(a, b), mont_mul(sub(a, b), z)) }
+    }
+
+    // The layers with `len >= 8` work on whole vectors.  The last three layers pair up
+    // coefficients within a vector, so each step loads two vectors `x` and `y` (16 coefficients)
+    // and shuffles them into `lo` and `hi`, so that lane i of `lo` and lane i of `hi` are the two
+    // inputs of one butterfly.  The lanes of `z` follow the blocks of the layer through the same
+    // shuffle.
+    //
+    //   len = 4:  lo = [x0 x1 x2 x3 y0 y1 y2 y3]  hi = [x4 x5 x6 x7 y4 y5 y6 y7]
+    //   len = 2:  lo = [x0 x1 y0 y1 x4 x5 y4 y5]  hi = [x2 x3 y2 y3 x6 x7 y6 y7]
+    //   len = 1:  lo = [x0 y0 x2 y2 x4 y4 x6 y6]  hi = [x1 y1 x3 y3 x5 y5 x7 y7]
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn split4(x: __m256i, y: __m256i) -> (__m256i, __m256i) {
+        unsafe {
+            (
+                _mm256_permute2x128_si256::<0x20>(x, y),
+                _mm256_permute2x128_si256::<0x31>(x, y),
+            )
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn split2(x: __m256i, y: __m256i) -> (__m256i, __m256i) {
+        unsafe { (_mm256_unpacklo_epi64(x, y), _mm256_unpackhi_epi64(x, y)) }
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn split1(x: __m256i, y: __m256i) -> (__m256i, __m256i) {
+        unsafe {
+            (
+                _mm256_blend_epi32::<0xAA>(x, _mm256_slli_epi64::<32>(y)),
+                _mm256_blend_epi32::<0xAA>(_mm256_srli_epi64::<32>(x), y),
+            )
+        }
+    }
+
+    // Each shuffle is its own inverse, with `lo` and `hi` in place of `x` and `y`
+    use split1 as join1;
+    use split2 as join2;
+    use split4 as join4;
+
+    /// The indices of the blocks in each lane, for the block indices `b` of `x` and `y`
+    fn lanes4(b: [usize; 2]) -> [usize; 8] {
+        [b[0], b[0], b[0], b[0], b[1], b[1], b[1], b[1]]
+    }
+
+    fn lanes2(b: [usize; 4]) -> [usize; 8] {
+        [b[0], b[0], b[2], b[2], b[1], b[1], b[3], b[3]]
+    }
+
+    fn lanes1(b: [usize; 8]) -> [usize; 8] {
+        [b[0], b[4], b[1], b[5], b[2], b[6], b[3], b[7]]
+    }
+
+    // Algorithm 41 NTT
+    #[target_feature(enable = "avx2")]
+    unsafe fn ntt_avx2(w: &mut [u32; 256]) {
+        unsafe {
+            let mut m = 0;
+            for len in [128, 64, 32, 16, 8] {
+                for start in (0..256).step_by(2 * len) {
+                    m += 1;
+                    let z = splat(ZETA_MONT[m]);
+
+                    for j in (start..(start + len)).step_by(8) {
+                        let (a, b) = ct_butterfly(load(w, j), load(w, j + len), z);
+                        store(w, j, a);
+                        store(w, j + len, b);
+                    }
+                }
+            }
+
+            for i in (0..256).step_by(16) {
+                let (lo, hi) = split4(load(w, i), load(w, i + 8));
+                let z = gather(&ZETA_MONT, lanes4([0, 1].map(|k| 32 + (i >> 3) + k)));
+                let (lo, hi) = ct_butterfly(lo, hi, z);
+                let (x, y) = join4(lo, hi);
+                store(w, i, x);
+                store(w, i + 8, y);
+            }
+
+            for i in (0..256).step_by(16) {
+                let (lo, hi) = split2(load(w, i), load(w, i + 8));
+                let z = gather(&ZETA_MONT, lanes2([0, 1, 2, 3].map(|k| 64 + (i >> 2) + k)));
+                let (lo, hi) = ct_butterfly(lo, hi, z);
+                let (x, y) = join2(lo, hi);
+                store(w, i, x);
+                store(w, i + 8, y);
+            }
+
+            for i in (0..256).step_by(16) {
+                let (lo, hi) = split1(load(w, i), load(w, i + 8));
+                let z = gather(
+                    &ZETA_MONT,
+                    lanes1([0, 1, 2, 3, 4, 5, 6, 7].map(|k| 128 + (i >> 1) + k)),
+                );
+                let (lo, hi) = ct_butterfly(lo, hi, z);
+                let (x, y) = join1(lo, hi);
+                store(w, i, x);
+                store(w, i + 8, y);
+            }
+        }
+    }
+
+    // Algorithm 42 NTT^{-1}
+    #[target_feature(enable = "avx2")]
+    unsafe fn ntt_inverse_avx2(w: &mut [u32; 256]) {
+        unsafe {
+            for i in (0..256).step_by(16) {
+                let (lo, hi) = split1(load(w, i), load(w, i + 8));
+                let z = gather(
+                    &NEG_ZETA_MONT,
+                    lanes1([0, 1, 2, 3, 4, 5, 6, 7].map(|k| 255 - (i >> 1) - k)),
+                );
+                let (lo, hi) = gs_butterfly(lo, hi, z);
+                let (x, y) = join1(lo, hi);
+                store(w, i, x);
+                store(w, i + 8, y);
+            }
+
+            for i in (0..256).step_by(16) {
+                let (lo, hi) = split2(load(w, i), load(w, i + 8));
+                let z = gather(
+                    &NEG_ZETA_MONT,
+                    lanes2([0, 1, 2, 3].map(|k| 127 - (i >> 2) - k)),
+                );
+                let (lo, hi) = gs_butterfly(lo, hi, z);
+                let (x, y) = join2(lo, hi);
+                store(w, i, x);
+                store(w, i + 8, y);
+            }
+
+            for i in (0..256).step_by(16) {
+                let (lo, hi) = split4(load(w, i), load(w, i + 8));
+                let z = gather(&NEG_ZETA_MONT, lanes4([0, 1].map(|k| 63 - (i >> 3) - k)));
+                let (lo, hi) = gs_butterfly(lo, hi, z);
+                let (x, y) = join4(lo, hi);
+                store(w, i, x);
+                store(w, i + 8, y);
+            }
+
+            let mut m = 32;
+            for len in [8, 16, 32, 64, 128] {
+                for start in (0..256).step_by(2 * len) {
+                    m -= 1;
+                    let z = splat(NEG_ZETA_MONT[m]);
+
+                    for j in (start..(start + len)).step_by(8) {
+                        let (a, b) = gs_butterfly(load(w, j), load(w, j + len), z);
+                        store(w, j, a);
+                        store(w, j + len, b);
+                    }
+                }
+            }
+
+            let inverse_256 = splat(INVERSE_256_MONT);
+            for i in (0..256).step_by(8) {
+                let x = mont_mul(load(w, i), inverse_256);
+                store(w, i, x);
+            }
+        }
+    }
+
+    // Algorithm 45 MultiplyNTT
+    #[target_feature(enable = "avx2")]
+    unsafe fn multiply_avx2(a: &mut [u32; 256], b: &[u32; 256]) {
+        unsafe {
+            let r2 = splat(R2);
+            for i in (0..256).step_by(8) {
+                let x = mont_mul(mont_mul(load(a, i), load(b, i)), r2);
+                store(a, i, x);
+            }
+        }
+    }
+
+    #[target_feature(enable = "avx2")]
+    unsafe fn infinity_norm_avx2(w: &[u32; 256]) -> u32 {
+        unsafe {
+            let q = splat(Q);
+            let mut max = _mm256_setzero_si256();
+            for i in (0..256).step_by(8) {
+                let x = load(w, i);
+                max = _mm256_max_epu32(max, _mm256_min_epu32(x, _mm256_sub_epi32(q, x)));
+            }
+
+            let mut lanes = [0; 8];
+            store(&mut lanes, 0, max);
+            lanes.into_iter().max().unwrap_or_default()
+        }
+    }
+
+    // Algorithm 36 Decompose, for a modulus M dividing q - 1
+    #[allow(clippy::integer_division_remainder_used)]
+    #[target_feature(enable = "avx2")]
+    unsafe fn decompose_avx2(
+        w: &[u32; 256],
+        modulus: u32,
+        multiplier: u32,
+        shift: i32,
+        r1: &mut [u32; 256],
+        r0: &mut [u32; 256],
+    ) {
+        unsafe {
+            let m = splat(modulus);
+            let m_minus_one = splat(modulus - 1);
+            let half_m = splat(modulus >> 1);
+            let q_minus_m = splat(Q - modulus);
+            let top = splat((Q - 1) / modulus);
+            let multiplier = splat(multiplier);
+            let shift = _mm_cvtsi32_si128(shift);
+
+            for i in (0..256).step_by(8) {
+                let x = load(w, i);
+
+                // Barrett reduction, as in `BarrettReduce::reduce`
+                let even = _mm256_srl_epi64(_mm256_mul_epu32(x, multiplier), shift);
+                let odd = _mm256_srl_epi64(
+                    _mm256_mul_epu32(_mm256_srli_epi64::<32>(x), multiplier),
+                    shift,
+                );
+                let quotient = _mm256_blend_epi32::<0xAA>(even, _mm256_slli_epi64::<32>(odd));
+                let remainder = _mm256_sub_epi32(x, _mm256_mullo_epi32(quotient, m));
+
+                // Lanes are less than 2^31, so signed comparisons work.  The masks are -1 where
+                // true, so subtracting a mask adds one.
+                let over = _mm256_cmpgt_epi32(remainder, m_minus_one);
+                let remainder = _mm256_sub_epi32(remainder, _mm256_and_si256(over, m));
+                let quotient = _mm256_sub_epi32(quotient, over);
+
+                // mod+-: map the upper half of [0, M) to negative values, and carry into r1
+                let carry = _mm256_cmpgt_epi32(remainder, half_m);
+                let low = _mm256_add_epi32(remainder, _mm256_and_si256(carry, q_minus_m));
+                let high = _mm256_sub_epi32(quotient, carry);
+
+                // r+ - r0 = q - 1
+                let wrap = _mm256_cmpeq_epi32(high, top);
+                let low = _mm256_blendv_epi8(low, sub(low, splat(1)), wrap);
+                let high = _mm256_andnot_si256(wrap, high);
+
+                store(r1, i, high);
+                store(r0, i, low);
+            }
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn rotate_left(x: __m256i, n: i32) -> __m256i {
+        // Shifting by 64 gives zero, so a rotation by zero works too
+        unsafe {
+            _mm256_or_si256(
+                _mm256_sll_epi64(x, _mm_cvtsi32_si128(n)),
+                _mm256_srl_epi64(x, _mm_cvtsi32_si128(64 - n)),
+            )
+        }
+    }
+
+    #[target_feature(enable = "avx2")]
+    unsafe fn keccak_f1600_x4_avx2(state: &mut [[u64; 4]; 25]) {
+        unsafe {
+            let mut a = [_mm256_setzero_si256(); 25];
+            for (a, words) in a.iter_mut().zip(state.iter()) {
+                // SAFETY: `words` holds four `u64`s, and the load is unaligned
+                *a = _mm256_loadu_si256(words.as_ptr().cast());
+            }
+
+            for rc in KECCAK_ROUND_CONSTANTS {
+                // theta
+                let mut c = [_mm256_setzero_si256(); 5];
+                for x in 0..5 {
+                    c[x] = _mm256_xor_si256(
+                        _mm256_xor_si256(_mm256_xor_si256(a[x], a[x + 5]), a[x + 10]),
+                        _mm256_xor_si256(a[x + 15], a[x + 20]),
+                    );
+                }
+                for x in 0..5 {
+                    let d = _mm256_xor_si256(c[PREV[x]], rotate_left(c[NEXT[x]], 1));
+                    for y in (0..25).step_by(5) {
+                        a[x + y] = _mm256_xor_si256(a[x + y], d);
+                    }
+                }
+
+                // rho and pi
+                let mut b = [_mm256_setzero_si256(); 25];
+                for i in 0..25 {
+                    b[KECCAK_PI[i]] = rotate_left(a[i], KECCAK_RHO[i]);
+                }
+
+                // chi
+                for y in (0..25).step_by(5) {
+                    for x in 0..5 {
+                        a[x + y] = _mm256_xor_si256(
+                            b[x + y],
+                            _mm256_andnot_si256(b[NEXT[x] + y], b[NEXT[NEXT[x]] + y]),
+                        );
+                    }
+                }
+
+                // iota
+                a[0] = _mm256_xor_si256(
+                    a[0],
+                    _mm256_set1_epi64x(i64::from_ne_bytes(rc.to_ne_bytes())),
+                );
+            }
+
+            for (a, words) in a.iter().zip(state.iter_mut()) {
+                // SAFETY: `words` holds four `u64`s, and the store is unaligned
+                _mm256_storeu_si256(words.as_mut_ptr().cast(), *a);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    #[allow(clippy::integer_division_remainder_used)]
+    mod test {
+        use super::*;
+        use crate::algebra::{AlgebraExt, Decompose};
+        use crate::ntt::{multiply_ntts_scalar, ntt_inverse_scalar, ntt_scalar};
+        use crate::{MlDsa44, MlDsa65, ParameterSet, QMinus1};
+        use hybrid_array::typenum::Quot;
+        use rand::Rng;
+
+        fn polynomials() -> impl Iterator<Item = Polynomial> {
+            let mut rng = rand::rng();
+            let edges = [0, 1, Q >> 1, (Q >> 1) + 1, Q - 1];
+            let edge_cases = edges
+                .into_iter()
+                .map(|x| Polynomial::new(Array::from_fn(|_| Elem::new(x))));
+            let mixed = Polynomial::new(Array::from_fn(|i| Elem::new(edges[i % edges.len()])));
+            let random = (0..100).map(move |_| {
+                Polynomial::new(Array::from_fn(|_| Elem::new(rng.random_range(0..Q))))
+            });
+            edge_cases.chain([mixed]).chain(random)
+        }
+
+        #[test]
+        fn ntt_matches_scalar() {
+            let Some(avx2) = Avx2::detect() else {
+                return;
+            };
+
+            for f in polynomials() {
+                let f_hat = ntt_scalar(&f);
+                assert_eq!(avx2.ntt(&f), f_hat);
+
+                let g_hat = NttPolynomial::new(f.0.clone());
+                assert_eq!(avx2.ntt_inverse(&g_hat), ntt_inverse_scalar(&g_hat));
+
+                assert_eq!(
+                    avx2.multiply_ntts(&f_hat, &g_hat),
+                    multiply_ntts_scalar(&f_hat, &g_hat)
+                );
+            }
+        }
+
+        #[test]
+        fn infinity_norm_matches_scalar() {
+            let Some(avx2) = Avx2::detect() else {
+                return;
+            };
+
+            for f in polynomials() {
+                let expected = f.0.iter().map(AlgebraExt::infinity_norm).max().unwrap();
+                assert_eq!(avx2.infinity_norm(&f), expected);
+            }
+        }
+
+        fn check_decompose<TwoGamma2: Unsigned>(avx2: Avx2) {
+            // Every value in [0, q), a polynomial at a time
+            for start in (0..Q).step_by(256) {
+                let f = Polynomial::new(Array::from_fn(|i| {
+                    Elem::new((start + u32::try_from(i).unwrap()).min(Q - 1))
+                }));
+                let (r1, r0) = avx2.decompose::<TwoGamma2>(&f).unwrap();
+                for (x, (y1, y0)) in f.0.iter().zip(r1.0.iter().zip(r0.0.iter())) {
+                    assert_eq!(x.decompose::<TwoGamma2>(), (*y1, *y0), "{x:?}");
+                }
+            }
+        }
+
+        #[test]
+        fn decompose_matches_scalar() {
+            let Some(avx2) = Avx2::detect() else {
+                return;
+            };
+
+            check_decompose::<<MlDsa44 as ParameterSet>::TwoGamma2>(avx2);
+            check_decompose::<<MlDsa65 as ParameterSet>::TwoGamma2>(avx2);
+
+            // Custom parameter sets may use any other divisor of q - 1
+            check_decompose::<Quot<QMinus1, hybrid_array::typenum::U64>>(avx2);
+
+            // Moduli that do not divide q - 1 are left to the scalar code
+            let f = Polynomial::default();
+            assert!(avx2.decompose::<hybrid_array::typenum::U1000>(&f).is_none());
+        }
+
+        #[test]
+        fn keccak_matches_scalar() {
+            let Some(avx2) = Avx2::detect() else {
+                return;
+            };
+
+            let mut rng = rand::rng();
+            for _ in 0..10 {
+                let mut state: [[u64; 4]; 25] = rng.random();
+                let mut expected = state;
+                crate::keccak_x4::keccak_f1600_x4_scalar(&mut expected);
+                avx2.keccak_f1600_x4(&mut state);
+                assert_eq!(state, expected);
+            }
+        }
+    }
+}
+
+#[cfg(not(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    any(feature = "std", target_feature = "avx2")
+)))]
+mod imp {
+    use hybrid_array::typenum::Unsigned;
+
+    use crate::algebra::{NttPolynomial, Polynomial};
+
+    /// AVX2 is never available on this target
+    #[derive(Clone, Copy)]
+    pub(crate) struct Avx2(core::convert::Infallible);
+
+    impl Avx2 {
+        pub(crate) fn detect() -> Option<Self> {
+            None
+        }
+
+        pub(crate) fn ntt(self, _f: &Polynomial) -> NttPolynomial {
+            match self.0 {}
+        }
+
+        pub(crate) fn ntt_inverse(self, _f_hat: &NttPolynomial) -> Polynomial {
+            match self.0 {}
+        }
+
+        pub(crate) fn multiply_ntts(
+            self,
+            _f_hat: &NttPolynomial,
+            _g_hat: &NttPolynomial,
+        ) -> NttPolynomial {
+            match self.0 {}
+        }
+
+        pub(crate) fn infinity_norm(self, _f: &Polynomial) -> u32 {
+            match self.0 {}
+        }
+
+        #[allow(clippy::extra_unused_type_parameters)]
+        pub(crate) fn decompose<TwoGamma2: Unsigned>(
+            self,
+            _f: &Polynomial,
+        ) -> Option<(Polynomial, Polynomial)> {
+            match self.0 {}
+        }
+
+        pub(crate) fn keccak_f1600_x4(self, _state: &mut [[u64; 4]; 25]) {
+            match self.0 {}
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::VerifyingKey;
+use crate::crypto::ShakeXof;
+use crate::param::{EncodedVerifyingKey, MlDsaParams};
+use crate::xof::Xof;
+
+#[cfg(feature = "pkcs8")]
+use pkcs8::{
+    der::AnyRef,
+    spki::{self, AssociatedAlgorithmIdentifier, SubjectPublicKeyInfoRef},
+};
+
+/// A bounded, thread-safe cache of decoded verifying keys, keyed by their encoding.
+///
+/// A verifying key computes its matrix `A_hat` and other derived values the first time it is
+/// used.  Applications that decode the same keys over and over, such as a validator seeing the
+/// same certificate authorities in every chain, can decode them through a cache instead.  It hands
+/// out shared references to one key per encoding, so the derived values are only computed once.
+///
+/// When the cache is full, the least recently used key is evicted.  Keys that have been handed out
+/// remain usable after they are evicted.
+///
+/// ```
+/// use ml_dsa::{KeyGen, MlDsa65, VerifyingKeyCache};
+///
+/// let kp = MlDsa65::key_gen_internal(&Default::default());
+/// let sig = kp.signing_key().sign_deterministic(b"Hello world", &[]).unwrap();
+/// let enc = kp.verifying_key().encode();
+///
+/// let cache = VerifyingKeyCache::<MlDsa65>::new(64);
+/// let vk = cache.decode(&enc);
+/// assert!(vk.verify_with_context(b"Hello world", &[], &sig));
+///
+/// // The second decoding is the same key, already expanded
+/// assert!(std::sync::Arc::ptr_eq(&vk, &cache.decode(&enc)));
+/// ```
+pub struct VerifyingKeyCache<P: MlDsaParams, X: Xof = ShakeXof> {
+    capacity: usize,
+    state: Mutex<CacheState<P, X>>,
+}
+
+struct CacheState<P: MlDsaParams, X: Xof> {
+    entries: HashMap<EncodedVerifyingKey<P>, CacheEntry<P, X>>,
+    // Incremented on every lookup, to order the entries by last use
+    clock: u64,
+}
+
+struct CacheEntry<P: MlDsaParams, X: Xof> {
+    key: Arc<VerifyingKey<P, X>>,
+    last_used: u64,
+}
+
+impl<P: MlDsaParams, X: Xof> VerifyingKeyCache<P, X> {
+    /// Create a cache holding at most `capacity` keys.  A cache with capacity zero holds nothing,
+    /// and decodes every key afresh.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(CacheState {
+                entries: HashMap::new(),
+                clock: 0,
+            }),
+        }
+    }
+
+    /// The maximum number of keys held by the cache
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of keys held by the cache
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Whether the cache holds no keys
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove every key from the cache
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    /// Decode a verifying key, or return the key previously decoded from the same bytes.
+    pub fn decode(&self, enc: &EncodedVerifyingKey<P>) -> Arc<VerifyingKey<P, X>> {
+        if let Some(key) = self.lock().get(enc) {
+            return key;
+        }
+
+        // Decoding is cheap, but there is no need to hold the lock for it
+        let key = Arc::new(VerifyingKey::decode(enc));
+        if self.capacity == 0 {
+            return key;
+        }
+
+        let mut state = self.lock();
+        if let Some(key) = state.get(enc) {
+            // Another thread got there first
+            return key;
+        }
+
+        if state.entries.len() >= self.capacity {
+            state.evict();
+        }
+
+        let last_used = state.tick();
+        state.entries.insert(
+            enc.clone(),
+            CacheEntry {
+                key: key.clone(),
+                last_used,
+            },
+        );
+        key
+    }
+
+    /// Decode a verifying key from a DER-encoded `SubjectPublicKeyInfo`, or return the key
+    /// previously decoded from the same key bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the DER is malformed, if the algorithm identifier does not match the
+    /// parameter set, or if the key has the wrong length.
+    #[cfg(feature = "pkcs8")]
+    pub fn decode_public_key_der(&self, der: &[u8]) -> spki::Result<Arc<VerifyingKey<P, X>>>
+    where
+        P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    {
+        let spki = SubjectPublicKeyInfoRef::try_from(der)?;
+        Ok(self.decode(&crate::encoded_verifying_key::<P, X>(&spki)?))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CacheState<P, X>> {
+        // The state is consistent between statements, so a panic elsewhere cannot corrupt it
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<P: MlDsaParams, X: Xof> CacheState<P, X> {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn get(&mut self, enc: &EncodedVerifyingKey<P>) -> Option<Arc<VerifyingKey<P, X>>> {
+        let now = self.tick();
+        let entry = self.entries.get_mut(enc)?;
+        entry.last_used = now;
+        Some(entry.key.clone())
+    }
+
+    fn evict(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(enc, _)| enc.clone());
+
+        if let Some(enc) = oldest {
+            self.entries.remove(&enc);
+        }
+    }
+}
+
+impl<P: MlDsaParams, X: Xof> core::fmt::Debug for VerifyingKeyCache<P, X> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("VerifyingKeyCache")
+            .field("capacity", &self.capacity)
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{KeyPair, MlDsa44};
+    use hybrid_array::Array;
+
+    fn encoded(seed: u8) -> EncodedVerifyingKey<MlDsa44> {
+        KeyPair::<MlDsa44>::from_seed(&Array([seed; 32]))
+            .verifying_key()
+            .encode()
+    }
+
+    #[test]
+    fn keys_are_shared() {
+        let cache = VerifyingKeyCache::<MlDsa44>::new(2);
+        let kp = KeyPair::<MlDsa44>::from_seed(&Array([1; 32]));
+        let sig = kp.signing_key().sign_deterministic(b"msg", &[]).unwrap();
+        let enc = kp.verifying_key().encode();
+
+        // Decoding does not expand the key
+        let vk = cache.decode(&enc);
+        assert!(!vk.expanded.is_ready());
+        assert!(vk.verify_with_context(b"msg", &[], &sig));
+        assert!(vk.expanded.is_ready());
+
+        // Later decodings share the expanded key
+        let again = cache.decode(&enc);
+        assert!(Arc::ptr_eq(&vk, &again));
+        assert!(again.expanded.is_ready());
+        assert_eq!(*vk, *kp.verifying_key());
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn least_recently_used_is_evicted() {
+        let cache = VerifyingKeyCache::<MlDsa44>::new(2);
+        let (a, b, c) = (encoded(1), encoded(2), encoded(3));
+
+        let vk_a = cache.decode(&a);
+        let vk_b = cache.decode(&b);
+        assert!(Arc::ptr_eq(&vk_a, &cache.decode(&a)));
+
+        // `b` is now the least recently used
+        let _ = cache.decode(&c);
+        assert_eq!(cache.len(), 2);
+        assert!(Arc::ptr_eq(&vk_a, &cache.decode(&a)));
+        assert!(!Arc::ptr_eq(&vk_b, &cache.decode(&b)));
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[cfg(all(feature = "pkcs8", feature = "alloc"))]
+    #[test]
+    fn public_key_der() {
+        use crate::MlDsa65;
+        use pkcs8::EncodePublicKey;
+
+        let cache = VerifyingKeyCache::<MlDsa44>::new(2);
+        let vk = KeyPair::<MlDsa44>::from_seed(&Array([1; 32]))
+            .verifying_key()
+            .clone();
+        let der = vk.to_public_key_der().unwrap();
+
+        let cached = cache.decode_public_key_der(der.as_bytes()).unwrap();
+        assert_eq!(*cached, vk);
+        assert!(Arc::ptr_eq(&cached, &cache.decode(&vk.encode())));
+
+        // The parameter set must match
+        let other = VerifyingKeyCache::<MlDsa65>::new(2);
+        assert!(other.decode_public_key_der(der.as_bytes()).is_err());
+        assert!(other.is_empty());
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let cache = VerifyingKeyCache::<MlDsa44>::new(0);
+        let enc = encoded(1);
+        assert!(!Arc::ptr_eq(&cache.decode(&enc), &cache.decode(&enc)));
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,532 @@
+use core::fmt;
+
+use hybrid_array::Array;
+use signature::{Error, MultipartSigner};
+
+use crate::algebra::{AlgebraExt, Elem, NttPolynomial, Polynomial};
+use crate::crypto::ShakeXof;
+use crate::hint::Hint;
+use crate::module_lattice::encode::{Encode, EncodedPolynomial};
+use crate::module_lattice::util::Truncate;
+use crate::ntt::{Ntt, NttInverse};
+use crate::observe::PhaseTimer;
+use crate::param::{BitlenQMinusD, MaskSamplingSize, MlDsaParams, ParameterSet, Validate};
+use crate::sampling::{rej_ntt_poly, sample_in_ball};
+use crate::util::{B32, B64};
+use crate::xof::{Xof, XofState};
+use crate::{
+    EncodedSignature, EncodedVerifyingKey, KeyPair, Phase, Prehash, Signature, SigningError,
+    SigningKey, VerifyingKey,
+};
+
+#[cfg(feature = "rand_core")]
+use rand_core::TryCryptoRng;
+
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[cfg(feature = "pkcs8")]
+use {
+    crate::private_key::PrivateKeyChoice,
+    pkcs8::{
+        PrivateKeyInfoRef,
+        der::AnyRef,
+        spki::{AlgorithmIdentifier, AssociatedAlgorithmIdentifier, SignatureAlgorithmIdentifier},
+    },
+};
+
+#[cfg(all(feature = "alloc", feature = "pkcs8"))]
+use pkcs8::{EncodePrivateKey, der};
+
+/// An ML-DSA signing key that holds only its 32-byte seed ξ.
+///
+/// A [`SigningKey`] keeps its secret vectors both as they are and in NTT form, along with the
+/// whole matrix `A_hat`, which comes to more than 100 KiB for [`MlDsa87`](crate::MlDsa87).  A
+/// `CompactSigningKey` instead runs key generation from the seed each time it signs.  This costs
+/// roughly one key generation per signature, in exchange for keeping thousands of keys in memory.
+/// When a key is about to sign several messages, [`CompactSigningKey::expand`] it once instead.
+/// Signing with an expanded key is also the way to set a
+/// [rejection budget](SigningKey::with_rejection_budget), which a compact key does not have room
+/// for.
+///
+/// Keys that do not have a seed can be kept as an [`EncodedSigningKey`](crate::EncodedSigningKey)
+/// and decoded with [`SigningKey::decode`] when needed.
+///
+/// ```
+/// use ml_dsa::{CompactSigningKey, MlDsa87};
+///
+/// let sk = CompactSigningKey::<MlDsa87>::from_seed(&Default::default());
+/// let sig = sk.sign_deterministic(b"Hello world", &[]).unwrap();
+/// assert!(sk.verifying_key().verify_with_context(b"Hello world", &[], &sig));
+/// ```
+#[derive(Clone)]
+pub struct CompactSigningKey<P: MlDsaParams, X: Xof = ShakeXof> {
+    seed: B32,
+    xof: X,
+    _params: core::marker::PhantomData<P>,
+}
+
+impl<P: MlDsaParams, X: Xof> fmt::Debug for CompactSigningKey<P, X> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompactSigningKey").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<P: MlDsaParams, X: Xof> Drop for CompactSigningKey<P, X> {
+    fn drop(&mut self) {
+        self.seed.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<P: MlDsaParams, X: Xof> ZeroizeOnDrop for CompactSigningKey<P, X> {}
+
+impl<P: MlDsaParams, X: Xof> CompactSigningKey<P, X> {
+    /// The signing key generated from the seed `xi`, as in [`KeyPair::from_seed`]
+    #[must_use]
+    pub fn from_seed(xi: &B32) -> Self {
+        Self {
+            seed: xi.clone(),
+            xof: X::default(),
+            _params: core::marker::PhantomData,
+        }
+    }
+
+    /// Use the specified XOF configuration for all further operations with this key.
+    #[must_use]
+    pub fn with_xof(mut self, xof: X) -> Self {
+        self.xof = xof;
+        self
+    }
+
+    /// The XOF configuration used by this key
+    pub fn xof(&self) -> &X {
+        &self.xof
+    }
+
+    /// Generate the full key pair from the seed
+    pub fn key_pair(&self) -> KeyPair<P, X> {
+        KeyPair::from_seed_with_xof(&self.seed, self.xof.clone())
+    }
+
+    /// Generate the full signing key from the seed
+    pub fn expand(&self) -> SigningKey<P, X> {
+        self.key_pair().signing_key
+    }
+
+    /// Generate the verifying key from the seed
+    pub fn verifying_key(&self) -> VerifyingKey<P, X> {
+        self.key_pair().verifying_key
+    }
+
+    /// Expand the key and sign with [`SigningKey::sign_randomized`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return [`SigningError::ContextTooLong`] if the context string is more
+    /// than 255 bytes long, and [`SigningError::RngFailure`] if it fails to get enough randomness.
+    #[cfg(feature = "rand_core")]
+    pub fn sign_randomized<R: TryCryptoRng + ?Sized>(
+        &self,
+        M: &[u8],
+        ctx: &[u8],
+        rng: &mut R,
+    ) -> Result<Signature<P>, SigningError> {
+        self.expand().sign_randomized(M, ctx, rng)
+    }
+
+    /// Expand the key and sign with [`SigningKey::sign_deterministic`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return [`SigningError::ContextTooLong`] if the context string is more
+    /// than 255 bytes long.
+    pub fn sign_deterministic(&self, M: &[u8], ctx: &[u8]) -> Result<Signature<P>, SigningError> {
+        self.expand().sign_deterministic(M, ctx)
+    }
+
+    /// Expand the key and sign with [`SigningKey::sign_mu_deterministic`].
+    ///
+    /// # Errors
+    ///
+    /// This method only fails with [`SigningError::RejectionBudgetExceeded`], when the counter of
+    /// the mask runs out, which does not happen in practice.
+    pub fn sign_mu_deterministic(&self, mu: &B64) -> Result<Signature<P>, SigningError> {
+        self.expand().sign_mu_deterministic(mu)
+    }
+}
+
+/// The `Signer` implementation for `CompactSigningKey` uses the optional deterministic variant of
+/// ML-DSA, and only supports signing with an empty context string.
+impl<P: MlDsaParams, X: Xof> signature::Signer<Signature<P>> for CompactSigningKey<P, X> {
+    fn try_sign(&self, msg: &[u8]) -> Result<Signature<P>, Error> {
+        self.try_multipart_sign(&[msg])
+    }
+}
+
+/// The `MultipartSigner` implementation for `CompactSigningKey` uses the optional deterministic
+/// variant of ML-DSA, and only supports signing with an empty context string.
+impl<P: MlDsaParams, X: Xof> MultipartSigner<Signature<P>> for CompactSigningKey<P, X> {
+    fn try_multipart_sign(&self, msg: &[&[u8]]) -> Result<Signature<P>, Error> {
+        self.expand().try_multipart_sign(msg)
+    }
+}
+
+/// The `RandomizedSigner` implementation for `CompactSigningKey` only supports signing with an
+/// empty context string.
+#[cfg(feature = "rand_core")]
+impl<P: MlDsaParams, X: Xof> signature::RandomizedSigner<Signature<P>> for CompactSigningKey<P, X> {
+    fn try_sign_with_rng<R: TryCryptoRng + ?Sized>(
+        &self,
+        rng: &mut R,
+        msg: &[u8],
+    ) -> Result<Signature<P>, Error> {
+        Ok(self.sign_randomized(msg, &[], rng)?)
+    }
+}
+
+impl<P: MlDsaParams, X: Xof> signature::Keypair for CompactSigningKey<P, X> {
+    type VerifyingKey = VerifyingKey<P, X>;
+
+    fn verifying_key(&self) -> Self::VerifyingKey {
+        CompactSigningKey::verifying_key(self)
+    }
+}
+
+#[cfg(feature = "pkcs8")]
+impl<P, X> SignatureAlgorithmIdentifier for CompactSigningKey<P, X>
+where
+    P: MlDsaParams,
+    P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
+{
+    type Params = AnyRef<'static>;
+
+    const SIGNATURE_ALGORITHM_IDENTIFIER: AlgorithmIdentifier<Self::Params> =
+        crate::algorithm_identifier::<P, X>();
+}
+
+/// Only private keys with a seed can be decoded as a `CompactSigningKey`.  A key in the `both` form
+/// is expanded once, to check that its two halves match.
+#[cfg(feature = "pkcs8")]
+impl<P, X> TryFrom<PrivateKeyInfoRef<'_>> for CompactSigningKey<P, X>
+where
+    P: MlDsaParams,
+    P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
+{
+    type Error = pkcs8::Error;
+
+    fn try_from(private_key_info: PrivateKeyInfoRef<'_>) -> pkcs8::Result<Self> {
+        crate::check_algorithm::<P, X>(private_key_info.algorithm)?;
+
+        match PrivateKeyChoice::from_der(private_key_info.private_key.as_bytes())? {
+            PrivateKeyChoice::Seed(seed) => {
+                let seed = Array::try_from(seed).map_err(|_| pkcs8::Error::KeyMalformed)?;
+                Ok(Self::from_seed(&seed))
+            }
+            PrivateKeyChoice::Both { .. } => {
+                let kp = KeyPair::<P, X>::try_from(private_key_info)?;
+                Ok(Self::from_seed(&kp.seed))
+            }
+            PrivateKeyChoice::ExpandedKey(_) => Err(pkcs8::Error::KeyMalformed),
+        }
+    }
+}
+
+/// The `EncodePrivateKey` implementation for `CompactSigningKey` uses the `seed` form.
+#[cfg(all(feature = "alloc", feature = "pkcs8"))]
+impl<P, X> EncodePrivateKey for CompactSigningKey<P, X>
+where
+    P: MlDsaParams,
+    P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
+{
+    fn to_pkcs8_der(&self) -> pkcs8::Result<der::SecretDocument> {
+        crate::encode_private_key::<P, X>(&PrivateKeyChoice::Seed(&self.seed))
+    }
+}
+
+/// An ML-DSA verifying key that verifies encoded signatures with only a few polynomials in memory.
+///
+/// A [`VerifyingKey`] holds the whole matrix `A_hat` in NTT form, and a decoded [`Signature`]
+/// holds the vector `z` and the hint, which together need tens of KiB for
+/// [`MlDsa87`](crate::MlDsa87).  A `CompactVerifyingKey` keeps the key in its encoded form and
+/// works on an [`EncodedSignature`] directly.  It generates each row of `A_hat` from ρ, one entry
+/// at a time, accumulates that row of `A_hat·z − c·t1·2^d` and absorbs the corresponding row of
+/// `w1'` into the hash before moving on to the next row.  The columns of `z` and the rows of `t1`
+/// and the hint are decoded from the encodings as they are needed.
+///
+/// The results are the same as those of [`Signature::decode`] followed by the matching method of
+/// [`VerifyingKey`].  In exchange for the memory, each signature costs one sampling of the matrix
+/// and `K·L` forward NTTs of the columns of `z`, where a cached `VerifyingKey` does neither.
+///
+/// Verification needs no allocation, and its stack usage is about the same for every parameter
+/// set, since the largest values are single polynomials.  The figures below are for
+/// `x86_64-unknown-linux-gnu` with the release profile and rustc 1.95: they sum the frame sizes,
+/// as read from `objdump -d`, along the deepest static call path of `verify_with_context`.  They
+/// vary with the compiler and the target, and `tests/stack.rs` only checks that verification fits
+/// in a 16 KiB thread stack.
+///
+/// | Parameter set               | Key      | Verification | `VerifyingKey`, for comparison |
+/// |-----------------------------|----------|--------------|--------------------------------|
+/// | [`MlDsa44`](crate::MlDsa44) | 1376 B   | 3.5 KiB      | 53 KiB                         |
+/// | [`MlDsa65`](crate::MlDsa65) | 2016 B   | 3.5 KiB      | 120 KiB                        |
+/// | [`MlDsa87`](crate::MlDsa87) | 2656 B   | 3.5 KiB      | 209 KiB                        |
+///
+/// The key is an [`EncodedVerifyingKey`] and the 64-byte hash `tr`.  The last column is a
+/// [`VerifyingKey`] decoded on the stack and used once, matrix included.
+///
+/// ```
+/// use ml_dsa::{CompactVerifyingKey, KeyPair, MlDsa87};
+///
+/// let kp = KeyPair::<MlDsa87>::from_seed(&Default::default());
+/// let sig = kp.signing_key().sign_deterministic(b"Hello world", &[]).unwrap();
+///
+/// let vk = CompactVerifyingKey::<MlDsa87>::decode(&kp.verifying_key().encode());
+/// assert!(vk.verify_with_context(b"Hello world", &[], &sig.encode()));
+/// ```
+#[derive(Clone)]
+pub struct CompactVerifyingKey<P: MlDsaParams, X: Xof = ShakeXof> {
+    encoded: EncodedVerifyingKey<P>,
+    tr: B64,
+    xof: X,
+}
+
+impl<P: MlDsaParams, X: Xof> fmt::Debug for CompactVerifyingKey<P, X> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompactVerifyingKey")
+            .field("encoded", &self.encoded)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P: MlDsaParams, X: Xof> PartialEq for CompactVerifyingKey<P, X> {
+    fn eq(&self, other: &Self) -> bool {
+        self.encoded == other.encoded
+    }
+}
+
+impl<P: MlDsaParams, X: Xof> CompactVerifyingKey<P, X> {
+    /// Decode the key from an appropriately sized byte array.
+    ///
+    /// Any byte array is a valid encoding, so this only copies the key and computes its hash.
+    pub fn decode(enc: &EncodedVerifyingKey<P>) -> Self {
+        let () = P::VALID;
+        let xof = X::default();
+        let tr = xof.h().absorb(enc).squeeze_new();
+
+        Self {
+            encoded: enc.clone(),
+            tr,
+            xof,
+        }
+    }
+
+    /// Encode the key in a fixed-size byte array.
+    pub fn encode(&self) -> EncodedVerifyingKey<P> {
+        self.encoded.clone()
+    }
+
+    /// Use the specified XOF configuration for all further operations with this key.
+    #[must_use]
+    pub fn with_xof(mut self, xof: X) -> Self {
+        self.xof = xof;
+        self
+    }
+
+    /// The XOF configuration used by this key
+    pub fn xof(&self) -> &X {
+        &self.xof
+    }
+
+    /// Decode the full verifying key, with the matrix `A_hat`
+    pub fn expand(&self) -> VerifyingKey<P, X> {
+        VerifyingKey::decode(&self.encoded).with_xof(self.xof.clone())
+    }
+
+    /// Verify like [`VerifyingKey::verify_internal`].
+    pub fn verify_internal(&self, Mp: &[&[u8]], sigma: &EncodedSignature<P>) -> bool {
+        self.raw_verify_internal(&[Mp], sigma)
+    }
+
+    /// Verify like [`VerifyingKey::verify_with_context`].
+    pub fn verify_with_context(&self, M: &[u8], ctx: &[u8], sigma: &EncodedSignature<P>) -> bool {
+        if ctx.len() > 255 {
+            return false;
+        }
+
+        let Mp: &[&[&[u8]]] = &[&[&[0], &[Truncate::truncate(ctx.len())], ctx, M]];
+        self.raw_verify_internal(Mp, sigma)
+    }
+
+    /// Verify like [`VerifyingKey::verify_mu`].
+    pub fn verify_mu(&self, mu: &B64, sigma: &EncodedSignature<P>) -> bool {
+        let _timer = PhaseTimer::start(&self.xof, Phase::Verify);
+        verify_mu_streaming::<P, X>(&self.xof, &self.encoded, mu, sigma)
+    }
+
+    /// Verify like [`VerifyingKey::verify_prehash_with_context`].
+    pub fn verify_prehash_with_context<PH: Prehash>(
+        &self,
+        digest: &[u8],
+        ctx: &[u8],
+        sigma: &EncodedSignature<P>,
+    ) -> bool {
+        use hybrid_array::typenum::Unsigned;
+
+        if ctx.len() > 255 || digest.len() != PH::OutputSize::USIZE {
+            return false;
+        }
+
+        let Mp: &[&[&[u8]]] = &[&[&[1], &[Truncate::truncate(ctx.len())], ctx, PH::OID, digest]];
+        self.raw_verify_internal(Mp, sigma)
+    }
+
+    fn raw_verify_internal(&self, Mp: &[&[&[u8]]], sigma: &EncodedSignature<P>) -> bool {
+        let mu = crate::message_representative(&self.xof, &self.tr, Mp);
+        self.verify_mu(&mu, sigma)
+    }
+}
+
+impl<P: MlDsaParams, X: Xof> From<&VerifyingKey<P, X>> for CompactVerifyingKey<P, X> {
+    fn from(vk: &VerifyingKey<P, X>) -> Self {
+        Self::decode(&vk.encode()).with_xof(vk.xof().clone())
+    }
+}
+
+// The checks of Algorithm 27 sigDecode and lines 7-13 of Algorithm 8 ML-DSA.Verify_internal, as
+// `verify_mu` does them, but on the encodings and one row of `A_hat` at a time.  Between rows, the
+// only state is the hash of the rows of `w1'` so far.
+fn verify_mu_streaming<P: MlDsaParams, X: Xof>(
+    xof: &X,
+    vk: &EncodedVerifyingKey<P>,
+    mu: &B64,
+    sigma: &EncodedSignature<P>,
+) -> bool {
+    type Gamma1Sample<P> = Array<u8, <<P as ParameterSet>::Gamma1 as MaskSamplingSize>::SampleSize>;
+
+    let (rho, t1_enc) = P::split_vk(vk);
+    let (c_tilde, z_enc, h_enc) = P::split_sig(sigma);
+    let (z_enc, _) = Gamma1Sample::<P>::slice_as_chunks(z_enc);
+    let (t1_enc, _) = EncodedPolynomial::<BitlenQMinusD>::slice_as_chunks(t1_enc);
+
+    if !Hint::<P>::is_valid(h_enc)
+        || z_enc
+            .iter()
+            .any(|z| P::Gamma1::unpack(z).infinity_norm() >= P::GAMMA1_MINUS_BETA)
+    {
+        return false;
+    }
+
+    let c_hat = sample_in_ball(xof, c_tilde, P::TAU).ntt();
+    let mut h = xof.h().absorb(mu);
+
+    for (i, t1) in t1_enc.iter().enumerate() {
+        let r = Truncate::truncate(i);
+        let mut Az_hat = NttPolynomial::default();
+        for (j, z) in z_enc.iter().enumerate() {
+            let a_hat = rej_ntt_poly(xof, rho, r, Truncate::truncate(j));
+            Az_hat = &Az_hat + &(&a_hat * &P::Gamma1::unpack(z).ntt());
+        }
+
+        let t1: Polynomial = Encode::<BitlenQMinusD>::decode(t1);
+        let ct1_2d_hat = &c_hat * &(Elem::new(1 << 13) * &t1).ntt();
+
+        let wp_approx = (&Az_hat - &ct1_2d_hat).ntt_inverse();
+        let w1p = Hint::<P>::use_hint_row(&Hint::<P>::unpack_row(h_enc, i), &wp_approx);
+        h = h.absorb(&Encode::<P::W1Bits>::encode(&w1p));
+    }
+
+    *c_tilde == h.squeeze_new::<P::Lambda>()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AesXof, Blake3Xof, MlDsa44, MlDsa65, MlDsa87};
+    use hybrid_array::typenum::Unsigned;
+
+    fn check_compact<P: MlDsaParams, X: Xof>() {
+        let seed = Array([9u8; 32]);
+        let kp = KeyPair::<P, X>::from_seed(&seed);
+        let sk = CompactSigningKey::<P, X>::from_seed(&seed);
+
+        assert!(sk.expand() == *kp.signing_key());
+        assert_eq!(sk.verifying_key(), *kp.verifying_key());
+
+        let msg = b"compact";
+        assert_eq!(
+            sk.sign_deterministic(msg, b"ctx").unwrap(),
+            kp.signing_key().sign_deterministic(msg, b"ctx").unwrap()
+        );
+        let sig = signature::Signer::sign(&sk, msg);
+        assert!(kp.verifying_key().verify_with_context(msg, &[], &sig));
+    }
+
+    #[test]
+    fn compact_matches_expanded() {
+        check_compact::<MlDsa44, ShakeXof>();
+        check_compact::<MlDsa87, AesXof>();
+    }
+
+    fn check_streaming<P: MlDsaParams, X: Xof>() {
+        let kp = KeyPair::<P, X>::from_seed(&Array([7u8; 32]));
+        let vk = kp.verifying_key();
+        let compact = CompactVerifyingKey::from(vk);
+        assert_eq!(compact, CompactVerifyingKey::decode(&vk.encode()));
+        assert_eq!(compact.expand(), *vk);
+
+        let msg = b"streaming";
+        let sig = kp.signing_key().sign_deterministic(msg, b"ctx").unwrap();
+        let enc = sig.encode();
+        assert!(compact.verify_with_context(msg, b"ctx", &enc));
+        assert!(!compact.verify_with_context(msg, b"xtc", &enc));
+        assert!(!compact.verify_with_context(msg, &[0; 256], &enc));
+
+        let mu = crate::message_representative(vk.xof(), vk.tr(), &[&[&[0, 3], b"ctx", msg]]);
+        assert!(compact.verify_mu(&mu, &enc));
+        assert!(compact.verify_internal(&[&[0, 3], b"ctx", msg], &enc));
+
+        // Flip bits throughout the signature, in `c_tilde`, `z` and the hint, and check that both
+        // verifiers agree on each of the results, including those that do not decode
+        let expected = |enc: &EncodedSignature<P>| {
+            Signature::<P>::decode(enc).is_some_and(|sig| vk.verify_with_context(msg, b"ctx", &sig))
+        };
+        let (mut valid, mut invalid) = (0, 0);
+        for i in (0..enc.len())
+            .step_by(7)
+            .chain(enc.len() - P::Omega::USIZE - 8..enc.len())
+        {
+            for bit in [0x01, 0x80] {
+                let mut tampered = enc.clone();
+                tampered[i] ^= bit;
+                let result = compact.verify_with_context(msg, b"ctx", &tampered);
+                assert_eq!(result, expected(&tampered), "byte {i} bit {bit:#x}");
+                if result {
+                    valid += 1;
+                } else {
+                    invalid += 1;
+                }
+            }
+        }
+        assert!(invalid > 0);
+        assert_eq!(valid, 0);
+    }
+
+    #[test]
+    fn streaming_matches_expanded() {
+        check_streaming::<MlDsa44, ShakeXof>();
+        check_streaming::<MlDsa65, ShakeXof>();
+        check_streaming::<MlDsa87, ShakeXof>();
+        check_streaming::<MlDsa65, Blake3Xof>();
+        check_streaming::<MlDsa87, AesXof>();
+    }
+
+    #[test]
+    fn compact_is_small() {
+        assert_eq!(size_of::<CompactSigningKey<MlDsa87>>(), 32);
+        assert!(size_of::<SigningKey<MlDsa87>>() > 100 * 1024);
+    }
+}
@@ -0,0 +1,324 @@
+//! ML-DSA keys and signatures in COSE, as specified by the IETF draft "ML-DSA for JOSE and COSE"
+//! (draft-ietf-cose-dilithium).
+//!
+//! The draft assigns the algorithms ML-DSA-44, ML-DSA-65 and ML-DSA-87 the identifiers -48, -49
+//! and -50, and defines the Algorithm Key Pair key type, `AKP` (7), for their keys.  A `COSE_Key`
+//! of this type has the parameters
+//!
+//! | Label | Name   | Value                                   |
+//! |-------|--------|-----------------------------------------|
+//! | 1     | `kty`  | 7                                       |
+//! | 3     | `alg`  | the algorithm of the key                |
+//! | -1    | `pub`  | the encoded verifying key, as bytes     |
+//! | -2    | `priv` | the seed ξ, as bytes, for a private key |
+//!
+//! A `COSE_Sign1` signature is the ML-DSA signature of the `Sig_structure` of the message, with
+//! an empty context string.  The messages made here are tagged, and have a protected header that
+//! names the algorithm and nothing else, an empty unprotected header and an attached payload.
+//! Verification accepts messages with or without the tag, and rejects those with a detached
+//! payload or a `crit` header parameter, since no extensions are understood.  The external
+//! additional authenticated data is always empty.
+//!
+//! The draft only covers standard ML-DSA, so these conversions are only available for keys with
+//! the [`ShakeXof`](crate::ShakeXof) backend.
+//!
+//! ```
+//! use ml_dsa::{KeyPair, MlDsa65, VerifyingKey};
+//!
+//! let kp = KeyPair::<MlDsa65>::from_seed(&[7; 32].into());
+//! let msg = kp.signing_key().sign_cose_sign1_deterministic(b"Hello world").unwrap();
+//!
+//! let vk = VerifyingKey::<MlDsa65>::from_cose_key(&kp.verifying_key().to_cose_key()).unwrap();
+//! assert_eq!(vk.verify_cose_sign1(&msg).unwrap(), b"Hello world");
+//! ```
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use ciborium::Value;
+use signature::Error;
+
+#[cfg(feature = "rand_core")]
+use rand_core::TryCryptoRng;
+
+use crate::param::{EncodedSignature, EncodedVerifyingKey, MlDsaParams};
+use crate::secret::{Secret, wipe};
+use crate::util::B32;
+use crate::{
+    KeyPair, MlDsa44, MlDsa65, MlDsa87, Signature, SigningError, SigningKey, VerifyingKey,
+};
+
+/// The key type of ML-DSA keys, for the `kty` parameter of a `COSE_Key`
+pub const KEY_TYPE: i64 = 7;
+
+/// The CBOR tag of a `COSE_Sign1` message
+pub const SIGN1_TAG: u64 = 18;
+
+// The labels of the key parameters
+const KTY: i64 = 1;
+const ALG: i64 = 3;
+const PUB: i64 = -1;
+const PRIV: i64 = -2;
+
+// The labels of the header parameters
+const HEADER_ALG: i64 = 1;
+const HEADER_CRIT: i64 = 2;
+
+/// A parameter set with a COSE algorithm identifier
+pub trait CoseAlgorithm: MlDsaParams {
+    /// The identifier of the algorithm, for the `alg` parameter of keys and headers
+    const ALGORITHM: i64;
+}
+
+impl CoseAlgorithm for MlDsa44 {
+    const ALGORITHM: i64 = -48;
+}
+
+impl CoseAlgorithm for MlDsa65 {
+    const ALGORITHM: i64 = -49;
+}
+
+impl CoseAlgorithm for MlDsa87 {
+    const ALGORITHM: i64 = -50;
+}
+
+impl<P: CoseAlgorithm> VerifyingKey<P> {
+    /// Encode the key as a `COSE_Key`
+    #[must_use]
+    pub fn to_cose_key(&self) -> Vec<u8> {
+        to_cose_key::<P>(&self.encode(), None)
+    }
+
+    /// Decode a key from a `COSE_Key`.  A `priv` parameter is ignored.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an opaque error if the `COSE_Key` is not an `AKP` key of this
+    /// algorithm.
+    pub fn from_cose_key(key: &[u8]) -> Result<Self, Error> {
+        let key = parse_cose_key::<P>(key)?;
+        Ok(Self::decode(&verifying_key::<P>(&key)?))
+    }
+
+    /// Verify a `COSE_Sign1` message, and return its payload.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an opaque error if the message is malformed, if its protected
+    /// header names another algorithm or has a `crit` parameter, if its payload is detached, or if
+    /// the signature is not valid.
+    pub fn verify_cose_sign1(&self, msg: &[u8]) -> Result<Vec<u8>, Error> {
+        let msg = match decode(msg)? {
+            Value::Tag(SIGN1_TAG, msg) => *msg,
+            Value::Tag(..) => return Err(Error::new()),
+            msg => msg,
+        };
+
+        let Value::Array(msg) = msg else {
+            return Err(Error::new());
+        };
+        let [
+            Value::Bytes(protected),
+            Value::Map(unprotected),
+            Value::Bytes(payload),
+            Value::Bytes(signature),
+        ] = <[Value; 4]>::try_from(msg).map_err(|_| Error::new())?
+        else {
+            return Err(Error::new());
+        };
+
+        let Value::Map(header) = decode(&protected)? else {
+            return Err(Error::new());
+        };
+        check_labels(&header)?;
+        if parameter(&header, HEADER_ALG) != Some(&P::ALGORITHM.into())
+            || parameter(&header, HEADER_CRIT).is_some()
+            || parameter(&unprotected, HEADER_ALG).is_some()
+        {
+            return Err(Error::new());
+        }
+
+        let signature =
+            EncodedSignature::<P>::try_from(signature.as_slice()).map_err(|_| Error::new())?;
+        let signature = Signature::decode(&signature).ok_or_else(Error::new)?;
+
+        if !self.verify_with_context(&sig_structure(&protected, &payload), &[], &signature) {
+            return Err(Error::new());
+        }
+
+        Ok(payload)
+    }
+}
+
+impl<P: CoseAlgorithm> KeyPair<P> {
+    /// Encode the key pair as a `COSE_Key`, with its seed as `priv`.  The encoding is as secret as
+    /// the key.
+    #[must_use]
+    pub fn to_cose_key(&self) -> Vec<u8> {
+        to_cose_key::<P>(&self.verifying_key.encode(), Some(&self.seed))
+    }
+
+    /// Decode a key pair from a `COSE_Key` with a `priv` parameter.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an opaque error if the `COSE_Key` is not a private `AKP` key of
+    /// this algorithm, or if its `pub` parameter is not the verifying key of its seed.
+    pub fn from_cose_key(key: &[u8]) -> Result<Self, Error> {
+        let mut key = parse_cose_key::<P>(key)?;
+        let priv_label = Value::from(PRIV);
+        let Some((_, Value::Bytes(seed))) = key.iter_mut().find(|(l, _)| *l == priv_label) else {
+            return Err(Error::new());
+        };
+        let seed = Secret::new(core::mem::take(seed));
+        let seed = Secret::new(B32::try_from(seed.as_slice()).map_err(|_| Error::new())?);
+
+        let kp = Self::from_seed(&seed);
+        if verifying_key::<P>(&key)? != kp.verifying_key.encode() {
+            return Err(Error::new());
+        }
+
+        Ok(kp)
+    }
+}
+
+impl<P: CoseAlgorithm> SigningKey<P> {
+    /// Sign `payload` as a tagged `COSE_Sign1` message, with the deterministic variant of ML-DSA.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the [rejection budget](SigningKey::with_rejection_budget) runs out,
+    /// or if [fault checks](SigningKey::with_fault_checks) reject the signature.
+    pub fn sign_cose_sign1_deterministic(&self, payload: &[u8]) -> Result<Vec<u8>, SigningError> {
+        let protected = protected_header::<P>();
+        let signature = self.sign_deterministic(&sig_structure(&protected, payload), &[])?;
+        Ok(sign1(protected, payload, &signature))
+    }
+
+    /// Sign `payload` as a tagged `COSE_Sign1` message, with the hedged variant of ML-DSA.
+    ///
+    /// # Errors
+    ///
+    /// This method will return [`SigningError::RngFailure`] if it fails to get enough randomness.
+    /// It also fails if the [rejection budget](SigningKey::with_rejection_budget) runs out, or if
+    /// [fault checks](SigningKey::with_fault_checks) reject the signature.
+    #[cfg(feature = "rand_core")]
+    pub fn sign_cose_sign1_randomized<R: TryCryptoRng + ?Sized>(
+        &self,
+        payload: &[u8],
+        rng: &mut R,
+    ) -> Result<Vec<u8>, SigningError> {
+        let protected = protected_header::<P>();
+        let signature = self.sign_randomized(&sig_structure(&protected, payload), &[], rng)?;
+        Ok(sign1(protected, payload, &signature))
+    }
+}
+
+fn to_cose_key<P: CoseAlgorithm>(vk: &EncodedVerifyingKey<P>, seed: Option<&B32>) -> Vec<u8> {
+    // In the order of deterministic encoding
+    let mut key = vec![
+        (KTY.into(), KEY_TYPE.into()),
+        (ALG.into(), P::ALGORITHM.into()),
+        (PUB.into(), Value::Bytes(vk.to_vec())),
+    ];
+    if let Some(seed) = seed {
+        key.push((PRIV.into(), Value::Bytes(seed.to_vec())));
+    }
+
+    // The encoding is as long as the key and seed, with a few bytes of structure, so that it is
+    // written without reallocating and leaving copies of the seed behind
+    let mut key = Value::Map(key);
+    let mut encoded = Vec::with_capacity(vk.len() + B32::default().len() + 32);
+    ciborium::into_writer(&key, &mut encoded).expect("writing to a Vec cannot fail");
+    if let Value::Map(key) = &mut key {
+        // The copy of the seed, if any, is the last parameter
+        if let Some((_, Value::Bytes(seed))) = key.get_mut(3) {
+            wipe(seed);
+        }
+    }
+    encoded
+}
+
+// Parse a `COSE_Key`, and check that it is an `AKP` key of the algorithm `P`
+fn parse_cose_key<P: CoseAlgorithm>(key: &[u8]) -> Result<Vec<(Value, Value)>, Error> {
+    let Value::Map(key) = decode(key)? else {
+        return Err(Error::new());
+    };
+    check_labels(&key)?;
+    if parameter(&key, KTY) != Some(&KEY_TYPE.into())
+        || parameter(&key, ALG) != Some(&P::ALGORITHM.into())
+    {
+        return Err(Error::new());
+    }
+
+    Ok(key)
+}
+
+fn verifying_key<P: MlDsaParams>(key: &[(Value, Value)]) -> Result<EncodedVerifyingKey<P>, Error> {
+    let Some(Value::Bytes(vk)) = parameter(key, PUB) else {
+        return Err(Error::new());
+    };
+    EncodedVerifyingKey::<P>::try_from(vk.as_slice()).map_err(|_| Error::new())
+}
+
+// The value of the parameter `label` of a map
+fn parameter(map: &[(Value, Value)], label: i64) -> Option<&Value> {
+    let label = Value::from(label);
+    map.iter()
+        .find(|(l, _)| *l == label)
+        .map(|(_, value)| value)
+}
+
+// COSE maps have integer or text labels, each at most once
+fn check_labels(map: &[(Value, Value)]) -> Result<(), Error> {
+    for (i, (label, _)) in map.iter().enumerate() {
+        if !(label.is_integer() || label.is_text()) || map[..i].iter().any(|(l, _)| l == label) {
+            return Err(Error::new());
+        }
+    }
+
+    Ok(())
+}
+
+fn protected_header<P: CoseAlgorithm>() -> Vec<u8> {
+    encode(&Value::Map(vec![(HEADER_ALG.into(), P::ALGORITHM.into())]))
+}
+
+// The `Sig_structure` of a `COSE_Sign1` message, which is what gets signed
+fn sig_structure(protected: &[u8], payload: &[u8]) -> Vec<u8> {
+    encode(&Value::Array(vec![
+        Value::Text("Signature1".into()),
+        Value::Bytes(protected.to_vec()),
+        Value::Bytes(Vec::new()),
+        Value::Bytes(payload.to_vec()),
+    ]))
+}
+
+fn sign1<P: MlDsaParams>(protected: Vec<u8>, payload: &[u8], signature: &Signature<P>) -> Vec<u8> {
+    encode(&Value::Tag(
+        SIGN1_TAG,
+        Box::new(Value::Array(vec![
+            Value::Bytes(protected),
+            Value::Map(Vec::new()),
+            Value::Bytes(payload.to_vec()),
+            Value::Bytes(signature.encode().to_vec()),
+        ])),
+    ))
+}
+
+fn encode(value: &Value) -> Vec<u8> {
+    let mut encoded = Vec::new();
+    ciborium::into_writer(value, &mut encoded).expect("writing to a Vec cannot fail");
+    encoded
+}
+
+// Decode a single CBOR item, with nothing after it
+fn decode(mut bytes: &[u8]) -> Result<Value, Error> {
+    let value = ciborium::from_reader(&mut bytes).map_err(|_| Error::new())?;
+    if !bytes.is_empty() {
+        return Err(Error::new());
+    }
+
+    Ok(value)
+}
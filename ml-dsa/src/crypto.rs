@@ -1,13 +1,9 @@
-use hybrid_array::Array;
 use sha3::{
     Shake128, Shake256,
     digest::{ExtendableOutput, XofReader},
 };
-use std::sync::Once;
 
-use crate::module_lattice::encode::ArraySize;
-
-static PRINT_ONCE: Once = Once::new();
+use crate::xof::{Xof, XofState};
 
 // CD: Added Debug to allow for logging
 #[derive(Debug)]
@@ -21,18 +17,12 @@ pub enum ShakeState<Shake: ExtendableOutput> {
 
 impl<Shake: ExtendableOutput + Default> Default for ShakeState<Shake> {
     fn default() -> Self {
-        // CD: Added println to confirm the hash function is being used
-        PRINT_ONCE.call_once(|| {
-            println!("\n ⍆ Using SHAKE hash function\n");
-        });
         Self::Absorbing(Shake::default())
     }
 }
 
-impl<Shake: ExtendableOutput + Default + Clone> ShakeState<Shake> {
-    #[allow(dead_code)] // removing compiler warnings given feature flags
-    /// Absorb input into the hash state
-    pub fn absorb(mut self, input: &[u8]) -> Self {
+impl<Shake: ExtendableOutput + Default + Clone> XofState for ShakeState<Shake> {
+    fn absorb(mut self, input: &[u8]) -> Self {
         match &mut self {
             Self::Absorbing(sponge) => sponge.update(input),
             Self::Squeezing(_) => unreachable!(),
@@ -41,9 +31,7 @@ impl<Shake: ExtendableOutput + Default + Clone> ShakeState<Shake> {
         self
     }
 
-    #[allow(dead_code)] // removing compiler warnings given feature flags
-    /// Squeeze output from the hash state
-    pub fn squeeze(&mut self, output: &mut [u8]) -> &mut Self {
+    fn squeeze(&mut self, output: &mut [u8]) -> &mut Self {
         match self {
             Self::Absorbing(sponge) => {
                 // Clone required to satisfy borrow checker
@@ -58,23 +46,34 @@ impl<Shake: ExtendableOutput + Default + Clone> ShakeState<Shake> {
 
         self
     }
-
-    #[allow(dead_code)] // removing compiler warnings given feature flags
-    /// Squeeze output from the hash state
-    pub fn squeeze_new<N: ArraySize>(&mut self) -> Array<u8, N> {
-        let mut v = Array::default();
-        self.squeeze(&mut v);
-        v
-    }
 }
 
-#[allow(dead_code)] // removing compiler warnings given feature flags
 /// SHAKE-128 hash state
 pub type G = ShakeState<Shake128>;
-#[allow(dead_code)] // removing compiler warnings given feature flags
 /// SHAKE-256 hash state
 pub type H = ShakeState<Shake256>;
 
+/// The standard FIPS 204 hash functions: SHAKE128 for `G` and SHAKE256 for `H`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ShakeXof;
+
+impl Xof for ShakeXof {
+    type G = G;
+    type H = H;
+
+    const NAME: &'static str = "SHAKE";
+    const ALGORITHM_ARC: Option<&'static str> = None;
+    const SHAKE: bool = true;
+
+    fn g(&self) -> G {
+        G::default()
+    }
+
+    fn h(&self) -> H {
+        H::default()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -4,18 +4,31 @@ use aes::{
 };
 use blake3::Hasher;
 use ctr::Ctr64BE;
-use hybrid_array::Array;
-use std::sync::Once;
-use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "std")]
+use std::{vec, vec::Vec};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
-use crate::module_lattice::encode::ArraySize;
+use crate::secret::Secret;
+use crate::xof::{Xof, domain_state, private_arc};
 
 type AesCtr = Ctr64BE<Aes128>;
 
-static PRINT_ONCE: Once = Once::new();
+/// The BLAKE3 `derive_key` context that keys the AES stream for the `G` role (`ExpandA`)
+pub const G_CONTEXT: &str = "RustCrypto ml-dsa AES-128-CTR XOF v1 G";
+/// The BLAKE3 `derive_key` context that keys the AES stream for the `H` role (everything except
+/// `ExpandA`)
+pub const H_CONTEXT: &str = "RustCrypto ml-dsa AES-128-CTR XOF v1 H";
 
 /// Tunable parameters for the AES-based XOF.
-#[derive(Clone, Copy, Debug)]
+///
+/// These only affect how the keystream is buffered, never the output.  They are carried by an
+/// [`AesXof`] value, so each key can be tuned independently.  The cache is only used with the
+/// `std` feature; otherwise these have no effect.
+///
+/// Each value is clamped to between [`AesParams::MIN_BYTES`] and [`AesParams::MAX_CACHE_BYTES`]
+/// and rounded up to whole AES blocks, and the fill sizes are capped to the cache size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct AesParams {
     /// Size of the keystream cache (bytes).
     pub cache_bytes: usize,
@@ -33,22 +46,21 @@ impl AesParams {
         min_refill: 512,
     };
 
-    fn sanitized(self) -> Self {
-        let cache_bytes = align16(self.cache_bytes.max(16));
-        let mut initial_fill = align16(self.initial_fill.max(16));
-        let mut min_refill = align16(self.min_refill.max(16));
+    /// The smallest value of each parameter: one AES block
+    pub const MIN_BYTES: usize = 16;
 
-        if initial_fill > cache_bytes {
-            initial_fill = cache_bytes;
-        }
-        if min_refill > cache_bytes {
-            min_refill = cache_bytes;
-        }
+    /// The largest value of each parameter, 1 MiB, so that an oversized setting cannot abort on
+    /// allocation
+    pub const MAX_CACHE_BYTES: usize = 1 << 20;
+
+    fn sanitized(self) -> Self {
+        let clamp = |n: usize| align16(n.clamp(Self::MIN_BYTES, Self::MAX_CACHE_BYTES));
+        let cache_bytes = clamp(self.cache_bytes);
 
         Self {
             cache_bytes,
-            initial_fill,
-            min_refill,
+            initial_fill: clamp(self.initial_fill).min(cache_bytes),
+            min_refill: clamp(self.min_refill).min(cache_bytes),
         }
     }
 }
@@ -59,66 +71,16 @@ impl Default for AesParams {
     }
 }
 
-static CACHE_BYTES: AtomicUsize = AtomicUsize::new(AesParams::DEFAULT.cache_bytes);
-static INITIAL_FILL: AtomicUsize = AtomicUsize::new(AesParams::DEFAULT.initial_fill);
-static MIN_REFILL: AtomicUsize = AtomicUsize::new(AesParams::DEFAULT.min_refill);
-
-fn current_params() -> AesParams {
-    AesParams {
-        cache_bytes: CACHE_BYTES.load(Ordering::Relaxed),
-        initial_fill: INITIAL_FILL.load(Ordering::Relaxed),
-        min_refill: MIN_REFILL.load(Ordering::Relaxed),
-    }
-}
-
-/// Fetch the active AES parameters.
-#[must_use] pub fn get_aes_params() -> AesParams {
-    current_params()
-}
-
-/// Override the global AES parameters, returning the previous set.
-pub fn set_aes_params(params: AesParams) -> AesParams {
-    let sanitized = params.sanitized();
-    let old = current_params();
-
-    CACHE_BYTES.store(sanitized.cache_bytes, Ordering::Relaxed);
-    INITIAL_FILL.store(sanitized.initial_fill, Ordering::Relaxed);
-    MIN_REFILL.store(sanitized.min_refill, Ordering::Relaxed);
-
-    old
-}
-
-/// Execute `f` with the provided AES parameters, restoring old values afterwards.
-pub fn with_aes_params<F, R>(params: AesParams, f: F) -> R
-where
-    F: FnOnce() -> R,
-{
-    struct Guard(AesParams);
-
-    impl Guard {
-        fn new(params: AesParams) -> Self {
-            Guard(set_aes_params(params))
-        }
-    }
-
-    impl Drop for Guard {
-        fn drop(&mut self) {
-            set_aes_params(self.0);
-        }
-    }
-
-    let guard = Guard::new(params);
-    let result = f();
-    drop(guard);
-    result
-}
-
 #[inline]
 fn align16(n: usize) -> usize {
-    (n + 15) & !15
+    // Saturate rather than wrap to zero, which would leave an empty cache
+    n.saturating_add(15) & !15
 }
 
 /// AES-CTR backed extendable-output state
+// The hasher is much larger than the cipher, but the state lives on the stack for a single
+// sampling call and boxing it would cost an allocation per XOF instance.
+#[allow(clippy::large_enum_variant)]
 pub enum AesState {
     /// Accumulates input using a streaming BLAKE3 hasher until squeezing begins.
     Absorbing {
@@ -127,151 +89,226 @@ pub enum AesState {
         /// Active tuning parameters.
         params: AesParams,
     },
-    /// Streams keystream bytes from AES-CTR with cached output.
-    Squeezing {
-        /// AES-CTR cipher instance.
-        cipher: AesCtr,
-        /// Cached keystream bytes.
-        cache: Vec<u8>,
-        /// Next unread offset in the cache.
-        off: usize,
-        /// Bytes currently valid in the cache.
-        len: usize,
-        /// Active tuning parameters.
-        params: AesParams,
-    },
+    /// Streams keystream bytes from AES-CTR.
+    Squeezing(Keystream),
 }
 
-impl Default for AesState {
-    fn default() -> Self {
-        PRINT_ONCE.call_once(|| {
-            println!("\n ⍆ Using AES hash function\n");
-        });
-        let params = current_params();
+impl AesState {
+    /// Start a hash in the domain named by `context`, with the default parameters
+    #[must_use]
+    pub fn new(context: &'static str) -> Self {
+        Self::with_params(context, AesParams::DEFAULT)
+    }
+
+    /// Start a hash in the domain named by `context`, with the specified parameters
+    #[must_use]
+    pub fn with_params(context: &'static str, params: AesParams) -> Self {
+        let params = params.sanitized();
         AesState::Absorbing {
-            hasher: Hasher::new(),
+            hasher: Hasher::new_derive_key(context),
             params,
         }
     }
 }
 
 impl AesState {
-    #[allow(dead_code)]
-    /// Absorb input into the hash state.
-    #[must_use] pub fn absorb(mut self, input: &[u8]) -> Self {
+    /// Absorb input into the hash state
+    #[must_use]
+    pub fn absorb(mut self, input: &[u8]) -> Self {
         match &mut self {
             AesState::Absorbing { hasher, .. } => {
                 hasher.update(input);
             }
-            AesState::Squeezing { .. } => unreachable!(),
+            AesState::Squeezing(_) => unreachable!(),
         }
         self
     }
 
-    fn ensure_cipher(&mut self) {
+    /// Squeeze output from the hash state
+    pub fn squeeze(&mut self, output: &mut [u8]) -> &mut Self {
         if let AesState::Absorbing { hasher, params } = self {
-            use core::mem;
-
-            let hasher = mem::replace(hasher, Hasher::new());
             let (key, nonce) = derive_key_nonce(hasher);
+            let cipher = AesCtr::new(&(*key).into(), &(*nonce).into());
+            *self = AesState::Squeezing(Keystream::new(cipher, *params));
+        }
 
-            let mut cipher = AesCtr::new(&key.into(), &nonce.into());
-            let mut cache = vec![0u8; params.cache_bytes];
-            let first = align16(params.initial_fill.min(cache.len()));
-            if first > 0 {
-                cipher.apply_keystream(&mut cache[..first]);
-            }
+        match self {
+            AesState::Squeezing(keystream) => keystream.fill(output),
+            AesState::Absorbing { .. } => unreachable!(),
+        }
 
-            *self = AesState::Squeezing {
-                cipher,
-                cache,
-                off: 0,
-                len: first,
-                params: *params,
-            };
+        self
+    }
+}
+
+// The cipher wipes its own key schedule and counter when it is dropped
+#[cfg(feature = "zeroize")]
+impl Drop for AesState {
+    fn drop(&mut self) {
+        if let AesState::Absorbing { hasher, .. } = self {
+            hasher.zeroize();
         }
     }
+}
 
-    #[allow(dead_code)]
-    /// Squeeze output from the hash state.
-    pub fn squeeze(&mut self, output: &mut [u8]) -> &mut Self {
-        self.ensure_cipher();
+/// The AES-CTR keystream, served through a cache sized by [`AesParams`]
+#[cfg(feature = "std")]
+pub struct Keystream {
+    cipher: AesCtr,
+    cache: Vec<u8>,
+    off: usize,
+    len: usize,
+    min_refill: usize,
+}
+
+#[cfg(feature = "std")]
+impl Keystream {
+    fn new(mut cipher: AesCtr, params: AesParams) -> Self {
+        let mut cache = vec![0u8; params.cache_bytes];
+        let first = params.initial_fill;
+        cipher.apply_keystream(&mut cache[..first]);
 
-        if let AesState::Squeezing {
+        Self {
             cipher,
             cache,
-            off,
-            len,
-            params,
-        } = self
-        {
-            let mut written = 0;
-            while written < output.len() {
-                if *off == *len {
-                    let need = output.len() - written;
-                    let cache_capacity = cache.len();
-                    if cache_capacity == 0 {
-                        cipher.apply_keystream(&mut output[written..]);
-                        return self;
-                    }
-
-                    let want = align16(core::cmp::max(need, params.min_refill));
-                    let filln = core::cmp::min(want, cache_capacity);
-                    cipher.apply_keystream(&mut cache[..filln]);
-                    *off = 0;
-                    *len = filln;
-                }
-
-                let avail = *len - *off;
+            off: 0,
+            len: first,
+            min_refill: params.min_refill,
+        }
+    }
+
+    fn fill(&mut self, output: &mut [u8]) {
+        let mut written = 0;
+        while written < output.len() {
+            if self.off == self.len {
                 let need = output.len() - written;
-                let take = core::cmp::min(avail, need);
-                output[written..written + take].copy_from_slice(&cache[*off..*off + take]);
-                *off += take;
-                written += take;
+                let want = align16(core::cmp::max(need, self.min_refill));
+                let filln = core::cmp::min(want, self.cache.len());
+
+                // `apply_keystream` XORs into the buffer, so it has to start out zeroed
+                self.cache[..filln].fill(0);
+                self.cipher.apply_keystream(&mut self.cache[..filln]);
+                self.off = 0;
+                self.len = filln;
             }
-        } else {
-            unreachable!();
+
+            let avail = self.len - self.off;
+            let need = output.len() - written;
+            let take = core::cmp::min(avail, need);
+            output[written..written + take].copy_from_slice(&self.cache[self.off..self.off + take]);
+            self.off += take;
+            written += take;
         }
+    }
+}
 
-        self
+#[cfg(all(feature = "std", feature = "zeroize"))]
+impl Drop for Keystream {
+    fn drop(&mut self) {
+        self.cache.zeroize();
     }
+}
+
+/// The AES-CTR keystream.  Without the `std` feature there is no cache, and [`AesParams`] have no
+/// effect.
+#[cfg(not(feature = "std"))]
+pub struct Keystream {
+    cipher: AesCtr,
+}
 
-    #[allow(dead_code)]
-    /// Squeeze output from the hash state into a new array.
-    pub fn squeeze_new<N: ArraySize>(&mut self) -> Array<u8, N> {
-        let mut v = Array::default();
-        self.squeeze(&mut v);
-        v
+#[cfg(not(feature = "std"))]
+impl Keystream {
+    fn new(cipher: AesCtr, _params: AesParams) -> Self {
+        Self { cipher }
+    }
+
+    fn fill(&mut self, output: &mut [u8]) {
+        // `apply_keystream` XORs into the buffer, so it has to start out zeroed
+        output.fill(0);
+        self.cipher.apply_keystream(output);
     }
 }
 
-fn derive_key_nonce(hasher: Hasher) -> ([u8; 16], [u8; 16]) {
+fn derive_key_nonce(hasher: &Hasher) -> (Secret<[u8; 16]>, Secret<[u8; 16]>) {
     let mut reader = hasher.finalize_xof();
-    let mut key = [0u8; 16];
-    let mut nonce = [0u8; 16];
-    reader.fill(&mut key);
-    reader.fill(&mut nonce);
+    let mut key = Secret::new([0u8; 16]);
+    let mut nonce = Secret::new([0u8; 16]);
+    reader.fill(&mut *key);
+    reader.fill(&mut *nonce);
+    #[cfg(feature = "zeroize")]
+    reader.zeroize();
     (key, nonce)
 }
 
-#[allow(dead_code)]
-/// AES hash state for G function
-pub type G = AesState;
-#[allow(dead_code)]
-/// AES hash state for H function
-pub type H = AesState;
+domain_state!(
+    /// AES hash state for G function, in the [`G_CONTEXT`] domain
+    G,
+    AesState,
+    G_CONTEXT
+);
+domain_state!(
+    /// AES hash state for H function, in the [`H_CONTEXT`] domain
+    H,
+    AesState,
+    H_CONTEXT
+);
+
+/// AES-128-CTR keyed from a BLAKE3 digest of the input, with separate `derive_key` contexts for
+/// `G` and `H`
+///
+/// The value carries the [`AesParams`] used by every state it starts.  Use
+/// [`SigningKey::with_aes_params`](crate::SigningKey::with_aes_params) or
+/// [`VerifyingKey::with_aes_params`](crate::VerifyingKey::with_aes_params) to tune a key.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AesXof {
+    params: AesParams,
+}
+
+impl AesXof {
+    /// Use the specified parameters, clamped to the range allowed by [`AesParams`], rounded up to
+    /// whole AES blocks and capped to the cache size
+    #[must_use]
+    pub fn new(params: AesParams) -> Self {
+        Self {
+            params: params.sanitized(),
+        }
+    }
+
+    /// The parameters in use
+    #[must_use]
+    pub fn params(&self) -> AesParams {
+        self.params
+    }
+}
+
+impl Xof for AesXof {
+    type G = G;
+    type H = H;
+
+    const NAME: &'static str = "AES-128-CTR";
+    const ALGORITHM_ARC: Option<&'static str> = Some(private_arc!(".2"));
+
+    fn g(&self) -> G {
+        G(AesState::with_params(G_CONTEXT, self.params))
+    }
+
+    fn h(&self) -> H {
+        H(AesState::with_params(H_CONTEXT, self.params))
+    }
+}
 
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::util::B32;
+    use crate::xof::XofState;
     use hex_literal::hex;
 
     #[test]
     fn g() {
         let input = b"hello world";
-        let expected1 = hex!("e03f0d1922245256d0ee5d8ff0a9da66c893fbd69e8ff59c4189967505a72c09");
-        let expected2 = hex!("efdc57f8f746cfe03c300ad03ea6d2289104b1f76d5ce31469a3d7d655175460");
+        let expected1 = hex!("02545c13784b66c84d501ef63e760b7be02f3c5e601f049e27dde078a7bf49d2");
+        let expected2 = hex!("1bd5d2bf693ba64f17ec68041836435b69087e8a2fd2b098940e0a3c7feae4dd");
 
         let mut g = G::default().absorb(input);
 
@@ -286,8 +323,8 @@ mod test {
     #[test]
     fn h() {
         let input = b"hello world";
-        let expected1 = hex!("e03f0d1922245256d0ee5d8ff0a9da66c893fbd69e8ff59c4189967505a72c09");
-        let expected2 = hex!("efdc57f8f746cfe03c300ad03ea6d2289104b1f76d5ce31469a3d7d655175460");
+        let expected1 = hex!("9757d45c2eda101d4858eea59cd66a7d492c282dde7e2f0e72926366563da29a");
+        let expected2 = hex!("3e3f9ef71a0c46668081ef32a3bad2165a58a245112868482620893476a4d1f4");
 
         let mut h = H::default().absorb(input);
 
@@ -298,4 +335,76 @@ mod test {
         let actual: B32 = h.squeeze_new();
         assert_eq!(actual, expected2);
     }
+
+    #[test]
+    fn params_do_not_change_output() {
+        let input = b"hello world";
+        let mut expected = [0u8; 1000];
+        AesXof::default().h().absorb(input).squeeze(&mut expected);
+
+        for (cache_bytes, initial_fill, min_refill) in [
+            (0, 0, 0),
+            (16, 16, 16),
+            (100, 7, 33),
+            (usize::MAX, usize::MAX, usize::MAX),
+            (usize::MAX - 7, 1, usize::MAX - 15),
+        ] {
+            let xof = AesXof::new(AesParams {
+                cache_bytes,
+                initial_fill,
+                min_refill,
+            });
+
+            let mut h = xof.h().absorb(input);
+            let mut actual = [0u8; 1000];
+            for chunk in actual.chunks_mut(37) {
+                h.squeeze(chunk);
+            }
+            assert_eq!(actual, expected);
+        }
+    }
+    #[test]
+    fn params_are_clamped() {
+        let max = AesXof::new(AesParams {
+            cache_bytes: usize::MAX,
+            initial_fill: usize::MAX,
+            min_refill: usize::MAX,
+        });
+        assert_eq!(
+            max.params(),
+            AesParams {
+                cache_bytes: AesParams::MAX_CACHE_BYTES,
+                initial_fill: AesParams::MAX_CACHE_BYTES,
+                min_refill: AesParams::MAX_CACHE_BYTES,
+            }
+        );
+
+        let min = AesXof::new(AesParams {
+            cache_bytes: 0,
+            initial_fill: 0,
+            min_refill: 0,
+        });
+        assert_eq!(
+            min.params(),
+            AesParams {
+                cache_bytes: AesParams::MIN_BYTES,
+                initial_fill: AesParams::MIN_BYTES,
+                min_refill: AesParams::MIN_BYTES,
+            }
+        );
+
+        let unaligned = AesXof::new(AesParams {
+            cache_bytes: 100,
+            initial_fill: 7,
+            min_refill: usize::MAX - 1,
+        });
+        assert_eq!(
+            unaligned.params(),
+            AesParams {
+                cache_bytes: 112,
+                initial_fill: 16,
+                min_refill: 112,
+            }
+        );
+    }
 }
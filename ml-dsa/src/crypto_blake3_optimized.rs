@@ -1,11 +1,11 @@
 // CD: Added crypto_blake3 module
-use crate::module_lattice::encode::ArraySize;
 use blake3::{Hasher, OutputReader};
-use hybrid_array::Array;
-use std::sync::Once;
+use std::{boxed::Box, vec::Vec};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
-#[allow(dead_code)]
-static PRINT_ONCE: Once = Once::new();
+use crate::crypto_blake3_niave::{G_CONTEXT, H_CONTEXT};
+use crate::xof::{Xof, domain_state, private_arc};
 
 // Cache for holding hash output
 const CACHE_BYTES: usize = 16_384; // multiple of 32;
@@ -17,8 +17,11 @@ fn align32(n: usize) -> usize {
     (n + 31) & !31
 }
 
+// The hasher is much larger than the reader, but the state lives on the stack for a single
+// sampling call and boxing it would cost an allocation per XOF instance.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
-/// BLAKE3 hash state
+/// BLAKE3 hash state, keyed with a `derive_key` context
 pub enum Blake3State {
     // Buffer inputs; do not touch the hasher until the first squeeze.
     /// Absorbing state
@@ -42,59 +45,29 @@ pub enum Blake3State {
     },
 }
 
-impl Default for Blake3State {
-    fn default() -> Self {
-        PRINT_ONCE.call_once(|| {
-            println!("\n ⍆ Using BLAKE3 optimized hash function\n");
-        });
+impl Blake3State {
+    /// Start a hash in the domain named by `context`
+    #[must_use]
+    pub fn new(context: &'static str) -> Self {
         Blake3State::Absorbing {
-            hasher: Hasher::new(),
+            hasher: Hasher::new_derive_key(context),
             buf: Vec::with_capacity(128),
         }
     }
-}
-
-impl Blake3State {
-    #[allow(dead_code)] // removing compiler warnings given feature flags
-    /// Absorb input into the hash state
-    #[must_use] pub fn absorb(mut self, input: &[u8]) -> Self {
-        match &mut self {
-            Blake3State::Absorbing { hasher, buf } => {
-                if input.len() >= PARALLEL_THRESHOLD {
-                    println!("Using parallel absorb");
-                    if !buf.is_empty() {
-                        hasher.update(buf);
-                        buf.clear();
-                    }
-                    // Large message → parallel absorb (requires blake3 with rayon enabled)
-                    hasher.update_rayon(input);
-                } else {
-                    // println!("Using sequential absorb");
-                    // Small message → just buffer; we hash once at first squeeze()
-                    buf.extend_from_slice(input);
-                }
-            }
-            Blake3State::Squeezing { .. } => unreachable!(), // absorb-after-squeeze not allowed
-        }
-        self
-    }
 
     #[inline]
     fn ensure_reader(&mut self) {
         if let Blake3State::Absorbing { hasher, buf } = self {
-            // println!("Updating hasher");
             // Hash all buffered input once, then finalize into an XOF reader.
             hasher.update(buf);
             buf.clear();
             let mut reader = hasher.finalize_xof();
 
             // Prime the cache with a large fill to avoid tiny reads.
-            // println!("Priming cache");
             let mut cache = Box::new([0u8; CACHE_BYTES]);
             let first = INITIAL_FILL.min(CACHE_BYTES);
             reader.fill(&mut cache[..first]);
 
-            // println!("Switching to squeezing state");
             *self = Blake3State::Squeezing {
                 reader,
                 cache,
@@ -103,10 +76,34 @@ impl Blake3State {
             };
         }
     }
+}
+
+impl Blake3State {
+    /// Absorb input into the hash state
+    #[must_use]
+    pub fn absorb(mut self, input: &[u8]) -> Self {
+        match &mut self {
+            Blake3State::Absorbing { hasher, buf } => {
+                if input.len() >= PARALLEL_THRESHOLD {
+                    if !buf.is_empty() {
+                        hasher.update(buf);
+                        buf.clear();
+                    }
+                    // Large message → parallel absorb (requires blake3 with rayon enabled)
+                    hasher.update_rayon(input);
+                } else {
+                    // Small message → just buffer; we hash once at first squeeze()
+                    reserve(buf, input.len());
+                    buf.extend_from_slice(input);
+                }
+            }
+            Blake3State::Squeezing { .. } => unreachable!(), // absorb-after-squeeze not allowed
+        }
+        self
+    }
 
     /// Squeeze output from the hash state
     pub fn squeeze(&mut self, out: &mut [u8]) -> &mut Self {
-        // println!("Squeezing");
         // On first squeeze, finalize and switch to streaming mode.
         self.ensure_reader();
 
@@ -122,15 +119,12 @@ impl Blake3State {
             while written < out.len() {
                 // Refill cache if empty.
                 if *off == *len {
-                    // println!("Refilling cache");
                     let need = out.len() - written; // bytes caller still needs
                     let want = align32(core::cmp::max(need, MIN_REFILL)); // >=512 and multiple of 32
                     let filln = core::cmp::min(want, CACHE_BYTES); // cap to cache size
                     reader.fill(&mut cache[..filln]);
                     *off = 0;
                     *len = filln;
-                } else {
-                    // println!("Serving from cache");
                 }
                 let avail = *len - *off;
                 let need = out.len() - written;
@@ -146,34 +140,85 @@ impl Blake3State {
         }
         self
     }
+}
 
-    #[allow(dead_code)] // removing compiler warnings given feature flags
-    /// Squeeze output from the hash state
-    pub fn squeeze_new<N: ArraySize>(&mut self) -> Array<u8, N> {
-        let mut v = Array::default();
-        self.squeeze(&mut v);
-        v
+// The buffer holds secret seeds, so it is wiped on drop.  Letting `Vec` grow it would free the old
+// allocation without wiping it, so it is grown by hand.
+fn reserve(buf: &mut Vec<u8>, additional: usize) {
+    if buf.capacity() - buf.len() >= additional {
+        return;
     }
+
+    let mut grown = Vec::with_capacity((buf.len() + additional).max(2 * buf.capacity()));
+    grown.extend_from_slice(buf);
+    #[cfg(feature = "zeroize")]
+    buf.zeroize();
+    *buf = grown;
 }
 
-#[allow(dead_code)] // removing compiler warnings given feature flags
-/// BLAKE3 hash state for G function
-pub type G = Blake3State;
-#[allow(dead_code)] // removing compiler warnings given feature flags
-/// BLAKE3 hash state for H function
-pub type H = Blake3State;
+#[cfg(feature = "zeroize")]
+impl Drop for Blake3State {
+    fn drop(&mut self) {
+        match self {
+            Blake3State::Absorbing { hasher, buf } => {
+                hasher.zeroize();
+                buf.zeroize();
+            }
+            Blake3State::Squeezing { reader, cache, .. } => {
+                reader.zeroize();
+                cache.zeroize();
+            }
+        }
+    }
+}
+
+domain_state!(
+    /// BLAKE3 hash state for G function, in the [`G_CONTEXT`] domain
+    G,
+    Blake3State,
+    G_CONTEXT
+);
+domain_state!(
+    /// BLAKE3 hash state for H function, in the [`H_CONTEXT`] domain
+    H,
+    Blake3State,
+    H_CONTEXT
+);
+
+/// BLAKE3 in extendable-output mode, with separate `derive_key` contexts for `G` and `H`, buffered
+/// absorption and a cached output stream.  Produces the same output as [`Blake3Xof`](crate::Blake3Xof).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Blake3OptimizedXof;
+
+impl Xof for Blake3OptimizedXof {
+    type G = G;
+    type H = H;
+
+    const NAME: &'static str = "BLAKE3 optimized";
+    // The output is that of `Blake3Xof`, so the keys are interchangeable
+    const ALGORITHM_ARC: Option<&'static str> = Some(private_arc!(".1"));
+
+    fn g(&self) -> G {
+        G::default()
+    }
+
+    fn h(&self) -> H {
+        H::default()
+    }
+}
 
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::util::B32;
+    use crate::xof::XofState;
     use hex_literal::hex;
 
     #[test]
     fn g() {
         let input = b"hello world";
-        let expected1 = hex!("d74981efa70a0c880b8d8c1985d075dbcbf679b99a5f9914e5aaf96b831a9e24");
-        let expected2 = hex!("a020ed55aed9a6ab2eaf3fd70d2c98c949e142d8f42a10250190b699e02cf9eb");
+        let expected1 = hex!("a65ebadbf92f5939d88a64aae726394d1abfa26e417c5c577b578e151dcbd63e");
+        let expected2 = hex!("2867763419e72b66009fc11ba853fcf3fe5b67afd1e8b424fc9b65a7bbd5a263");
 
         let mut g = G::default().absorb(input);
 
@@ -188,8 +233,8 @@ mod test {
     #[test]
     fn h() {
         let input = b"hello world";
-        let expected1 = hex!("d74981efa70a0c880b8d8c1985d075dbcbf679b99a5f9914e5aaf96b831a9e24");
-        let expected2 = hex!("a020ed55aed9a6ab2eaf3fd70d2c98c949e142d8f42a10250190b699e02cf9eb");
+        let expected1 = hex!("86c41c66fefa433f39ea33fbf77d929afbf823252aa9c80e6602648541145373");
+        let expected2 = hex!("606e3c1c5c36cbb553933ec874dc20b897c60a89aa23eaa481081a5d23e781f4");
 
         let mut h = H::default().absorb(input);
 
@@ -0,0 +1,202 @@
+//! Hierarchical deterministic derivation of ML-DSA keys from a master secret.
+//!
+//! A tree of [`DerivationKey`]s grows from a 32-byte master secret.  Each node has a child for
+//! every segment, which is a byte string of 1 to 255 bytes, and a path such as
+//! `m/tenant/3/device/17` names a node below the one it is applied to.  Each node then gives the
+//! seed ξ of one key pair for each parameter set, which is passed to [`KeyPair::from_seed`].
+//! Backing up the master secret is enough to recover every key in the tree, and handing out the
+//! `DerivationKey` of a node, such as `m/tenant/3`, lets its holder derive the keys below it but
+//! none of the others.
+//!
+//! Unlike BIP 32 for elliptic curves, there is no derivation of public keys alone: an ML-DSA
+//! verifying key is only known once key generation has run on the secret seed.  Every function of
+//! this module therefore takes a secret, and a service that only holds verifying keys has to be
+//! given them.
+//!
+//! # Key derivation function
+//!
+//! All values are 32 bytes, and all hashing is SHAKE256.  The node of the master secret `s` is
+//! `s` itself, and the child of the node `n` for the segment `c` is
+//!
+//! ```text
+//! SHAKE256("ML-DSA-HD/child" || n || len(c) || c, 32)
+//! ```
+//!
+//! where `len(c)` is one byte.  The seed of the node `n` for a parameter set is
+//!
+//! ```text
+//! SHAKE256("ML-DSA-HD/xi" || params || n, 32)
+//! ```
+//!
+//! where `params` is the concatenation of `k`, `l`, η, γ1, γ2, λ (in bytes), ω and τ, each as a
+//! 4-byte big-endian integer.  For [`MlDsa65`](crate::MlDsa65), this is `6, 5, 4, 2^19,
+//! (q - 1)/32, 48, 55, 49`.  The seeds of different parameter sets, including ones defined outside
+//! this crate, are therefore independent.  The segments of a path are the bytes between the `/`
+//! characters, so that the segment `3` is the single byte `0x33`.
+//!
+//! Each key pair also depends on the [`Xof`] backend that runs key generation, so the same seed
+//! gives unrelated keys with different backends.
+//!
+//! All intermediate values, including the state of SHAKE256, are zeroized once they are no longer
+//! needed.
+//!
+//! ```
+//! use ml_dsa::{MlDsa65, derive::DerivationKey};
+//!
+//! let master = DerivationKey::new(&[7; 32].into());
+//! let device = master.derive("m/tenant/3/device/17").unwrap();
+//! let kp = device.key_pair::<MlDsa65>();
+//!
+//! // The tenant's key derives the same device keys
+//! let tenant = master.derive("m/tenant/3").unwrap();
+//! let same = tenant.derive("m/device/17").unwrap().key_pair::<MlDsa65>();
+//! assert_eq!(kp.verifying_key(), same.verifying_key());
+//! ```
+
+use core::fmt;
+
+use hybrid_array::typenum::Unsigned;
+use sha3::Shake256;
+use sha3::digest::{ExtendableOutput, Update};
+use signature::Error;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::KeyPair;
+use crate::compact::CompactSigningKey;
+use crate::crypto::ShakeXof;
+use crate::param::{MlDsaParams, ParameterSet, Validate};
+use crate::util::B32;
+use crate::xof::Xof;
+
+const CHILD_LABEL: &[u8] = b"ML-DSA-HD/child";
+const XI_LABEL: &[u8] = b"ML-DSA-HD/xi";
+
+/// A node of a derivation tree, from which keys and further nodes are derived.
+///
+/// The node is as secret as the keys below it.  It is zeroized when it is dropped.
+#[derive(Clone)]
+pub struct DerivationKey {
+    node: B32,
+}
+
+impl fmt::Debug for DerivationKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DerivationKey").finish_non_exhaustive()
+    }
+}
+
+impl Drop for DerivationKey {
+    fn drop(&mut self) {
+        self.node.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for DerivationKey {}
+
+impl DerivationKey {
+    /// The root of the tree of the master secret
+    #[must_use]
+    pub fn new(master: &B32) -> Self {
+        Self {
+            node: master.clone(),
+        }
+    }
+
+    /// The child of this node for `segment`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an opaque error if the segment is empty or more than 255 bytes long.
+    pub fn child(&self, segment: &[u8]) -> Result<Self, Error> {
+        let len = u8::try_from(segment.len()).map_err(|_| Error::new())?;
+        if len == 0 {
+            return Err(Error::new());
+        }
+
+        let mut hash = Shake256::default();
+        hash.update(CHILD_LABEL);
+        hash.update(&self.node);
+        hash.update(&[len]);
+        hash.update(segment);
+
+        let mut node = B32::default();
+        hash.finalize_xof_into(&mut node);
+        Ok(Self { node })
+    }
+
+    /// The node at `path` below this one.  The path starts with `m`, which stands for this node,
+    /// and continues with its segments, each preceded by `/`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an opaque error if the path does not start with `m`, or if one of
+    /// its segments is empty or more than 255 bytes long.
+    pub fn derive(&self, path: &str) -> Result<Self, Error> {
+        let mut segments = path.split('/');
+        if segments.next() != Some("m") {
+            return Err(Error::new());
+        }
+
+        segments.try_fold(self.clone(), |node, segment| node.child(segment.as_bytes()))
+    }
+
+    /// The seed ξ of the key pair of this node for the parameter set `P`
+    ///
+    /// # Panics
+    ///
+    /// This method does not panic: τ is at most 64 in every parameter set that compiles.
+    #[must_use]
+    pub fn seed<P: ParameterSet>(&self) -> Zeroizing<B32> {
+        let () = P::VALID;
+        let params = [
+            P::K::U32,
+            P::L::U32,
+            P::Eta::U32,
+            P::Gamma1::U32,
+            P::Gamma2::U32,
+            P::Lambda::U32,
+            P::Omega::U32,
+            u32::try_from(P::TAU).expect("TAU is at most 64, as checked by VALID"),
+        ];
+
+        let mut hash = Shake256::default();
+        hash.update(XI_LABEL);
+        for x in params {
+            hash.update(&x.to_be_bytes());
+        }
+        hash.update(&self.node);
+
+        let mut xi = Zeroizing::new(B32::default());
+        hash.finalize_xof_into(&mut xi);
+        xi
+    }
+
+    /// The key pair of this node for the parameter set `P`, with the standard SHAKE backend
+    #[must_use]
+    pub fn key_pair<P: MlDsaParams>(&self) -> KeyPair<P> {
+        self.key_pair_with_xof(ShakeXof)
+    }
+
+    /// The key pair of this node for the parameter set `P`, with key generation run by `xof`
+    #[must_use]
+    pub fn key_pair_with_xof<P: MlDsaParams, X: Xof>(&self, xof: X) -> KeyPair<P, X> {
+        KeyPair::from_seed_with_xof(&self.seed::<P>(), xof)
+    }
+
+    /// The signing key of this node for the parameter set `P`, in its compact form, with the
+    /// standard SHAKE backend
+    #[must_use]
+    pub fn compact_signing_key<P: MlDsaParams>(&self) -> CompactSigningKey<P> {
+        self.compact_signing_key_with_xof(ShakeXof)
+    }
+
+    /// The signing key of this node for the parameter set `P`, in its compact form, with key
+    /// generation run by `xof`
+    #[must_use]
+    pub fn compact_signing_key_with_xof<P: MlDsaParams, X: Xof>(
+        &self,
+        xof: X,
+    ) -> CompactSigningKey<P, X> {
+        CompactSigningKey::from_seed(&self.seed::<P>()).with_xof(xof)
+    }
+}
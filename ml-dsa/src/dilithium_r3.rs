@@ -0,0 +1,758 @@
+//! CRYSTALS-Dilithium as submitted to the third round of the NIST process (version 3.1), for
+//! interoperating with keys and signatures that predate FIPS 204, such as those of `liboqs` and
+//! `PQClean`.
+//!
+//! Round 3 differs from ML-DSA in the following ways:
+//!
+//! * Key generation hashes the seed on its own, without the dimensions `k` and `l`.
+//! * The hash `tr` of the verifying key is 32 bytes rather than 64, so that signing keys are 32
+//!   bytes shorter.
+//! * The message is hashed directly with `tr`.  There is no context string, and no domain
+//!   separator for pre-hashed messages.
+//! * The commitment hash `c_tilde` is always 32 bytes, so that signatures are shorter than ML-DSA
+//!   ones at the higher security levels.
+//! * Deterministic signing derives the mask seed from the key and the message alone, and
+//!   randomized signing draws the whole seed at random.
+//!
+//! The other algorithms, and the encoding of verifying keys, are those of ML-DSA.
+//! Signatures have their own [`Signature`] type, since `c_tilde` has a different length.
+//!
+//! The [`Aes`] variant is Dilithium-AES, which uses AES-256-CTR instead of SHAKE to expand the
+//! matrix, the secret vectors and the mask.  It is unrelated to [`AesXof`](crate::AesXof).
+//!
+//! ```
+//! use ml_dsa::dilithium_r3::{Dilithium3, KeyPair};
+//!
+//! let kp = KeyPair::<Dilithium3>::from_seed(&Default::default());
+//! let sig = kp.signing_key().sign_deterministic(b"Hello world").unwrap();
+//! assert!(kp.verifying_key().verify_message(b"Hello world", &sig));
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use aes::{
+    Aes256,
+    cipher::{KeyIvInit, StreamCipher},
+};
+use ctr::Ctr32BE;
+use hybrid_array::{
+    Array,
+    typenum::{U32, U64, Unsigned},
+};
+use signature::Error;
+
+#[cfg(feature = "rand_core")]
+use rand_core::{CryptoRng, TryCryptoRng};
+
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::algebra::{AlgebraExt, Elem, NttMatrix, NttVector, Truncate, Vector};
+use crate::crypto::ShakeXof;
+use crate::hint::Hint;
+use crate::module_lattice::encode::ArraySize;
+use crate::ntt::Ntt;
+use crate::param::{
+    self, EncodedHint, EncodedVerifyingKey, EncodedZ, Eta, MaskSamplingSize, MlDsaParams,
+    ParameterSet, SamplingSize, SignatureParams, SigningKeyParams, VerifyingKeyParams,
+};
+use crate::sampling::{self, rej_bounded_poly_from, rej_ntt_poly_from};
+use crate::secret::Secret;
+use crate::util::{B32, B64};
+use crate::xof::{Xof, XofState};
+use crate::{MlDsa44, MlDsa65, MlDsa87, SigningError, compute_t1_t0, sign_loop, verify_mu};
+
+use self::sealed::Zeroed as _;
+/// A Dilithium round-3 parameter set.
+///
+/// Each one has the dimensions and bounds of the ML-DSA parameter set of the same security level.
+/// The encodings of its signing keys and signatures are plain arrays, since some of their lengths
+/// are not supported by `hybrid_array`.
+pub trait DilithiumParams: Clone + fmt::Debug + Default + PartialEq + 'static {
+    /// The ML-DSA parameter set with the same dimensions and bounds
+    type Base: MlDsaParams;
+
+    /// An encoded signing key, which is 32 bytes shorter than an ML-DSA one
+    type EncodedSigningKey: EncodedBytes;
+
+    /// An encoded signature, with a 32-byte `c_tilde`
+    type EncodedSignature: EncodedBytes;
+}
+
+/// A byte array that holds an encoded key or signature
+pub trait EncodedBytes:
+    AsRef<[u8]>
+    + AsMut<[u8]>
+    + Copy
+    + fmt::Debug
+    + PartialEq
+    + Eq
+    + Send
+    + Sync
+    + 'static
+    + sealed::Zeroed
+{
+}
+
+impl<const N: usize> EncodedBytes for [u8; N] {}
+
+type Base<P> = <P as DilithiumParams>::Base;
+type K<P> = <Base<P> as ParameterSet>::K;
+type L<P> = <Base<P> as ParameterSet>::L;
+
+/// `Dilithium2` is the round-3 parameter set for security category 2.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Dilithium2;
+
+impl DilithiumParams for Dilithium2 {
+    type Base = MlDsa44;
+    type EncodedSigningKey = [u8; 2528];
+    type EncodedSignature = [u8; 2420];
+}
+
+/// `Dilithium3` is the round-3 parameter set for security category 3.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Dilithium3;
+
+impl DilithiumParams for Dilithium3 {
+    type Base = MlDsa65;
+    type EncodedSigningKey = [u8; 4000];
+    type EncodedSignature = [u8; 3293];
+}
+
+/// `Dilithium5` is the round-3 parameter set for security category 5.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Dilithium5;
+
+impl DilithiumParams for Dilithium5 {
+    type Base = MlDsa87;
+    type EncodedSigningKey = [u8; 4864];
+    type EncodedSignature = [u8; 4595];
+}
+
+mod sealed {
+    use super::{ArraySize, B32, B64, Eta, MaskSamplingSize, NttMatrix, Vector};
+
+    pub trait Zeroed {
+        fn zeroed() -> Self;
+    }
+
+    impl<const N: usize> Zeroed for [u8; N] {
+        fn zeroed() -> Self {
+            [0; N]
+        }
+    }
+
+    // The expansion functions that differ between the variants, as in `symmetric.h` of the
+    // reference implementation
+    pub trait Sealed {
+        fn expand_a<K: ArraySize, L: ArraySize>(rho: &B32) -> NttMatrix<K, L>;
+        fn expand_s<K: ArraySize>(rhop: &B64, eta: Eta, base: usize) -> Vector<K>;
+        fn expand_mask<K: ArraySize, Gamma1: MaskSamplingSize>(
+            rhopp: &B64,
+            kappa: u16,
+        ) -> Vector<K>;
+    }
+}
+
+/// The symmetric primitives used to expand seeds, either [`Shake`] or [`Aes`]
+pub trait Variant:
+    sealed::Sealed + Clone + fmt::Debug + Default + PartialEq + Eq + 'static
+{
+}
+
+/// Dilithium, which expands seeds with SHAKE128 and SHAKE256 as ML-DSA does
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Shake;
+
+impl Variant for Shake {}
+
+impl sealed::Sealed for Shake {
+    fn expand_a<K: ArraySize, L: ArraySize>(rho: &B32) -> NttMatrix<K, L> {
+        sampling::expand_a::<ShakeXof, K, L>(&ShakeXof, rho)
+    }
+
+    fn expand_s<K: ArraySize>(rhop: &B64, eta: Eta, base: usize) -> Vector<K> {
+        sampling::expand_s::<ShakeXof, K>(&ShakeXof, rhop, eta, base)
+    }
+
+    fn expand_mask<K: ArraySize, Gamma1: MaskSamplingSize>(rhopp: &B64, kappa: u16) -> Vector<K> {
+        sampling::expand_mask::<ShakeXof, K, Gamma1>(&ShakeXof, rhopp, kappa)
+    }
+}
+
+/// Dilithium-AES, which expands seeds with AES-256-CTR.  The other hashes are still SHAKE256.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Aes;
+
+impl Variant for Aes {}
+
+// AES-256-CTR keyed with the first 32 bytes of `seed`.  The nonce takes the first two bytes of the
+// IV, little-endian, and the block counter the last four, big-endian.
+fn aes_stream(seed: &[u8], nonce: u16) -> impl FnMut(&mut [u8]) {
+    let mut iv = [0u8; 16];
+    iv[..2].copy_from_slice(&nonce.to_le_bytes());
+    let mut cipher = Ctr32BE::<Aes256>::new(seed[..32].into(), &iv.into());
+
+    move |output| {
+        output.fill(0);
+        cipher.apply_keystream(output);
+    }
+}
+
+impl sealed::Sealed for Aes {
+    fn expand_a<K: ArraySize, L: ArraySize>(rho: &B32) -> NttMatrix<K, L> {
+        NttMatrix::new(Array::from_fn(|r| {
+            NttVector::new(Array::from_fn(|s| {
+                let (r, s): (u16, u16) = (Truncate::truncate(r), Truncate::truncate(s));
+                rej_ntt_poly_from(aes_stream(rho, (r << 8) | s))
+            }))
+        }))
+    }
+
+    fn expand_s<K: ArraySize>(rhop: &B64, eta: Eta, base: usize) -> Vector<K> {
+        Vector::new(Array::from_fn(|r| {
+            rej_bounded_poly_from(eta, aes_stream(rhop, Truncate::truncate(r + base)))
+        }))
+    }
+
+    fn expand_mask<K: ArraySize, Gamma1: MaskSamplingSize>(rhopp: &B64, kappa: u16) -> Vector<K> {
+        Vector::new(Array::from_fn(|r| {
+            let r: u16 = Truncate::truncate(r);
+            let mut v = Secret::new(Array::<u8, Gamma1::SampleSize>::default());
+            aes_stream(rhopp, kappa + r)(&mut v);
+            Gamma1::unpack(&v)
+        }))
+    }
+}
+
+// The message representative of round 3, with no context string
+fn message_representative(tr: &B32, M: &[u8]) -> B64 {
+    ShakeXof.h().absorb(tr).absorb(M).squeeze_new()
+}
+
+/// A Dilithium round-3 signature, whose commitment hash `c_tilde` is always 32 bytes
+#[derive(Clone, PartialEq, Debug)]
+pub struct Signature<P: DilithiumParams> {
+    c_tilde: B32,
+    z: Vector<L<P>>,
+    h: Hint<Base<P>>,
+}
+
+impl<P: DilithiumParams> Signature<P> {
+    /// Encode the signature in a fixed-size byte array.
+    pub fn encode(&self) -> P::EncodedSignature {
+        let z = Base::<P>::encode_z(&self.z);
+        let h = self.h.bit_pack();
+
+        let mut enc = P::EncodedSignature::zeroed();
+        let (c_tilde_enc, rest) = enc.as_mut().split_at_mut(32);
+        let (z_enc, h_enc) = rest.split_at_mut(z.len());
+        c_tilde_enc.copy_from_slice(&self.c_tilde);
+        z_enc.copy_from_slice(&z);
+        h_enc.copy_from_slice(&h);
+        enc
+    }
+
+    /// Decode the signature from an appropriately sized byte array.
+    pub fn decode(enc: &P::EncodedSignature) -> Option<Self> {
+        let (c_tilde, rest) = enc.as_ref().split_at(32);
+        let (z, h) = rest.split_at(EncodedZ::<Base<P>>::default().len());
+
+        let c_tilde = B32::try_from(c_tilde).ok()?;
+        let z = Base::<P>::decode_z(<&EncodedZ<Base<P>>>::try_from(z).ok()?);
+        let h = Hint::bit_unpack(<&EncodedHint<Base<P>>>::try_from(h).ok()?)?;
+
+        if z.infinity_norm() >= Base::<P>::GAMMA1_MINUS_BETA {
+            return None;
+        }
+
+        Some(Self { c_tilde, z, h })
+    }
+}
+
+impl<'a, P: DilithiumParams> TryFrom<&'a [u8]> for Signature<P> {
+    type Error = Error;
+
+    fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut enc = P::EncodedSignature::zeroed();
+        if value.len() != enc.as_ref().len() {
+            return Err(Error::new());
+        }
+        enc.as_mut().copy_from_slice(value);
+        Self::decode(&enc).ok_or(Error::new())
+    }
+}
+
+// `TryInto` for the encoded signature comes from these, since a blanket implementation on
+// `P::EncodedSignature` would overlap with the one in `core`
+macro_rules! impl_signature_into_bytes {
+    ($($params:ty),*) => {
+        $(
+            impl From<Signature<$params>> for <$params as DilithiumParams>::EncodedSignature {
+                fn from(sig: Signature<$params>) -> Self {
+                    sig.encode()
+                }
+            }
+        )*
+    };
+}
+
+impl_signature_into_bytes!(Dilithium2, Dilithium3, Dilithium5);
+
+impl<P: DilithiumParams> signature::SignatureEncoding for Signature<P>
+where
+    P::EncodedSignature: From<Self>,
+{
+    type Repr = P::EncodedSignature;
+}
+
+/// A Dilithium round-3 key pair
+#[derive(Clone, Debug)]
+pub struct KeyPair<P: DilithiumParams, V: Variant = Shake> {
+    signing_key: SigningKey<P, V>,
+    verifying_key: VerifyingKey<P, V>,
+}
+
+impl<P: DilithiumParams, V: Variant> KeyPair<P, V> {
+    /// Generate a key pair from the specified RNG
+    #[cfg(feature = "rand_core")]
+    pub fn generate<R: CryptoRng + ?Sized>(rng: &mut R) -> Self {
+        let mut zeta = B32::default();
+        rng.fill_bytes(&mut zeta);
+        Self::from_seed(&zeta)
+    }
+
+    /// Deterministically generate a key pair from the 32-byte seed `zeta`, as the reference
+    /// implementation does with the output of its RNG
+    #[must_use]
+    pub fn from_seed(zeta: &B32) -> Self {
+        // Derive seeds, without the dimensions that ML-DSA includes
+        let mut h = ShakeXof.h().absorb(zeta);
+        let rho: B32 = h.squeeze_new();
+        let rhop = Secret::new(h.squeeze_new::<U64>());
+        let key = Secret::new(h.squeeze_new::<U32>());
+
+        // Sample private key components
+        let A_hat = V::expand_a::<K<P>, L<P>>(&rho);
+        let s1 = V::expand_s::<L<P>>(&rhop, <Base<P> as ParameterSet>::Eta::ETA, 0);
+        let s2 = V::expand_s::<K<P>>(&rhop, <Base<P> as ParameterSet>::Eta::ETA, L::<P>::USIZE);
+
+        // Compute derived values
+        let s1_hat = Secret::new(s1.ntt());
+        let (t1, t0) = compute_t1_t0::<Base<P>>(&A_hat, &s1_hat, &s2);
+
+        let verifying_key = VerifyingKey::new(rho, t1, Some(A_hat.clone()));
+        let signing_key = SigningKey::new(
+            rho,
+            key.take(),
+            verifying_key.tr.clone(),
+            s1,
+            s2,
+            t0,
+            Some(A_hat),
+        );
+
+        Self {
+            signing_key,
+            verifying_key,
+        }
+    }
+
+    /// The signing key of the key pair
+    pub fn signing_key(&self) -> &SigningKey<P, V> {
+        &self.signing_key
+    }
+
+    /// The verifying key of the key pair
+    pub fn verifying_key(&self) -> &VerifyingKey<P, V> {
+        &self.verifying_key
+    }
+}
+
+/// A Dilithium round-3 signing key
+#[derive(Clone, PartialEq)]
+pub struct SigningKey<P: DilithiumParams, V: Variant = Shake> {
+    rho: B32,
+    key: B32,
+    tr: B32,
+    s1: Vector<L<P>>,
+    s2: Vector<K<P>>,
+    t0: Vector<K<P>>,
+
+    // Derived values
+    s1_hat: NttVector<L<P>>,
+    s2_hat: NttVector<K<P>>,
+    t0_hat: NttVector<K<P>>,
+    A_hat: NttMatrix<K<P>, L<P>>,
+
+    // The maximum number of iterations of the rejection sampling loop, if any
+    rejection_budget: Option<usize>,
+
+    _variant: PhantomData<V>,
+}
+
+impl<P: DilithiumParams, V: Variant> fmt::Debug for SigningKey<P, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningKey").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<P: DilithiumParams, V: Variant> Drop for SigningKey<P, V> {
+    fn drop(&mut self) {
+        self.rho.zeroize();
+        self.key.zeroize();
+        self.tr.zeroize();
+        self.s1.zeroize();
+        self.s2.zeroize();
+        self.t0.zeroize();
+        self.s1_hat.zeroize();
+        self.s2_hat.zeroize();
+        self.t0_hat.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<P: DilithiumParams, V: Variant> ZeroizeOnDrop for SigningKey<P, V> {}
+
+impl<P: DilithiumParams, V: Variant> SigningKey<P, V> {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        rho: B32,
+        key: B32,
+        tr: B32,
+        s1: Vector<L<P>>,
+        s2: Vector<K<P>>,
+        t0: Vector<K<P>>,
+        A_hat: Option<NttMatrix<K<P>, L<P>>>,
+    ) -> Self {
+        let A_hat = A_hat.unwrap_or_else(|| V::expand_a::<K<P>, L<P>>(&rho));
+        let s1_hat = s1.ntt();
+        let s2_hat = s2.ntt();
+        let t0_hat = t0.ntt();
+
+        Self {
+            rho,
+            key,
+            tr,
+            s1,
+            s2,
+            t0,
+            s1_hat,
+            s2_hat,
+            t0_hat,
+            A_hat,
+            rejection_budget: None,
+            _variant: PhantomData,
+        }
+    }
+
+    /// Limit the rejection sampling loop of each signature to `iterations` iterations, as
+    /// [`crate::SigningKey::with_rejection_budget`] does.
+    #[must_use]
+    pub fn with_rejection_budget(mut self, iterations: usize) -> Self {
+        self.rejection_budget = Some(iterations);
+        self
+    }
+
+    /// The maximum number of iterations of the rejection sampling loop, if there is one
+    pub fn rejection_budget(&self) -> Option<usize> {
+        self.rejection_budget
+    }
+
+    /// Sign a message with the default, deterministic signing of round 3.
+    ///
+    /// # Errors
+    ///
+    /// This method will return [`SigningError::RejectionBudgetExceeded`] if the
+    /// [rejection budget](SigningKey::with_rejection_budget) runs out.
+    pub fn sign_deterministic(&self, M: &[u8]) -> Result<Signature<P>, SigningError> {
+        let mu = message_representative(&self.tr, M);
+        let rhopp = Secret::new(
+            ShakeXof
+                .h()
+                .absorb(&self.key)
+                .absorb(&mu)
+                .squeeze_new::<U64>(),
+        );
+        self.sign_mu(&mu, &rhopp)
+    }
+
+    /// Sign a message with the randomized signing of round 3, which draws the mask seed from
+    /// `rng`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return [`SigningError::RngFailure`] if it fails to get enough randomness,
+    /// and [`SigningError::RejectionBudgetExceeded`] if the
+    /// [rejection budget](SigningKey::with_rejection_budget) runs out.
+    #[cfg(feature = "rand_core")]
+    pub fn sign_randomized<R: TryCryptoRng + ?Sized>(
+        &self,
+        M: &[u8],
+        rng: &mut R,
+    ) -> Result<Signature<P>, SigningError> {
+        let mut rhopp = Secret::new(B64::default());
+        rng.try_fill_bytes(&mut rhopp)
+            .map_err(|_| SigningError::RngFailure)?;
+
+        let mu = message_representative(&self.tr, M);
+        self.sign_mu(&mu, &rhopp)
+    }
+
+    fn sign_mu(&self, mu: &B64, rhopp: &B64) -> Result<Signature<P>, SigningError> {
+        let (c_tilde, z, h) = sign_loop::<Base<P>, _, U32>(
+            &ShakeXof,
+            &self.A_hat,
+            &self.s1_hat,
+            &self.s2_hat,
+            &self.t0_hat,
+            mu,
+            self.rejection_budget,
+            |kappa| V::expand_mask::<L<P>, <Base<P> as ParameterSet>::Gamma1>(rhopp, kappa),
+        )?;
+        Ok(Signature { c_tilde, z, h })
+    }
+
+    /// Derive the verifying key from the signing key
+    pub fn verifying_key(&self) -> VerifyingKey<P, V> {
+        let (t1, _) = compute_t1_t0::<Base<P>>(&self.A_hat, &self.s1_hat, &self.s2);
+
+        VerifyingKey::new(self.rho.clone(), t1, Some(self.A_hat.clone()))
+    }
+
+    /// Encode the key in a fixed-size byte array.
+    pub fn encode(&self) -> P::EncodedSigningKey {
+        // The ML-DSA encoding, with `tr` padded to 64 bytes and the padding removed afterwards
+        let mut tr = B64::default();
+        tr[..32].copy_from_slice(&self.tr);
+        let padded = Base::<P>::concat_sk(
+            self.rho.clone(),
+            self.key.clone(),
+            tr,
+            Base::<P>::encode_s1(&self.s1),
+            Base::<P>::encode_s2(&self.s2),
+            Base::<P>::encode_t0(&self.t0),
+        );
+
+        let mut enc = P::EncodedSigningKey::zeroed();
+        enc.as_mut()[..96].copy_from_slice(&padded[..96]);
+        enc.as_mut()[96..].copy_from_slice(&padded[128..]);
+        enc
+    }
+
+    /// Decode the key from an appropriately sized byte array.
+    pub fn decode(enc: &P::EncodedSigningKey) -> Self {
+        let enc = enc.as_ref();
+        let mut padded = param::EncodedSigningKey::<Base<P>>::default();
+        padded[..96].copy_from_slice(&enc[..96]);
+        padded[128..].copy_from_slice(&enc[96..]);
+
+        let (rho, key, tr, s1_enc, s2_enc, t0_enc) = Base::<P>::split_sk(&padded);
+        Self::new(
+            rho.clone(),
+            key.clone(),
+            Array::from_fn(|i| tr[i]),
+            Base::<P>::decode_s1(s1_enc),
+            Base::<P>::decode_s2(s2_enc),
+            Base::<P>::decode_t0(t0_enc),
+            None,
+        )
+    }
+}
+
+/// The `Signer` implementation for `SigningKey` uses deterministic signing.
+impl<P: DilithiumParams, V: Variant> signature::Signer<Signature<P>> for SigningKey<P, V> {
+    fn try_sign(&self, msg: &[u8]) -> Result<Signature<P>, Error> {
+        Ok(self.sign_deterministic(msg)?)
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl<P: DilithiumParams, V: Variant> signature::RandomizedSigner<Signature<P>>
+    for SigningKey<P, V>
+{
+    fn try_sign_with_rng<R: TryCryptoRng + ?Sized>(
+        &self,
+        rng: &mut R,
+        msg: &[u8],
+    ) -> Result<Signature<P>, Error> {
+        Ok(self.sign_randomized(msg, rng)?)
+    }
+}
+
+impl<P: DilithiumParams, V: Variant> signature::Keypair for SigningKey<P, V> {
+    type VerifyingKey = VerifyingKey<P, V>;
+
+    fn verifying_key(&self) -> Self::VerifyingKey {
+        SigningKey::verifying_key(self)
+    }
+}
+
+/// A Dilithium round-3 verifying key
+#[derive(Clone, Debug)]
+pub struct VerifyingKey<P: DilithiumParams, V: Variant = Shake> {
+    rho: B32,
+    t1: Vector<K<P>>,
+
+    // Derived values
+    A_hat: NttMatrix<K<P>, L<P>>,
+    t1_2d_hat: NttVector<K<P>>,
+    tr: B32,
+
+    _variant: PhantomData<V>,
+}
+
+impl<P: DilithiumParams, V: Variant> PartialEq for VerifyingKey<P, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.rho == other.rho && self.t1 == other.t1
+    }
+}
+
+impl<P: DilithiumParams, V: Variant> VerifyingKey<P, V> {
+    fn new(rho: B32, t1: Vector<K<P>>, A_hat: Option<NttMatrix<K<P>, L<P>>>) -> Self {
+        let A_hat = A_hat.unwrap_or_else(|| V::expand_a::<K<P>, L<P>>(&rho));
+        let t1_2d_hat = (Elem::new(1 << 13) * &t1).ntt();
+        let tr: B32 = ShakeXof
+            .h()
+            .absorb(&Base::<P>::concat_vk(
+                rho.clone(),
+                Base::<P>::encode_t1(&t1),
+            ))
+            .squeeze_new();
+
+        Self {
+            rho,
+            t1,
+            A_hat,
+            t1_2d_hat,
+            tr,
+            _variant: PhantomData,
+        }
+    }
+
+    /// Verify a signature on a message.
+    pub fn verify_message(&self, M: &[u8], sigma: &Signature<P>) -> bool {
+        let mu = message_representative(&self.tr, M);
+        verify_mu::<Base<P>, _, U32>(
+            &ShakeXof,
+            &self.A_hat,
+            &self.t1_2d_hat,
+            &mu,
+            &sigma.c_tilde,
+            &sigma.z,
+            &sigma.h,
+        )
+    }
+
+    /// Encode the key in a fixed-size byte array.  This is the same as the ML-DSA encoding.
+    pub fn encode(&self) -> EncodedVerifyingKey<Base<P>> {
+        Base::<P>::concat_vk(self.rho.clone(), Base::<P>::encode_t1(&self.t1))
+    }
+
+    /// Decode the key from an appropriately sized byte array.
+    pub fn decode(enc: &EncodedVerifyingKey<Base<P>>) -> Self {
+        let (rho, t1_enc) = Base::<P>::split_vk(enc);
+        Self::new(rho.clone(), Base::<P>::decode_t1(t1_enc), None)
+    }
+}
+
+impl<P: DilithiumParams, V: Variant> signature::Verifier<Signature<P>> for VerifyingKey<P, V> {
+    fn verify(&self, msg: &[u8], signature: &Signature<P>) -> Result<(), Error> {
+        self.verify_message(msg, signature)
+            .then_some(())
+            .ok_or(Error::new())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{KeyGen, MlDsa65};
+
+    fn round_trip<P: DilithiumParams, V: Variant>(sizes: [usize; 3]) {
+        let kp = KeyPair::<P, V>::from_seed(&Array([3; 32]));
+        let sk = kp.signing_key();
+        let vk = kp.verifying_key();
+
+        let sig = sk.sign_deterministic(b"round 3").unwrap();
+        assert!(vk.verify_message(b"round 3", &sig));
+        assert!(!vk.verify_message(b"round 4", &sig));
+
+        // The sizes of the round-3 submission
+        let sk_enc = sk.encode();
+        let vk_enc = vk.encode();
+        let sig_enc = sig.encode();
+        assert_eq!(
+            [vk_enc.len(), sk_enc.as_ref().len(), sig_enc.as_ref().len()],
+            sizes
+        );
+
+        assert!(SigningKey::<P, V>::decode(&sk_enc) == *sk);
+        assert_eq!(VerifyingKey::<P, V>::decode(&vk_enc), *vk);
+        assert_eq!(sk.verifying_key(), *vk);
+        assert_eq!(Signature::<P>::decode(&sig_enc), Some(sig));
+    }
+
+    #[test]
+    fn round_trips() {
+        round_trip::<Dilithium2, Shake>([1312, 2528, 2420]);
+        round_trip::<Dilithium3, Shake>([1952, 4000, 3293]);
+        round_trip::<Dilithium5, Shake>([2592, 4864, 4595]);
+        round_trip::<Dilithium2, Aes>([1312, 2528, 2420]);
+        round_trip::<Dilithium3, Aes>([1952, 4000, 3293]);
+        round_trip::<Dilithium5, Aes>([2592, 4864, 4595]);
+    }
+
+    #[test]
+    fn variants_differ() {
+        let seed = Array([5; 32]);
+        let shake = KeyPair::<Dilithium2, Shake>::from_seed(&seed);
+        let aes = KeyPair::<Dilithium2, Aes>::from_seed(&seed);
+
+        // Both variants share `rho`, but expand it differently
+        assert_eq!(shake.verifying_key().rho, aes.verifying_key().rho);
+        assert_ne!(shake.verifying_key().encode(), aes.verifying_key().encode());
+    }
+
+    #[test]
+    fn aes_stream_layout() {
+        use aes::cipher::{BlockEncrypt, KeyInit};
+
+        let seed = [7u8; 64];
+        let cipher = Aes256::new((&seed[..32]).into());
+
+        let mut stream = aes_stream(&seed, 0x0102);
+        let mut output = [0u8; 48];
+        stream(&mut output[..5]);
+        stream(&mut output[5..]);
+
+        for (i, block) in output.chunks_exact(16).enumerate() {
+            let mut expected = [0u8; 16];
+            expected[..2].copy_from_slice(&[0x02, 0x01]);
+            expected[12..].copy_from_slice(&u32::try_from(i).unwrap().to_be_bytes());
+            let mut expected = expected.into();
+            cipher.encrypt_block(&mut expected);
+            assert_eq!(block, expected.as_slice());
+        }
+    }
+
+    #[test]
+    fn not_ml_dsa() {
+        // The same seed gives different keys, since ML-DSA also hashes the dimensions
+        let seed = Array([1; 32]);
+        let r3 = KeyPair::<Dilithium3>::from_seed(&seed);
+        let ml_dsa = MlDsa65::key_gen_internal(&seed);
+        assert_ne!(
+            r3.verifying_key().encode().as_slice(),
+            ml_dsa.verifying_key().encode().as_slice()
+        );
+
+        assert_eq!(
+            size_of::<<Dilithium3 as DilithiumParams>::EncodedSigningKey>() + 32,
+            crate::EncodedSigningKey::<MlDsa65>::default().len()
+        );
+    }
+}
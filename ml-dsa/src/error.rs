@@ -0,0 +1,90 @@
+use core::fmt;
+
+use signature::Error;
+
+/// The reason a signing operation failed.
+///
+/// The traits of the [`signature`] crate return it as the [source](core::error::Error::source) of
+/// their opaque [`Error`].  Without the `alloc` feature, that error carries no source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SigningError {
+    /// The context string is more than 255 bytes long.
+    ContextTooLong {
+        /// The length of the context string
+        len: usize,
+    },
+
+    /// The digest of a pre-hashed message is not the length of the output of its hash function.
+    InvalidDigestLength {
+        /// The output length of the hash function
+        expected: usize,
+        /// The length of the digest
+        len: usize,
+    },
+
+    /// The random number generator failed to produce randomness.
+    RngFailure,
+
+    /// The rejection sampling loop ran out of iterations before a candidate was accepted.  This
+    /// happens when the budget set by [`SigningKey::with_rejection_budget`] is spent, or, without
+    /// a budget, when the counter of the mask runs out, which FIPS 204 leaves to implementations.
+    ///
+    /// [`SigningKey::with_rejection_budget`]: crate::SigningKey::with_rejection_budget
+    RejectionBudgetExceeded {
+        /// The number of iterations that were tried
+        iterations: usize,
+    },
+
+    /// The signature failed the [fault checks](crate::SigningKey::with_fault_checks), or a key pair
+    /// failed its [pairwise consistency test](crate::KeyPair::pairwise_consistency_test).
+    FaultDetected,
+
+    /// The presignatures ran out before a candidate was accepted.
+    PresignaturesExhausted {
+        /// The number of candidates that were tried
+        tried: usize,
+    },
+
+    /// A presignature was made by another key.
+    ForeignPresignature,
+}
+
+impl fmt::Display for SigningError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ContextTooLong { len } => {
+                write!(f, "context string is {len} bytes long, the maximum is 255")
+            }
+            Self::InvalidDigestLength { expected, len } => {
+                write!(f, "digest is {len} bytes long, expected {expected}")
+            }
+            Self::RngFailure => f.write_str("random number generator failed"),
+            Self::RejectionBudgetExceeded { iterations } => write!(
+                f,
+                "rejection sampling found no signature in {iterations} iterations"
+            ),
+            Self::FaultDetected => {
+                f.write_str("ML-DSA signature failed verification; a fault may have occurred")
+            }
+            Self::PresignaturesExhausted { tried } => {
+                write!(f, "presignatures ran out after {tried} candidates")
+            }
+            Self::ForeignPresignature => f.write_str("presignature was made by another key"),
+        }
+    }
+}
+
+impl core::error::Error for SigningError {}
+
+impl From<SigningError> for Error {
+    #[cfg(feature = "alloc")]
+    fn from(err: SigningError) -> Self {
+        Error::from_source(err)
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn from(_: SigningError) -> Self {
+        Error::new()
+    }
+}
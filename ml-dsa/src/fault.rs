@@ -0,0 +1,98 @@
+// Fault injection for the tests of the fault checks
+
+/// A fault to inject into each signature before it is checked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Fault {
+    /// Flip a bit of the commitment hash
+    FlipCTilde,
+    /// Put z out of range, as if its bound had been skipped in the rejection loop
+    SkipZBound,
+}
+
+impl Fault {
+    pub(crate) fn inject<P: crate::MlDsaParams>(self, sigma: &mut crate::Signature<P>) {
+        match self {
+            Self::FlipCTilde => sigma.c_tilde[0] ^= 1,
+            Self::SkipZBound => {
+                sigma.z.0[0].0[0] = crate::algebra::Elem::new(P::GAMMA1_MINUS_BETA);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{KeyGen, KeyPair, MlDsa44, MlDsa65, MlDsaParams, SigningError, SigningKey};
+    use hybrid_array::Array;
+
+    fn check_faults<P: MlDsaParams>() {
+        let kp = P::key_gen_internal(&Array([3u8; 32]));
+        let msg = b"fault injection";
+
+        for fault in [Fault::FlipCTilde, Fault::SkipZBound] {
+            // Without checks, the faulty signature is released
+            let sk = kp.signing_key().clone().with_fault(fault);
+            let sig = sk.sign_deterministic(msg, &[]).unwrap();
+            assert!(!kp.verifying_key().verify_with_context(msg, &[], &sig));
+
+            // With checks, it is caught
+            let sk = sk.with_fault_checks();
+            assert!(sk.has_fault_checks());
+            assert_eq!(
+                sk.sign_deterministic(msg, &[]),
+                Err(SigningError::FaultDetected)
+            );
+
+            let mu = crate::MuBuilder::new(kp.verifying_key(), &[])
+                .unwrap()
+                .finalize();
+            assert_eq!(
+                sk.sign_mu_deterministic(&mu),
+                Err(SigningError::FaultDetected)
+            );
+        }
+
+        // Checks do not change correct signatures
+        let sk = kp.signing_key().clone().with_fault_checks();
+        assert_eq!(
+            sk.sign_deterministic(msg, b"ctx").unwrap(),
+            kp.signing_key().sign_deterministic(msg, b"ctx").unwrap()
+        );
+    }
+
+    #[test]
+    fn faults_are_detected() {
+        check_faults::<MlDsa44>();
+        check_faults::<MlDsa65>();
+    }
+
+    #[test]
+    fn pairwise_consistency_test() {
+        let seed = Array([4u8; 32]);
+        let kp = KeyPair::<MlDsa44>::from_seed(&seed);
+        assert!(kp.pairwise_consistency_test().is_ok());
+
+        let kp = kp.with_fault_checks().unwrap();
+        assert!(kp.signing_key().has_fault_checks());
+
+        // A signing key that does not match the verifying key fails the test
+        let other: SigningKey<MlDsa44> = KeyPair::from_seed(&Array([5u8; 32])).signing_key;
+        let mismatched = KeyPair {
+            signing_key: other,
+            ..KeyPair::from_seed(&seed)
+        };
+        assert_eq!(
+            mismatched.pairwise_consistency_test(),
+            Err(SigningError::FaultDetected)
+        );
+        assert!(mismatched.with_fault_checks().is_err());
+
+        // So does a faulty signer
+        let faulty = KeyPair {
+            signing_key: kp.signing_key.with_fault(Fault::FlipCTilde),
+            ..KeyPair::from_seed(&seed)
+        };
+        assert!(faulty.with_fault_checks().is_err());
+    }
+}
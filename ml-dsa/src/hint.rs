@@ -47,6 +47,16 @@ where
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl<P> zeroize::Zeroize for Hint<P>
+where
+    P: SignatureParams,
+{
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
 impl<P> Hint<P>
 where
     P: SignatureParams,
@@ -77,21 +87,21 @@ where
     }
 
     pub fn use_hint(&self, r: &Vector<P::K>) -> Vector<P::K> {
-        let hi = self.0.iter();
-        let ri = r.0.iter();
-
         Vector::new(
-            hi.zip(ri)
-                .map(|(hv, rv)| {
-                    let hvi = hv.iter();
-                    let rvi = rv.0.iter();
+            self.0
+                .iter()
+                .zip(r.0.iter())
+                .map(|(hv, rv)| Self::use_hint_row(hv, rv))
+                .collect(),
+        )
+    }
 
-                    Polynomial::new(
-                        hvi.zip(rvi)
-                            .map(|(&h, &r)| use_hint::<P::TwoGamma2>(h, r))
-                            .collect(),
-                    )
-                })
+    // UseHint on a single row, for verifiers that work a row at a time
+    pub fn use_hint_row(h: &Array<bool, U256>, r: &Polynomial) -> Polynomial {
+        Polynomial::new(
+            h.iter()
+                .zip(r.0.iter())
+                .map(|(&h, &r)| use_hint::<P::TwoGamma2>(h, r))
                 .collect(),
         )
     }
@@ -115,39 +125,50 @@ where
         y
     }
 
-    fn monotonic(a: &[usize]) -> bool {
+    fn monotonic(a: &[u8]) -> bool {
         a.iter().enumerate().all(|(i, x)| i == 0 || a[i - 1] <= *x)
     }
 
-    pub fn bit_unpack(y: &EncodedHint<P>) -> Option<Self> {
+    // The checks of Algorithm 21 HintBitUnpack, which reject malformed encodings
+    pub fn is_valid(y: &EncodedHint<P>) -> bool {
         let (indices, cuts) = P::split_hint(y);
-        let cuts: Array<usize, P::K> = cuts.iter().map(|x| usize::from(*x)).collect();
-
-        let indices: Array<usize, P::Omega> = indices.iter().map(|x| usize::from(*x)).collect();
-        let max_cut: usize = cuts.iter().copied().max().unwrap();
-        if !Self::monotonic(&cuts)
+        let max_cut = usize::from(cuts.iter().copied().max().unwrap());
+        if !Self::monotonic(cuts)
             || max_cut > indices.len()
-            || indices[max_cut..].iter().copied().max().unwrap_or(0) > 0
+            || indices[max_cut..].iter().any(|&x| x > 0)
         {
-            return None;
+            return false;
         }
 
-        let mut h = Self::default();
         let mut start = 0;
-        for (i, &end) in cuts.iter().enumerate() {
-            let indices = &indices[start..end];
-
-            if !Self::monotonic(indices) {
-                return None;
+        for end in cuts.iter().copied().map(usize::from) {
+            if !Self::monotonic(&indices[start..end]) {
+                return false;
             }
+            start = end;
+        }
 
-            for &j in indices {
-                h.0[i][j] = true;
-            }
+        true
+    }
 
-            start = end;
+    // Row `i` of an encoded hint that `is_valid` accepts
+    pub fn unpack_row(y: &EncodedHint<P>, i: usize) -> Array<bool, U256> {
+        let (indices, cuts) = P::split_hint(y);
+        let start = if i == 0 { 0 } else { usize::from(cuts[i - 1]) };
+        let end = usize::from(cuts[i]);
+
+        let mut row = Array::<bool, U256>::default();
+        for &j in &indices[start..end] {
+            row[usize::from(j)] = true;
+        }
+        row
+    }
+
+    pub fn bit_unpack(y: &EncodedHint<P>) -> Option<Self> {
+        if !Self::is_valid(y) {
+            return None;
         }
 
-        Some(h)
+        Some(Self(Array::from_fn(|i| Self::unpack_row(y, i))))
     }
 }
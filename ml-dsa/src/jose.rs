@@ -0,0 +1,251 @@
+//! ML-DSA keys and signatures in JOSE, as specified by the IETF draft "ML-DSA for JOSE and COSE"
+//! (draft-ietf-cose-dilithium).
+//!
+//! The draft names the algorithms `ML-DSA-44`, `ML-DSA-65` and `ML-DSA-87`, and defines the
+//! Algorithm Key Pair key type, `AKP`, for their keys.  A JWK of this type carries the algorithm
+//! of the key as `alg`, the encoded verifying key as `pub` and, for a private key, the seed ξ as
+//! `priv`, each in unpadded base64url:
+//!
+//! ```json
+//! {"kty":"AKP","alg":"ML-DSA-44","pub":"...","priv":"..."}
+//! ```
+//!
+//! A JWS signature is the ML-DSA signature of the JWS signing input, with an empty context string.
+//! Only the compact serialization is supported.  The signatures made here have a protected header
+//! that names the algorithm and nothing else, and verification rejects headers with a `crit`
+//! member, since no extensions are understood.
+//!
+//! The draft only covers standard ML-DSA, so these conversions are only available for keys with
+//! the [`ShakeXof`](crate::ShakeXof) backend.
+//!
+//! ```
+//! use ml_dsa::{KeyPair, MlDsa65, VerifyingKey};
+//!
+//! let kp = KeyPair::<MlDsa65>::from_seed(&[7; 32].into());
+//! let jws = kp.signing_key().sign_jws_deterministic(b"Hello world").unwrap();
+//!
+//! let vk = VerifyingKey::<MlDsa65>::from_jwk(&kp.verifying_key().to_jwk()).unwrap();
+//! assert_eq!(vk.verify_jws(&jws).unwrap(), b"Hello world");
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use serde_json::{Map, Value};
+use signature::Error;
+
+#[cfg(feature = "rand_core")]
+use rand_core::TryCryptoRng;
+
+use crate::param::{EncodedSignature, EncodedVerifyingKey, MlDsaParams};
+use crate::secret::Secret;
+use crate::util::B32;
+use crate::{
+    KeyPair, MlDsa44, MlDsa65, MlDsa87, Signature, SigningError, SigningKey, VerifyingKey,
+};
+
+/// The key type of ML-DSA keys, for the `kty` member of a JWK
+pub const KEY_TYPE: &str = "AKP";
+
+/// A parameter set with a JOSE algorithm name
+pub trait JoseAlgorithm: MlDsaParams {
+    /// The name of the algorithm, for the `alg` member of JWKs and JWS headers
+    const ALGORITHM: &'static str;
+}
+
+impl JoseAlgorithm for MlDsa44 {
+    const ALGORITHM: &'static str = "ML-DSA-44";
+}
+
+impl JoseAlgorithm for MlDsa65 {
+    const ALGORITHM: &'static str = "ML-DSA-65";
+}
+
+impl JoseAlgorithm for MlDsa87 {
+    const ALGORITHM: &'static str = "ML-DSA-87";
+}
+
+impl<P: JoseAlgorithm> VerifyingKey<P> {
+    /// Encode the key as a JWK
+    #[must_use]
+    pub fn to_jwk(&self) -> String {
+        to_jwk::<P>(&self.encode(), None)
+    }
+
+    /// Decode a key from a JWK.  A `priv` member is ignored.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an opaque error if the JWK is not an `AKP` key of this algorithm.
+    pub fn from_jwk(jwk: &str) -> Result<Self, Error> {
+        let jwk = parse_jwk::<P>(jwk)?;
+        Ok(Self::decode(&verifying_key::<P>(&jwk)?))
+    }
+
+    /// Verify a JWS in the compact serialization, and return its payload.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an opaque error if the JWS is malformed, if its header names
+    /// another algorithm or has a `crit` member, or if the signature is not valid.
+    pub fn verify_jws(&self, jws: &str) -> Result<Vec<u8>, Error> {
+        let mut parts = jws.split('.');
+        let (Some(protected), Some(payload), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(Error::new());
+        };
+
+        let header: Map<String, Value> =
+            serde_json::from_slice(&decode(protected)?).map_err(|_| Error::new())?;
+        if header.get("alg").and_then(Value::as_str) != Some(P::ALGORITHM)
+            || header.contains_key("crit")
+        {
+            return Err(Error::new());
+        }
+
+        let signature = EncodedSignature::<P>::try_from(decode(signature)?.as_slice())
+            .map_err(|_| Error::new())?;
+        let signature = Signature::decode(&signature).ok_or_else(Error::new)?;
+
+        // The signing input is everything up to the second `.`
+        let input = &jws[..protected.len() + 1 + payload.len()];
+        if !self.verify_with_context(input.as_bytes(), &[], &signature) {
+            return Err(Error::new());
+        }
+
+        decode(payload)
+    }
+}
+
+impl<P: JoseAlgorithm> KeyPair<P> {
+    /// Encode the key pair as a JWK, with its seed as `priv`.  The JWK is as secret as the key.
+    #[must_use]
+    pub fn to_jwk(&self) -> String {
+        to_jwk::<P>(&self.verifying_key.encode(), Some(&self.seed))
+    }
+
+    /// Decode a key pair from a JWK with a `priv` member.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an opaque error if the JWK is not a private `AKP` key of this
+    /// algorithm, or if its `pub` member is not the verifying key of its seed.
+    pub fn from_jwk(jwk: &str) -> Result<Self, Error> {
+        let mut jwk = parse_jwk::<P>(jwk)?;
+        let Some(Value::String(seed)) = jwk.remove("priv") else {
+            return Err(Error::new());
+        };
+        let seed = Secret::new(decode(&Secret::new(seed))?);
+        let seed = Secret::new(B32::try_from(seed.as_slice()).map_err(|_| Error::new())?);
+
+        let kp = Self::from_seed(&seed);
+        if verifying_key::<P>(&jwk)? != kp.verifying_key.encode() {
+            return Err(Error::new());
+        }
+
+        Ok(kp)
+    }
+}
+
+impl<P: JoseAlgorithm> SigningKey<P> {
+    /// Sign `payload` as a JWS in the compact serialization, with the deterministic variant of
+    /// ML-DSA.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the [rejection budget](SigningKey::with_rejection_budget) runs out,
+    /// or if [fault checks](SigningKey::with_fault_checks) reject the signature.
+    pub fn sign_jws_deterministic(&self, payload: &[u8]) -> Result<String, SigningError> {
+        let input = signing_input::<P>(payload);
+        let signature = self.sign_deterministic(input.as_bytes(), &[])?;
+        Ok(compact(input, &signature))
+    }
+
+    /// Sign `payload` as a JWS in the compact serialization, with the hedged variant of ML-DSA.
+    ///
+    /// # Errors
+    ///
+    /// This method will return [`SigningError::RngFailure`] if it fails to get enough randomness.
+    /// It also fails if the [rejection budget](SigningKey::with_rejection_budget) runs out, or if
+    /// [fault checks](SigningKey::with_fault_checks) reject the signature.
+    #[cfg(feature = "rand_core")]
+    pub fn sign_jws_randomized<R: TryCryptoRng + ?Sized>(
+        &self,
+        payload: &[u8],
+        rng: &mut R,
+    ) -> Result<String, SigningError> {
+        let input = signing_input::<P>(payload);
+        let signature = self.sign_randomized(input.as_bytes(), &[], rng)?;
+        Ok(compact(input, &signature))
+    }
+}
+
+fn to_jwk<P: JoseAlgorithm>(vk: &EncodedVerifyingKey<P>, seed: Option<&B32>) -> String {
+    // The members are names and base64url, which need no escaping.  The JWK is written into a
+    // string that is long enough from the start, so that no copies of the seed are left behind.
+    let len = Base64UrlUnpadded::encoded_len(vk) + Base64UrlUnpadded::encoded_len(&B32::default());
+    let mut jwk = String::with_capacity(len + 64);
+    jwk.push_str(r#"{"kty":""#);
+    jwk.push_str(KEY_TYPE);
+    jwk.push_str(r#"","alg":""#);
+    jwk.push_str(P::ALGORITHM);
+    jwk.push_str(r#"","pub":""#);
+    jwk.push_str(&Base64UrlUnpadded::encode_string(vk));
+    if let Some(seed) = seed {
+        jwk.push_str(r#"","priv":""#);
+        jwk.push_str(&Secret::new(Base64UrlUnpadded::encode_string(seed)));
+    }
+    jwk.push_str(r#""}"#);
+    jwk
+}
+
+// Parse a JWK, and check that it is an `AKP` key of the algorithm `P`
+fn parse_jwk<P: JoseAlgorithm>(jwk: &str) -> Result<Map<String, Value>, Error> {
+    let jwk: Map<String, Value> = serde_json::from_str(jwk).map_err(|_| Error::new())?;
+    if jwk.get("kty").and_then(Value::as_str) != Some(KEY_TYPE)
+        || jwk.get("alg").and_then(Value::as_str) != Some(P::ALGORITHM)
+    {
+        return Err(Error::new());
+    }
+
+    Ok(jwk)
+}
+
+fn verifying_key<P: MlDsaParams>(
+    jwk: &Map<String, Value>,
+) -> Result<EncodedVerifyingKey<P>, Error> {
+    EncodedVerifyingKey::<P>::try_from(member(jwk, "pub")?.as_slice()).map_err(|_| Error::new())
+}
+
+// The bytes of a member of a JWK
+fn member(jwk: &Map<String, Value>, name: &str) -> Result<Vec<u8>, Error> {
+    decode(
+        jwk.get(name)
+            .and_then(Value::as_str)
+            .ok_or_else(Error::new)?,
+    )
+}
+
+fn decode(part: &str) -> Result<Vec<u8>, Error> {
+    Base64UrlUnpadded::decode_vec(part).map_err(|_| Error::new())
+}
+
+// The protected header and payload of a JWS, joined by a `.`
+fn signing_input<P: JoseAlgorithm>(payload: &[u8]) -> String {
+    let mut header = String::from(r#"{"alg":""#);
+    header.push_str(P::ALGORITHM);
+    header.push_str(r#""}"#);
+
+    let mut input = Base64UrlUnpadded::encode_string(header.as_bytes());
+    input.push('.');
+    input.push_str(&Base64UrlUnpadded::encode_string(payload));
+    input
+}
+
+fn compact<P: MlDsaParams>(mut input: String, signature: &Signature<P>) -> String {
+    input.push('.');
+    input.push_str(&Base64UrlUnpadded::encode_string(&signature.encode()));
+    input
+}
@@ -0,0 +1,155 @@
+// Four SHAKE sponges computed in lockstep.
+//
+// `ExpandA`, `ExpandS` and `ExpandMask` hash the same seed with different indices, so their
+// sponges are independent and can share one permutation call.  The state is interleaved, with the
+// four lanes of each word next to each other, which is the layout the AVX2 permutation wants.  On
+// other targets each lane is copied out and permuted on its own with the `keccak` crate.
+//
+// Only whole blocks are squeezed.  Each lane produces exactly the stream that a single SHAKE
+// state would, so a caller that consumes its lane byte by byte gets the same result as the
+// sequential code.
+
+use crate::avx2::Avx2;
+use crate::secret::wipe;
+
+/// The rate of SHAKE128, in bytes
+pub(crate) const SHAKE128_RATE: usize = 168;
+
+/// The rate of SHAKE256, in bytes
+pub(crate) const SHAKE256_RATE: usize = 136;
+
+/// The SHAKE domain separator and the first bit of the padding
+const SHAKE_PAD: u8 = 0x1f;
+
+/// Four SHAKE states with a rate of `RATE` bytes, which must absorb inputs of equal length
+pub(crate) struct ShakeX4<const RATE: usize> {
+    state: [[u64; 4]; 25],
+    // The byte offset of the next input within the current block
+    offset: usize,
+    squeezing: bool,
+}
+
+impl<const RATE: usize> ShakeX4<RATE> {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: [[0; 4]; 25],
+            offset: 0,
+            squeezing: false,
+        }
+    }
+
+    /// Absorb one input into each lane.  The inputs must have the same length.
+    #[must_use]
+    pub(crate) fn absorb(mut self, inputs: [&[u8]; 4]) -> Self {
+        assert!(!self.squeezing);
+        let len = inputs[0].len();
+        assert!(inputs.iter().all(|input| input.len() == len));
+
+        for i in 0..len {
+            for (lane, input) in inputs.iter().enumerate() {
+                self.xor_byte(lane, self.offset, input[i]);
+            }
+
+            self.offset += 1;
+            if self.offset == RATE {
+                self.permute();
+                self.offset = 0;
+            }
+        }
+
+        self
+    }
+
+    /// Squeeze the next block of output from each lane
+    pub(crate) fn squeeze_block(&mut self) -> [[u8; RATE]; 4] {
+        if !self.squeezing {
+            for lane in 0..4 {
+                self.xor_byte(lane, self.offset, SHAKE_PAD);
+                self.xor_byte(lane, RATE - 1, 0x80);
+            }
+            self.squeezing = true;
+        }
+
+        self.permute();
+        core::array::from_fn(|lane| {
+            core::array::from_fn(|i| self.state[i >> 3][lane].to_le_bytes()[i & 7])
+        })
+    }
+
+    fn xor_byte(&mut self, lane: usize, offset: usize, byte: u8) {
+        self.state[offset >> 3][lane] ^= u64::from(byte) << ((offset & 7) << 3);
+    }
+
+    fn permute(&mut self) {
+        if let Some(avx2) = Avx2::detect() {
+            avx2.keccak_f1600_x4(&mut self.state);
+            return;
+        }
+
+        keccak_f1600_x4_scalar(&mut self.state);
+    }
+}
+
+// The sponges absorb secret seeds when sampling the secret vectors and the mask
+impl<const RATE: usize> Drop for ShakeX4<RATE> {
+    fn drop(&mut self) {
+        wipe(&mut self.state);
+    }
+}
+
+pub(crate) fn keccak_f1600_x4_scalar(state: &mut [[u64; 4]; 25]) {
+    for lane in 0..4 {
+        let mut words = core::array::from_fn(|i| state[i][lane]);
+        ::keccak::f1600(&mut words);
+        for (word, value) in state.iter_mut().zip(words) {
+            word[lane] = value;
+        }
+        wipe(&mut words);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sha3::digest::{ExtendableOutput, Update, XofReader};
+    use sha3::{Shake128, Shake256};
+
+    fn check<D: Default + Update + ExtendableOutput, const RATE: usize>() {
+        let data: [[u8; 4 * SHAKE128_RATE]; 4] = core::array::from_fn(|lane| {
+            core::array::from_fn(|i| u8::try_from((i * 7 + lane * 31) & 0xff).unwrap())
+        });
+
+        // Lengths on either side of a block boundary, so that padding lands in a fresh block
+        for len in [0, 1, 34, 66, RATE - 1, RATE, RATE + 1, 3 * RATE + 7] {
+            let inputs: [&[u8]; 4] = core::array::from_fn(|lane| &data[lane][..len]);
+
+            // Absorb in two parts, to check that the offset carries over
+            let split = len >> 1;
+            let mut x4 = ShakeX4::<RATE>::new()
+                .absorb(inputs.map(|input| &input[..split]))
+                .absorb(inputs.map(|input| &input[split..]));
+            let blocks = [x4.squeeze_block(), x4.squeeze_block(), x4.squeeze_block()];
+
+            for (lane, input) in inputs.iter().enumerate() {
+                let mut expected = [0u8; 3 * SHAKE128_RATE];
+                let mut hasher = D::default();
+                hasher.update(input);
+                hasher.finalize_xof().read(&mut expected[..3 * RATE]);
+
+                for (block, expected) in blocks.iter().zip(expected.chunks_exact(RATE)) {
+                    assert_eq!(block[lane], expected, "len {len} lane {lane}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn matches_shake128() {
+        check::<Shake128, SHAKE128_RATE>();
+    }
+
+    #[test]
+    fn matches_shake256() {
+        check::<Shake256, SHAKE256_RATE>();
+    }
+}
@@ -0,0 +1,81 @@
+// A value derived from a key, computed the first time it is needed.
+//
+// With `std`, the value lives in a `OnceLock`, so that keys remain `Send` and `Sync` and threads
+// sharing a key compute it once.  Without `std` there is no thread-safe cell to put it in, so it is
+// computed up front, when the key is created.
+//
+// Either way the value is computed by `Derive::derive`, so the deferred and the eager paths cannot
+// disagree about how.
+
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+/// A value computed from the components `C` of a key
+pub(crate) trait Derive<C> {
+    fn derive(from: &C) -> Self;
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Lazy<T> {
+    #[cfg(feature = "std")]
+    cell: OnceLock<T>,
+    #[cfg(not(feature = "std"))]
+    value: T,
+}
+
+impl<T> Lazy<T> {
+    /// A value derived from `from`.  With `std`, it is derived by the first call to `get`, which
+    /// must be passed the same components.
+    #[cfg_attr(feature = "std", allow(unused_variables))]
+    pub(crate) fn new<C>(from: &C) -> Self
+    where
+        T: Derive<C>,
+    {
+        #[cfg(feature = "std")]
+        {
+            Self {
+                cell: OnceLock::new(),
+            }
+        }
+
+        #[cfg(not(feature = "std"))]
+        Self {
+            value: T::derive(from),
+        }
+    }
+
+    /// A value computed by `init` right away.  The value is written straight into the cell, since
+    /// for large keys the copies made by `OnceLock::from` add up to a lot of stack.
+    pub(crate) fn ready(init: impl FnOnce() -> T) -> Self {
+        #[cfg(feature = "std")]
+        {
+            let cell = OnceLock::new();
+            cell.get_or_init(init);
+            Self { cell }
+        }
+
+        #[cfg(not(feature = "std"))]
+        Self { value: init() }
+    }
+
+    /// Whether the value has been computed
+    #[cfg(all(test, feature = "std"))]
+    pub(crate) fn is_ready(&self) -> bool {
+        self.cell.get().is_some()
+    }
+
+    /// The value, derived from `from` if this is the first call
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    pub(crate) fn get<C>(&self, from: &C) -> &T
+    where
+        T: Derive<C>,
+    {
+        #[cfg(feature = "std")]
+        {
+            self.cell.get_or_init(|| T::derive(from))
+        }
+
+        #[cfg(not(feature = "std"))]
+        &self.value
+    }
+}
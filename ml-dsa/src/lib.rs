@@ -1,5 +1,4 @@
-// CD: Removed no_std to allow for logging
-// #![no_std]
+#![no_std]
 #![doc = include_str!("../README.md")]
 #![doc(
     html_logo_url = "https://raw.githubusercontent.com/RustCrypto/meta/master/logo.svg",
@@ -32,7 +31,19 @@
 //! # }
 //! ```
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
 mod algebra;
+mod avx2;
+#[cfg(feature = "std")]
+mod cache;
+mod compact;
+#[cfg(feature = "cose")]
+pub mod cose;
 /// SHAKE hash function implementation
 pub mod crypto;
 /// AES-based hash function implementation
@@ -41,13 +52,35 @@ pub mod crypto_aes;
 /// Niave BLAKE3 hash function implementation
 pub mod crypto_blake3_niave;
 /// Optimized BLAKE3 hash function implementation
+#[cfg(feature = "std")]
 pub mod crypto_blake3_optimized;
+#[cfg(feature = "derive")]
+pub mod derive;
+pub mod dilithium_r3;
 mod encode;
+mod error;
+#[cfg(test)]
+mod fault;
 mod hint;
+#[cfg(feature = "jose")]
+pub mod jose;
+mod keccak_x4;
+mod lazy;
+mod mu;
 mod ntt;
+mod observe;
 mod param;
+/// Hash functions for HashML-DSA
+pub mod prehash;
+#[cfg(feature = "rand_core")]
+mod presign;
+#[cfg(feature = "pkcs8")]
+mod private_key;
 mod sampling;
+mod secret;
+mod serialization;
 mod util;
+mod xof;
 
 // TODO(RLB) Move module to an independent crate shared with ml_kem
 mod module_lattice;
@@ -69,7 +102,7 @@ use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[cfg(feature = "pkcs8")]
 use {
-    const_oid::db::fips204,
+    const_oid::{ObjectIdentifier, db::fips204},
     pkcs8::{
         AlgorithmIdentifierRef, PrivateKeyInfoRef,
         der::{self, AnyRef},
@@ -78,6 +111,7 @@ use {
             SubjectPublicKeyInfoRef,
         },
     },
+    subtle::ConstantTimeEq,
 };
 
 #[cfg(all(feature = "alloc", feature = "pkcs8"))]
@@ -88,31 +122,69 @@ use pkcs8::{
 };
 
 use crate::algebra::{AlgebraExt, Elem, NttMatrix, NttVector, Truncate, Vector};
-// CD: Added feature flag to allow for different hash functions
-#[cfg(all(
-    feature = "hash-shake",
-    not(feature = "hash-blake3-niave"),
-    not(feature = "hash-blake3-optimized"),
-    not(feature = "hash-aes")
-))]
-use crate::crypto::H;
-#[cfg(feature = "hash-aes")]
-use crate::crypto_aes::H;
-#[cfg(feature = "hash-blake3-niave")]
-use crate::crypto_blake3_niave::H;
-#[cfg(feature = "hash-blake3-optimized")]
-use crate::crypto_blake3_optimized::H;
 use crate::hint::Hint;
+use crate::lazy::{Derive, Lazy};
+use crate::module_lattice::encode::ArraySize;
 use crate::ntt::{Ntt, NttInverse};
-use crate::param::{ParameterSet, QMinus1, SamplingSize, SpecQ};
+use crate::observe::PhaseTimer;
+use crate::param::{EncodedW1, SamplingSize, SpecQ, Validate};
+#[cfg(feature = "pkcs8")]
+use crate::private_key::PrivateKeyChoice;
 use crate::sampling::{expand_a, expand_mask, expand_s, sample_in_ball};
+use crate::secret::Secret;
 use core::fmt;
 
-pub use crate::param::{EncodedSignature, EncodedSigningKey, EncodedVerifyingKey, MlDsaParams};
+#[cfg(feature = "std")]
+pub use crate::cache::VerifyingKeyCache;
+pub use crate::compact::{CompactSigningKey, CompactVerifyingKey};
+pub use crate::crypto::ShakeXof;
+pub use crate::crypto_aes::{AesParams, AesXof};
+pub use crate::crypto_blake3_niave::Blake3Xof;
+#[cfg(feature = "std")]
+pub use crate::crypto_blake3_optimized::Blake3OptimizedXof;
+pub use crate::error::SigningError;
+pub use crate::mu::MuBuilder;
+#[cfg(feature = "tracing")]
+pub use crate::observe::TracingObserver;
+pub use crate::observe::{Observed, ObservedState, Observer, Phase, Role};
+pub use crate::param::{
+    EncodedSignature, EncodedSigningKey, EncodedVerifyingKey, MlDsaParams, ParameterSet, QMinus1,
+};
+pub use crate::prehash::{HashSignature, Prehash};
+#[cfg(feature = "rand_core")]
+pub use crate::presign::Presignature;
+#[cfg(feature = "pkcs8")]
+pub use crate::private_key::PrivateKeyFormat;
 pub use crate::util::{B32, B64, B256};
+pub use crate::xof::{PRIVATE_ARC, Xof, XofState};
+pub use hybrid_array::typenum;
 pub use signature::{self, Error, MultipartSigner, MultipartVerifier};
 
-/// An ML-DSA signature
+/// The PKCS#8 and SPKI traits that keys implement.  With the `pem` feature, they include
+/// conversions to and from PEM:
+///
+/// ```
+/// # #[cfg(feature = "pem")]
+/// # {
+/// use ml_dsa::{KeyGen, KeyPair, MlDsa65};
+/// use ml_dsa::pkcs8::{DecodePrivateKey, EncodePrivateKey, LineEnding};
+///
+/// let kp = MlDsa65::key_gen_internal(&Default::default());
+/// let pem = kp.to_pkcs8_pem(LineEnding::LF).unwrap();
+/// let decoded = KeyPair::<MlDsa65>::from_pkcs8_pem(&pem).unwrap();
+/// assert_eq!(decoded.verifying_key(), kp.verifying_key());
+/// # }
+/// ```
+#[cfg(feature = "pkcs8")]
+pub use pkcs8;
+
+use signature::hazmat::{PrehashSigner, PrehashVerifier};
+
+#[cfg(feature = "rand_core")]
+use signature::hazmat::RandomizedPrehashSigner;
+
+/// An ML-DSA signature.  Its algorithm identifier is that of the key that made it, given by the
+/// key's `SignatureAlgorithmIdentifier` impl.
 #[derive(Clone, PartialEq, Debug)]
 pub struct Signature<P: MlDsaParams> {
     c_tilde: Array<u8, P::Lambda>,
@@ -133,6 +205,7 @@ impl<P: MlDsaParams> Signature<P> {
     /// Decode the signature from an appropriately sized byte array.
     // Algorithm 27 sigDecode
     pub fn decode(enc: &EncodedSignature<P>) -> Option<Self> {
+        let () = P::VALID;
         let (c_tilde, z, h) = P::split_sig(enc);
 
         let c_tilde = c_tilde.clone();
@@ -175,22 +248,23 @@ impl<P: MlDsaParams> SignatureBitStringEncoding for Signature<P> {
     }
 }
 
-#[cfg(feature = "pkcs8")]
-impl<P> AssociatedAlgorithmIdentifier for Signature<P>
-where
-    P: MlDsaParams,
-    P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
-{
-    type Params = AnyRef<'static>;
+// A signature does not record the backend that made it, so it has no algorithm identifier of its
+// own: take it from the key, through `SignatureAlgorithmIdentifier`.
 
-    const ALGORITHM_IDENTIFIER: AlgorithmIdentifierRef<'static> = P::ALGORITHM_IDENTIFIER;
+// The context string of ML-DSA.Sign and HashML-DSA.Sign is at most 255 bytes long
+fn check_context(ctx: &[u8]) -> Result<(), SigningError> {
+    if ctx.len() > 255 {
+        return Err(SigningError::ContextTooLong { len: ctx.len() });
+    }
+
+    Ok(())
 }
 
 // This method takes a slice of slices so that we can accommodate the varying calculations (direct
 // for test vectors, 0... for sign/sign_deterministic, 1... for the pre-hashed version) without
 // having to allocate memory for components.
-fn message_representative(tr: &[u8], Mp: &[&[&[u8]]]) -> B64 {
-    let mut h = H::default().absorb(tr);
+fn message_representative<X: Xof>(xof: &X, tr: &[u8], Mp: &[&[&[u8]]]) -> B64 {
+    let mut h = xof.h().absorb(tr);
 
     for m in Mp.iter().copied().flatten() {
         h = h.absorb(m);
@@ -199,38 +273,320 @@ fn message_representative(tr: &[u8], Mp: &[&[&[u8]]]) -> B64 {
     h.squeeze_new()
 }
 
+// The components of a signature, with a `c_tilde` of `Lambda` bytes
+type SignatureParts<P, Lambda> = (Array<u8, Lambda>, Vector<<P as ParameterSet>::L>, Hint<P>);
+
+// One iteration of the rejection sampling loop of ML-DSA.Sign_internal, up to the point where it
+// needs the message: the mask `y`, the commitment `w = A·y` and the encoding of its high bits.
+struct Commitment<P: MlDsaParams> {
+    y: Vector<P::L>,
+    w: Vector<P::K>,
+    w1_tilde: EncodedW1<P>,
+}
+
+#[cfg(feature = "zeroize")]
+impl<P: MlDsaParams> Drop for Commitment<P> {
+    fn drop(&mut self) {
+        self.y.zeroize();
+        self.w.zeroize();
+        self.w1_tilde.zeroize();
+    }
+}
+
+impl<P: MlDsaParams> Commitment<P> {
+    // Algorithm 7 ML-DSA.Sign_internal, lines 12-15
+    fn new(A_hat: &NttMatrix<P::K, P::L>, y: Vector<P::L>) -> Self {
+        let y_hat = Secret::new(y.ntt());
+        let w_hat = Secret::new(A_hat * &*y_hat);
+        let w = w_hat.ntt_inverse();
+        let w1 = Secret::new(w.high_bits::<P::TwoGamma2>());
+        let w1_tilde = P::encode_w1(&w1);
+        Self { y, w, w1_tilde }
+    }
+
+    // The rest of the iteration, which returns the components of the signature of `mu`, or `None`
+    // if the candidate is rejected.
+    // Algorithm 7 ML-DSA.Sign_internal, lines 15-31
+    fn respond<X: Xof, Lambda: ArraySize>(
+        &self,
+        xof: &X,
+        s1_hat: &NttVector<P::L>,
+        s2_hat: &NttVector<P::K>,
+        t0_hat: &NttVector<P::K>,
+        mu: &B64,
+    ) -> Option<SignatureParts<P, Lambda>> {
+        // Everything here reveals the secret key if the candidate is rejected, and is wiped.  The
+        // intermediate values are scoped, so that their stack space can be reused.
+        let Self { y, w, w1_tilde } = self;
+        let c_tilde = Secret::new(xof.h().absorb(mu).absorb(w1_tilde).squeeze_new::<Lambda>());
+        let c_hat = {
+            let c = Secret::new(sample_in_ball(xof, &c_tilde, P::TAU));
+            Secret::new(c.ntt())
+        };
+
+        let z = {
+            let cs1_hat = Secret::new(&*c_hat * s1_hat);
+            let cs1 = Secret::new(cs1_hat.ntt_inverse());
+            Secret::new(y + &cs1)
+        };
+        let w_cs2 = {
+            let cs2_hat = Secret::new(&*c_hat * s2_hat);
+            let cs2 = Secret::new(cs2_hat.ntt_inverse());
+            Secret::new(w - &cs2)
+        };
+
+        let r0_norm = Secret::new(w_cs2.low_bits::<P::TwoGamma2>()).infinity_norm();
+        if z.infinity_norm() >= P::GAMMA1_MINUS_BETA || r0_norm >= P::GAMMA2_MINUS_BETA {
+            return None;
+        }
+
+        let ct0 = {
+            let ct0_hat = Secret::new(&*c_hat * t0_hat);
+            Secret::new(ct0_hat.ntt_inverse())
+        };
+        let h = {
+            let minus_ct0 = Secret::new(-&*ct0);
+            let w_cs2_ct0 = Secret::new(&*w_cs2 + &*ct0);
+            Secret::new(Hint::<P>::new(&minus_ct0, &w_cs2_ct0))
+        };
+
+        if ct0.infinity_norm() >= P::Gamma2::U32 || h.hamming_weight() > P::Omega::USIZE {
+            return None;
+        }
+
+        let z = z.mod_plus_minus::<SpecQ>();
+        Some((c_tilde.take(), z, h.take()))
+    }
+}
+
+// The vectors `t1` and `t0` of the key with the secret vectors `s1` and `s2`, through intermediate
+// values that are wiped, since they reveal `t0`.
+// Algorithm 6 ML-DSA.KeyGen_internal, lines 5-6
+fn compute_t1_t0<P: MlDsaParams>(
+    A_hat: &NttMatrix<P::K, P::L>,
+    s1_hat: &NttVector<P::L>,
+    s2: &Vector<P::K>,
+) -> (Vector<P::K>, Vector<P::K>) {
+    let As1_hat = Secret::new(A_hat * s1_hat);
+    let As1 = Secret::new(As1_hat.ntt_inverse());
+    let t = Secret::new(&*As1 + s2);
+    t.power2round()
+}
+
+// The rejection sampling loop of ML-DSA.Sign_internal, given a way to expand the mask from its
+// counter.  It returns the components of the signature, with a `c_tilde` of `Lambda` bytes, or an
+// error once `budget` iterations, or all the values of the counter, have been tried.
+// Dilithium round 3 shares it, with its own mask and a shorter `c_tilde`.
+// Algorithm 7 ML-DSA.Sign_internal, lines 10-32
+#[allow(clippy::too_many_arguments)]
+fn sign_loop<P: MlDsaParams, X: Xof, Lambda: ArraySize>(
+    xof: &X,
+    A_hat: &NttMatrix<P::K, P::L>,
+    s1_hat: &NttVector<P::L>,
+    s2_hat: &NttVector<P::K>,
+    t0_hat: &NttVector<P::K>,
+    mu: &B64,
+    budget: Option<usize>,
+    expand_mask: impl Fn(u16) -> Vector<P::L>,
+) -> Result<SignatureParts<P, Lambda>, SigningError> {
+    let kappas = (0..u16::MAX)
+        .step_by(P::L::USIZE)
+        .take(budget.unwrap_or(usize::MAX));
+
+    // Rejection sampling loop
+    let mut iterations = 0;
+    for kappa in kappas {
+        iterations += 1;
+        let commitment = Commitment::<P>::new(A_hat, expand_mask(kappa));
+        let Some(signature) = commitment.respond(xof, s1_hat, s2_hat, t0_hat, mu) else {
+            continue;
+        };
+
+        if let Some(observer) = xof.observer() {
+            observer.rejection_sampling(iterations);
+        }
+
+        return Ok(signature);
+    }
+
+    Err(SigningError::RejectionBudgetExceeded { iterations })
+}
+
+// The part of ML-DSA.Verify_internal after the computation of mu, on the components of a signature.
+// This is shared by `VerifyingKey`, the fault checks of `SigningKey` and Dilithium round 3.
+// Algorithm 8 ML-DSA.Verify_internal, lines 7-13
+fn verify_mu<P: MlDsaParams, X: Xof, Lambda: ArraySize>(
+    xof: &X,
+    A_hat: &NttMatrix<P::K, P::L>,
+    t1_2d_hat: &NttVector<P::K>,
+    mu: &B64,
+    c_tilde: &Array<u8, Lambda>,
+    z: &Vector<P::L>,
+    h: &Hint<P>,
+) -> bool {
+    // Decoded signatures are already known to satisfy this bound, but freshly computed ones are not
+    if z.infinity_norm() >= P::GAMMA1_MINUS_BETA {
+        return false;
+    }
+
+    // Reconstruct w
+    let c = sample_in_ball(xof, c_tilde, P::TAU);
+
+    let z_hat = z.ntt();
+    let c_hat = c.ntt();
+    let Az_hat = A_hat * &z_hat;
+    let ct1_2d_hat = &c_hat * t1_2d_hat;
+
+    let wp_approx = (&Az_hat - &ct1_2d_hat).ntt_inverse();
+    let w1p = h.use_hint(&wp_approx);
+
+    let w1p_tilde = P::encode_w1(&w1p);
+    let cp_tilde = xof
+        .h()
+        .absorb(mu)
+        .absorb(&w1p_tilde)
+        .squeeze_new::<Lambda>();
+
+    *c_tilde == cp_tilde
+}
+
 /// An ML-DSA key pair
-pub struct KeyPair<P: MlDsaParams> {
+///
+/// The `X` parameter selects the hash functions used by the key pair.  The default, [`ShakeXof`],
+/// is standard ML-DSA.
+pub struct KeyPair<P: MlDsaParams, X: Xof = ShakeXof> {
     /// The signing key of the key pair
-    signing_key: SigningKey<P>,
+    signing_key: SigningKey<P, X>,
 
     /// The verifying key of the key pair
-    verifying_key: VerifyingKey<P>,
+    verifying_key: VerifyingKey<P, X>,
 
-    /// The seed this signing key was derived from
-    #[cfg(feature = "pkcs8")]
-    seed: B32,
+    /// The seed this signing key was derived from, which the private key formats encode
+    #[cfg(any(feature = "pkcs8", feature = "jose", feature = "cose"))]
+    seed: Secret<B32>,
 }
 
-impl<P: MlDsaParams> KeyPair<P> {
+impl<P: MlDsaParams, X: Xof> KeyPair<P, X> {
+    /// Generate a signing key pair from the specified RNG
+    // Algorithm 1 ML-DSA.KeyGen()
+    #[cfg(feature = "rand_core")]
+    pub fn generate<R: CryptoRng + ?Sized>(rng: &mut R) -> Self {
+        let mut xi = Secret::new(B32::default());
+        rng.fill_bytes(&mut xi);
+        Self::from_seed(&xi)
+    }
+
+    /// Deterministically generate a signing key pair from the specified seed
+    // Algorithm 6 ML-DSA.KeyGen_internal
+    #[must_use]
+    pub fn from_seed(xi: &B32) -> Self {
+        Self::from_seed_with_xof(xi, X::default())
+    }
+
+    /// Deterministically generate a signing key pair from the specified seed, using the specified
+    /// XOF configuration.  The keys inherit `xof`.
+    // Algorithm 6 ML-DSA.KeyGen_internal
+    pub fn from_seed_with_xof(xi: &B32, xof: X) -> Self {
+        // The keys take ownership of `xof`, so time with a copy of it
+        let timed = xof.clone();
+        let _timer = PhaseTimer::start(&timed, Phase::KeyGen);
+
+        // Derive seeds
+        let mut h = xof.h().absorb(xi).absorb(&[P::K::U8]).absorb(&[P::L::U8]);
+
+        let rho: B32 = h.squeeze_new();
+        let rhop = Secret::new(h.squeeze_new::<U64>());
+        let K = Secret::new(h.squeeze_new::<U32>());
+
+        // Sample private key components
+        let A_hat = expand_a::<X, P::K, P::L>(&xof, &rho);
+        let s1 = expand_s::<X, P::L>(&xof, &rhop, P::Eta::ETA, 0);
+        let s2 = expand_s::<X, P::K>(&xof, &rhop, P::Eta::ETA, P::L::USIZE);
+
+        // Compute derived values
+        let s1_hat = Secret::new(s1.ntt());
+        let (t1, t0) = compute_t1_t0::<P>(&A_hat, &s1_hat, &s2);
+
+        let verifying_key = VerifyingKey::new(rho, t1, Some(A_hat.clone()), xof.clone());
+        let signing_key = SigningKey::new(
+            rho,
+            K.take(),
+            verifying_key.tr().clone(),
+            s1,
+            s2,
+            t0,
+            Some(A_hat),
+            xof,
+        );
+
+        KeyPair {
+            signing_key,
+            verifying_key,
+            #[cfg(any(feature = "pkcs8", feature = "jose", feature = "cose"))]
+            seed: Secret::new(xi.clone()),
+        }
+    }
+
+    /// Use the specified XOF configuration for all further operations with both keys.
+    #[must_use]
+    pub fn with_xof(self, xof: X) -> Self {
+        KeyPair {
+            signing_key: self.signing_key.with_xof(xof.clone()),
+            verifying_key: self.verifying_key.with_xof(xof),
+            #[cfg(any(feature = "pkcs8", feature = "jose", feature = "cose"))]
+            seed: self.seed,
+        }
+    }
+
+    /// Run a pairwise consistency test and enable [fault checks](SigningKey::with_fault_checks) on
+    /// the signing key, which verifies against this key pair's verifying key.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`SigningError::FaultDetected`] if the pairwise consistency test fails,
+    /// and the error of signing if the test cannot sign.
+    pub fn with_fault_checks(mut self) -> Result<Self, SigningError> {
+        self.signing_key.t1_2d_hat = Some(self.verifying_key.expanded().t1_2d_hat.clone());
+        self.pairwise_consistency_test()?;
+        Ok(self)
+    }
+
+    /// Sign a fixed message with the signing key and verify the signature with the verifying key,
+    /// as in the pairwise consistency test that FIPS 140-3 requires after key generation.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`SigningError::FaultDetected`] if the signature does not verify, and
+    /// the error of signing if it cannot sign.
+    pub fn pairwise_consistency_test(&self) -> Result<(), SigningError> {
+        const MSG: &[u8] = b"ML-DSA pairwise consistency test";
+
+        let sig = self.signing_key.raw_sign_deterministic(&[MSG], &[])?;
+        if self.verifying_key.verify_with_context(MSG, &[], &sig) {
+            Ok(())
+        } else {
+            Err(SigningError::FaultDetected)
+        }
+    }
+
     /// The signing key of the key pair
-    pub fn signing_key(&self) -> &SigningKey<P> {
+    pub fn signing_key(&self) -> &SigningKey<P, X> {
         &self.signing_key
     }
 
     /// The verifying key of the key pair
-    pub fn verifying_key(&self) -> &VerifyingKey<P> {
+    pub fn verifying_key(&self) -> &VerifyingKey<P, X> {
         &self.verifying_key
     }
 }
 
-impl<P: MlDsaParams> AsRef<VerifyingKey<P>> for KeyPair<P> {
-    fn as_ref(&self) -> &VerifyingKey<P> {
+impl<P: MlDsaParams, X: Xof> AsRef<VerifyingKey<P, X>> for KeyPair<P, X> {
+    fn as_ref(&self) -> &VerifyingKey<P, X> {
         &self.verifying_key
     }
 }
 
-impl<P: MlDsaParams> fmt::Debug for KeyPair<P> {
+impl<P: MlDsaParams, X: Xof> fmt::Debug for KeyPair<P, X> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("KeyPair")
             .field("verifying_key", &self.verifying_key)
@@ -238,33 +594,49 @@ impl<P: MlDsaParams> fmt::Debug for KeyPair<P> {
     }
 }
 
-impl<P: MlDsaParams> signature::KeypairRef for KeyPair<P> {
-    type VerifyingKey = VerifyingKey<P>;
+impl<P: MlDsaParams, X: Xof> signature::KeypairRef for KeyPair<P, X> {
+    type VerifyingKey = VerifyingKey<P, X>;
 }
 
 #[cfg(feature = "pkcs8")]
-impl<P> TryFrom<PrivateKeyInfoRef<'_>> for KeyPair<P>
+impl<P, X> TryFrom<PrivateKeyInfoRef<'_>> for KeyPair<P, X>
 where
     P: MlDsaParams,
     P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
 {
     type Error = pkcs8::Error;
 
     fn try_from(private_key_info: pkcs8::PrivateKeyInfoRef<'_>) -> pkcs8::Result<Self> {
-        match private_key_info.algorithm {
-            alg if alg == P::ALGORITHM_IDENTIFIER => {}
-            other => return Err(spki::Error::OidUnknown { oid: other.oid }.into()),
+        check_algorithm::<P, X>(private_key_info.algorithm)?;
+
+        let (seed, expanded_key) =
+            match PrivateKeyChoice::from_der(private_key_info.private_key.as_bytes())? {
+                PrivateKeyChoice::Seed(seed) => (seed, None),
+                PrivateKeyChoice::Both { seed, expanded_key } => (seed, Some(expanded_key)),
+                // A key pair cannot be formed without the seed
+                PrivateKeyChoice::ExpandedKey(_) => return Err(pkcs8::Error::KeyMalformed),
+            };
+
+        let seed = Array::try_from(seed).map_err(|_| pkcs8::Error::KeyMalformed)?;
+        let kp = Self::from_seed(&seed);
+
+        // If both forms are present, they must describe the same key.  The expanded key is
+        // secret, so it is compared in constant time.
+        if let Some(expanded_key) = expanded_key {
+            let encoded = Secret::new(kp.signing_key.encode());
+            if !bool::from(expanded_key.ct_eq(encoded.as_slice())) {
+                return Err(pkcs8::Error::KeyMalformed);
+            }
         }
 
-        let seed = Array::try_from(private_key_info.private_key.as_bytes())
-            .map_err(|_| pkcs8::Error::KeyMalformed)?;
-        Ok(P::key_gen_internal(&seed))
+        Ok(kp)
     }
 }
 
 /// The `Signer` implementation for `KeyPair` uses the optional deterministic variant of ML-DSA, and
 /// only supports signing with an empty context string.
-impl<P: MlDsaParams> signature::Signer<Signature<P>> for KeyPair<P> {
+impl<P: MlDsaParams, X: Xof> signature::Signer<Signature<P>> for KeyPair<P, X> {
     fn try_sign(&self, msg: &[u8]) -> Result<Signature<P>, Error> {
         self.try_multipart_sign(&[msg])
     }
@@ -272,42 +644,97 @@ impl<P: MlDsaParams> signature::Signer<Signature<P>> for KeyPair<P> {
 
 /// The `Signer` implementation for `KeyPair` uses the optional deterministic variant of ML-DSA, and
 /// only supports signing with an empty context string.
-impl<P: MlDsaParams> MultipartSigner<Signature<P>> for KeyPair<P> {
+impl<P: MlDsaParams, X: Xof> MultipartSigner<Signature<P>> for KeyPair<P, X> {
     fn try_multipart_sign(&self, msg: &[&[u8]]) -> Result<Signature<P>, Error> {
-        self.signing_key.raw_sign_deterministic(msg, &[])
+        Ok(self.signing_key.raw_sign_deterministic(msg, &[])?)
+    }
+}
+
+/// The `PrehashSigner` implementation for `KeyPair` uses the optional deterministic variant of
+/// HashML-DSA, and only supports signing with an empty context string.
+impl<P: MlDsaParams, X: Xof, PH: Prehash> PrehashSigner<HashSignature<P, PH>> for KeyPair<P, X> {
+    fn sign_prehash(&self, prehash: &[u8]) -> Result<HashSignature<P, PH>, Error> {
+        self.signing_key.sign_prehash(prehash)
     }
 }
 
 #[cfg(feature = "pkcs8")]
-impl<P> SignatureAlgorithmIdentifier for KeyPair<P>
+impl<P, X> SignatureAlgorithmIdentifier for KeyPair<P, X>
 where
     P: MlDsaParams,
     P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
 {
     type Params = AnyRef<'static>;
 
     const SIGNATURE_ALGORITHM_IDENTIFIER: AlgorithmIdentifier<Self::Params> =
-        Signature::<P>::ALGORITHM_IDENTIFIER;
+        algorithm_identifier::<P, X>();
 }
 
 #[cfg(all(feature = "alloc", feature = "pkcs8"))]
-impl<P> EncodePrivateKey for KeyPair<P>
+impl<P, X> EncodePrivateKey for KeyPair<P, X>
 where
     P: MlDsaParams,
     P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
 {
     fn to_pkcs8_der(&self) -> pkcs8::Result<der::SecretDocument> {
-        let pkcs8_key = pkcs8::PrivateKeyInfoRef::new(
-            P::ALGORITHM_IDENTIFIER,
-            OctetStringRef::new(&self.seed)?,
-        );
-        Ok(der::SecretDocument::encode_msg(&pkcs8_key)?)
+        self.to_pkcs8_der_with_format(PrivateKeyFormat::Seed)
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "pkcs8"))]
+impl<P, X> KeyPair<P, X>
+where
+    P: MlDsaParams,
+    P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
+{
+    /// Serialize the key pair as a PKCS#8 private key in the given form.  [`EncodePrivateKey`]
+    /// uses [`PrivateKeyFormat::Seed`].
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the key cannot be DER-encoded.
+    pub fn to_pkcs8_der_with_format(
+        &self,
+        format: PrivateKeyFormat,
+    ) -> pkcs8::Result<der::SecretDocument> {
+        let expanded_key = self.signing_key.encode();
+        let private_key = match format {
+            PrivateKeyFormat::Seed => PrivateKeyChoice::Seed(&self.seed),
+            PrivateKeyFormat::ExpandedKey => PrivateKeyChoice::ExpandedKey(&expanded_key),
+            PrivateKeyFormat::Both => PrivateKeyChoice::Both {
+                seed: &self.seed,
+                expanded_key: &expanded_key,
+            },
+        };
+        encode_private_key::<P, X>(&private_key)
     }
 }
 
+#[cfg(all(feature = "alloc", feature = "pkcs8"))]
+fn encode_private_key<P, X>(
+    private_key: &PrivateKeyChoice<'_>,
+) -> pkcs8::Result<der::SecretDocument>
+where
+    P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
+{
+    let private_key = der::zeroize::Zeroizing::new(der::Encode::to_der(private_key)?);
+    let pkcs8_key = pkcs8::PrivateKeyInfoRef::new(
+        algorithm_identifier::<P, X>(),
+        OctetStringRef::new(&private_key)?,
+    );
+    Ok(der::SecretDocument::encode_msg(&pkcs8_key)?)
+}
+
 /// An ML-DSA signing key
+///
+/// The `X` parameter selects the hash functions used by the key.  The default, [`ShakeXof`], is
+/// standard ML-DSA.
 #[derive(Clone, PartialEq)]
-pub struct SigningKey<P: MlDsaParams> {
+pub struct SigningKey<P: MlDsaParams, X: Xof = ShakeXof> {
     rho: B32,
     K: B32,
     tr: B64,
@@ -320,16 +747,29 @@ pub struct SigningKey<P: MlDsaParams> {
     s2_hat: NttVector<P::K>,
     t0_hat: NttVector<P::K>,
     A_hat: NttMatrix<P::K, P::L>,
+
+    // The public key, in the form used for verification, if signatures are checked before they are
+    // released
+    t1_2d_hat: Option<NttVector<P::K>>,
+
+    // The maximum number of iterations of the rejection sampling loop, if any
+    rejection_budget: Option<usize>,
+
+    xof: X,
+
+    // Corrupts each signature before it is checked
+    #[cfg(test)]
+    fault: Option<fault::Fault>,
 }
 
-impl<P: MlDsaParams> fmt::Debug for SigningKey<P> {
+impl<P: MlDsaParams, X: Xof> fmt::Debug for SigningKey<P, X> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("SigningKey").finish_non_exhaustive()
     }
 }
 
 #[cfg(feature = "zeroize")]
-impl<P: MlDsaParams> Drop for SigningKey<P> {
+impl<P: MlDsaParams, X: Xof> Drop for SigningKey<P, X> {
     fn drop(&mut self) {
         self.rho.zeroize();
         self.K.zeroize();
@@ -337,13 +777,17 @@ impl<P: MlDsaParams> Drop for SigningKey<P> {
         self.s1.zeroize();
         self.s2.zeroize();
         self.t0.zeroize();
+        self.s1_hat.zeroize();
+        self.s2_hat.zeroize();
+        self.t0_hat.zeroize();
     }
 }
 
 #[cfg(feature = "zeroize")]
-impl<P: MlDsaParams> ZeroizeOnDrop for SigningKey<P> {}
+impl<P: MlDsaParams, X: Xof> ZeroizeOnDrop for SigningKey<P, X> {}
 
-impl<P: MlDsaParams> SigningKey<P> {
+impl<P: MlDsaParams, X: Xof> SigningKey<P, X> {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         rho: B32,
         K: B32,
@@ -352,8 +796,10 @@ impl<P: MlDsaParams> SigningKey<P> {
         s2: Vector<P::K>,
         t0: Vector<P::K>,
         A_hat: Option<NttMatrix<P::K, P::L>>,
+        xof: X,
     ) -> Self {
-        let A_hat = A_hat.unwrap_or_else(|| expand_a(&rho));
+        let () = P::VALID;
+        let A_hat = A_hat.unwrap_or_else(|| expand_a::<X, P::K, P::L>(&xof, &rho));
         let s1_hat = s1.ntt();
         let s2_hat = s2.ntt();
         let t0_hat = t0.ntt();
@@ -370,22 +816,114 @@ impl<P: MlDsaParams> SigningKey<P> {
             s2_hat,
             t0_hat,
             A_hat,
+
+            t1_2d_hat: None,
+
+            rejection_budget: None,
+
+            xof,
+
+            #[cfg(test)]
+            fault: None,
         }
     }
 
+    /// Verify every signature against the public key before releasing it, as a countermeasure
+    /// against faults injected during signing.  Signing then fails with
+    /// [`SigningError::FaultDetected`] instead of returning a signature that does not verify.
+    ///
+    /// This roughly doubles the cost of signing.  [`KeyPair::with_fault_checks`] also runs a
+    /// pairwise consistency test.
+    #[must_use]
+    pub fn with_fault_checks(mut self) -> Self {
+        let t1 = self.verifying_key().expanded().t1_2d_hat.clone();
+        self.t1_2d_hat = Some(t1);
+        self
+    }
+
+    /// Whether signatures are verified before they are released
+    pub fn has_fault_checks(&self) -> bool {
+        self.t1_2d_hat.is_some()
+    }
+
+    /// Limit the rejection sampling loop of each signature to `iterations` iterations, after which
+    /// signing fails with [`SigningError::RejectionBudgetExceeded`].
+    ///
+    /// A signature takes 4.25, 5.1 and 3.85 iterations on average for [`MlDsa44`], [`MlDsa65`]
+    /// and [`MlDsa87`] respectively, and each iteration is accepted independently, so the chance
+    /// of running out falls exponentially with the budget.  Without a budget, the loop runs until
+    /// the 16-bit counter of the mask runs out, after about 65,536 / ℓ iterations.
+    #[must_use]
+    pub fn with_rejection_budget(mut self, iterations: usize) -> Self {
+        self.rejection_budget = Some(iterations);
+        self
+    }
+
+    /// The maximum number of iterations of the rejection sampling loop, if there is one
+    pub fn rejection_budget(&self) -> Option<usize> {
+        self.rejection_budget
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_fault(mut self, fault: fault::Fault) -> Self {
+        self.fault = Some(fault);
+        self
+    }
+
+    /// Use the specified XOF configuration for all further operations with this key.
+    ///
+    /// Keys of the same type always produce the same output, so this is only useful for backends
+    /// that carry tuning parameters, such as [`AesXof`].
+    #[must_use]
+    pub fn with_xof(mut self, xof: X) -> Self {
+        self.xof = xof;
+        self
+    }
+
+    /// The XOF configuration used by this key
+    pub fn xof(&self) -> &X {
+        &self.xof
+    }
+
     /// This method reflects the ML-DSA.Sign_internal algorithm from FIPS 204. It does not
     /// include the domain separator that distinguishes between the normal and pre-hashed cases,
     /// and it does not separate the context string from the rest of the message.
+    ///
+    /// This method is intended for testing, and does not apply the checks of
+    /// [`SigningKey::with_fault_checks`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return [`SigningError::RejectionBudgetExceeded`] if the
+    /// [rejection budget](SigningKey::with_rejection_budget) runs out.
     // Algorithm 7 ML-DSA.Sign_internal
     // TODO(RLB) Only expose based on a feature.  Tests need access, but normal code shouldn't.
-    pub fn sign_internal(&self, Mp: &[&[u8]], rnd: &B32) -> Signature<P>
+    pub fn sign_internal(&self, Mp: &[&[u8]], rnd: &B32) -> Result<Signature<P>, SigningError>
     where
         P: MlDsaParams,
     {
-        self.raw_sign_internal(&[Mp], rnd)
+        let mu = message_representative(&self.xof, &self.tr, &[Mp]);
+        self.raw_sign_mu_unchecked(&mu, rnd)
     }
 
-    fn raw_sign_internal(&self, Mp: &[&[&[u8]]], rnd: &B32) -> Signature<P>
+    /// This method reflects the ML-DSA.Sign_internal algorithm from FIPS 204 with a pre-computed
+    /// μ, as used by the external-μ tests of ACVP.
+    ///
+    /// Like [`SigningKey::sign_internal`], this method is intended for testing.
+    ///
+    /// # Errors
+    ///
+    /// This method will return [`SigningError::RejectionBudgetExceeded`] if the
+    /// [rejection budget](SigningKey::with_rejection_budget) runs out.
+    // Algorithm 7 ML-DSA.Sign_internal (from line 7)
+    pub fn sign_mu_internal(&self, mu: &B64, rnd: &B32) -> Result<Signature<P>, SigningError>
+    where
+        P: MlDsaParams,
+    {
+        self.raw_sign_mu_unchecked(mu, rnd)
+    }
+
+    fn raw_sign_internal(&self, Mp: &[&[&[u8]]], rnd: &B32) -> Result<Signature<P>, SigningError>
     where
         P: MlDsaParams,
     {
@@ -393,69 +931,84 @@ impl<P: MlDsaParams> SigningKey<P> {
         // XXX(RLB): This line incorporates some of the logic from ML-DSA.sign to avoid computing
         // the concatenated M'.
         // XXX(RLB) Should the API represent this as an input?
-        let mu = message_representative(&self.tr, Mp);
+        let mu = message_representative(&self.xof, &self.tr, Mp);
         self.raw_sign_mu(&mu, rnd)
     }
 
-    fn raw_sign_mu(&self, mu: &B64, rnd: &B32) -> Signature<P>
+    fn raw_sign_mu(&self, mu: &B64, rnd: &B32) -> Result<Signature<P>, SigningError>
     where
         P: MlDsaParams,
     {
-        // Compute the private random seed
-        let rhopp: B64 = H::default()
-            .absorb(&self.K)
-            .absorb(rnd)
-            .absorb(mu)
-            .squeeze_new();
-
-        // Rejection sampling loop
-        for kappa in (0..u16::MAX).step_by(P::L::USIZE) {
-            let y = expand_mask::<P::L, P::Gamma1>(&rhopp, kappa);
-            let w = (&self.A_hat * &y.ntt()).ntt_inverse();
-            let w1 = w.high_bits::<P::TwoGamma2>();
-
-            let w1_tilde = P::encode_w1(&w1);
-            let c_tilde = H::default()
-                .absorb(mu)
-                .absorb(&w1_tilde)
-                .squeeze_new::<P::Lambda>();
-            let c = sample_in_ball(&c_tilde, P::TAU);
-            let c_hat = c.ntt();
-
-            let cs1 = (&c_hat * &self.s1_hat).ntt_inverse();
-            let cs2 = (&c_hat * &self.s2_hat).ntt_inverse();
+        let sigma = self.raw_sign_mu_unchecked(mu, rnd)?;
+        self.check_signature(mu, sigma)
+    }
 
-            let z = &y + &cs1;
-            let r0 = (&w - &cs2).low_bits::<P::TwoGamma2>();
+    // Apply the checks of `with_fault_checks` to a signature of `mu`, if the key has them
+    fn check_signature(
+        &self,
+        mu: &B64,
+        #[allow(unused_mut)] mut sigma: Signature<P>,
+    ) -> Result<Signature<P>, SigningError> {
+        #[cfg(test)]
+        if let Some(fault) = self.fault {
+            fault.inject(&mut sigma);
+        }
 
-            if z.infinity_norm() >= P::GAMMA1_MINUS_BETA
-                || r0.infinity_norm() >= P::GAMMA2_MINUS_BETA
-            {
-                continue;
+        if let Some(t1_2d_hat) = &self.t1_2d_hat {
+            if !verify_mu(
+                &self.xof,
+                &self.A_hat,
+                t1_2d_hat,
+                mu,
+                &sigma.c_tilde,
+                &sigma.z,
+                &sigma.h,
+            ) {
+                return Err(SigningError::FaultDetected);
             }
+        }
 
-            let ct0 = (&c_hat * &self.t0_hat).ntt_inverse();
-            let minus_ct0 = -&ct0;
-            let w_cs2_ct0 = &(&w - &cs2) + &ct0;
-            let h = Hint::<P>::new(&minus_ct0, &w_cs2_ct0);
+        Ok(sigma)
+    }
 
-            if ct0.infinity_norm() >= P::Gamma2::U32 || h.hamming_weight() > P::Omega::USIZE {
-                continue;
-            }
+    fn raw_sign_mu_unchecked(&self, mu: &B64, rnd: &B32) -> Result<Signature<P>, SigningError>
+    where
+        P: MlDsaParams,
+    {
+        let _timer = PhaseTimer::start(&self.xof, Phase::Sign);
 
-            let z = z.mod_plus_minus::<SpecQ>();
-            return Signature { c_tilde, z, h };
-        }
+        // Compute the private random seed
+        let rhopp = Secret::new(
+            self.xof
+                .h()
+                .absorb(&self.K)
+                .absorb(rnd)
+                .absorb(mu)
+                .squeeze_new::<U64>(),
+        );
 
-        unreachable!("Rejection sampling failed to find a valid signature");
+        let (c_tilde, z, h) = sign_loop(
+            &self.xof,
+            &self.A_hat,
+            &self.s1_hat,
+            &self.s2_hat,
+            &self.t0_hat,
+            mu,
+            self.rejection_budget,
+            |kappa| expand_mask::<X, P::L, P::Gamma1>(&self.xof, &rhopp, kappa),
+        )?;
+        Ok(Signature { c_tilde, z, h })
     }
 
     /// This method reflects the randomized ML-DSA.Sign algorithm.
     ///
     /// # Errors
     ///
-    /// This method will return an opaque error if the context string is more than 255 bytes long,
-    /// or if it fails to get enough randomness.
+    /// This method will return [`SigningError::ContextTooLong`] if the context string is more
+    /// than 255 bytes long, and [`SigningError::RngFailure`] if it fails to get enough randomness.
+    ///
+    /// It also fails if the [rejection budget](SigningKey::with_rejection_budget) runs out, or if
+    /// [fault checks](SigningKey::with_fault_checks) reject the signature.
     // Algorithm 2 ML-DSA.Sign
     #[cfg(feature = "rand_core")]
     pub fn sign_randomized<R: TryCryptoRng + ?Sized>(
@@ -463,62 +1016,140 @@ impl<P: MlDsaParams> SigningKey<P> {
         M: &[u8],
         ctx: &[u8],
         rng: &mut R,
-    ) -> Result<Signature<P>, Error> {
-        if ctx.len() > 255 {
-            return Err(Error::new());
-        }
+    ) -> Result<Signature<P>, SigningError> {
+        check_context(ctx)?;
 
-        let mut rnd = B32::default();
-        rng.try_fill_bytes(&mut rnd).map_err(|_| Error::new())?;
+        let mut rnd = Secret::new(B32::default());
+        rng.try_fill_bytes(&mut rnd)
+            .map_err(|_| SigningError::RngFailure)?;
 
-        let Mp = &[&[0], &[Truncate::truncate(ctx.len())], ctx, M];
-        Ok(self.sign_internal(Mp, &rnd))
+        let Mp: &[&[&[u8]]] = &[&[&[0], &[Truncate::truncate(ctx.len())], ctx, M]];
+        self.raw_sign_internal(Mp, &rnd)
     }
 
     /// This method reflects the randomized ML-DSA.Sign algorithm with a pre-computed μ.
     ///
     /// # Errors
     ///
-    /// This method can return an opaque error if it fails to get enough randomness.
+    /// This method will return [`SigningError::RngFailure`] if it fails to get enough randomness.
+    ///
+    /// It also fails if the [rejection budget](SigningKey::with_rejection_budget) runs out, or if
+    /// [fault checks](SigningKey::with_fault_checks) reject the signature.
     // Algorithm 2 ML-DSA.Sign (optional pre-computed μ variant)
     #[cfg(feature = "rand_core")]
     pub fn sign_mu_randomized<R: TryCryptoRng + ?Sized>(
         &self,
         mu: &B64,
         rng: &mut R,
-    ) -> Result<Signature<P>, Error> {
-        let mut rnd = B32::default();
-        rng.try_fill_bytes(&mut rnd).map_err(|_| Error::new())?;
+    ) -> Result<Signature<P>, SigningError> {
+        let mut rnd = Secret::new(B32::default());
+        rng.try_fill_bytes(&mut rnd)
+            .map_err(|_| SigningError::RngFailure)?;
 
-        Ok(self.raw_sign_mu(mu, &rnd))
+        self.raw_sign_mu(mu, &rnd)
     }
 
     /// This method reflects the optional deterministic variant of the ML-DSA.Sign algorithm.
     ///
     /// # Errors
     ///
-    /// This method will return an opaque error if the context string is more than 255 bytes long.
+    /// This method will return [`SigningError::ContextTooLong`] if the context string is more
+    /// than 255 bytes long.
+    ///
+    /// It also fails if the [rejection budget](SigningKey::with_rejection_budget) runs out, or if
+    /// [fault checks](SigningKey::with_fault_checks) reject the signature.
     // Algorithm 2 ML-DSA.Sign (optional deterministic variant)
-    pub fn sign_deterministic(&self, M: &[u8], ctx: &[u8]) -> Result<Signature<P>, Error> {
+    pub fn sign_deterministic(&self, M: &[u8], ctx: &[u8]) -> Result<Signature<P>, SigningError> {
         self.raw_sign_deterministic(&[M], ctx)
     }
 
     /// This method reflects the optional deterministic variant of the ML-DSA.Sign algorithm with a
     /// pre-computed μ.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the [rejection budget](SigningKey::with_rejection_budget)
+    /// runs out, or if [fault checks](SigningKey::with_fault_checks) reject the signature.
     // Algorithm 2 ML-DSA.Sign (optional deterministic and pre-computed μ variant)
-    pub fn sign_mu_deterministic(&self, mu: &B64) -> Signature<P> {
+    pub fn sign_mu_deterministic(&self, mu: &B64) -> Result<Signature<P>, SigningError> {
         let rnd = B32::default();
         self.raw_sign_mu(mu, &rnd)
     }
 
-    fn raw_sign_deterministic(&self, M: &[&[u8]], ctx: &[u8]) -> Result<Signature<P>, Error> {
-        if ctx.len() > 255 {
-            return Err(Error::new());
-        }
+    fn raw_sign_deterministic(
+        &self,
+        M: &[&[u8]],
+        ctx: &[u8],
+    ) -> Result<Signature<P>, SigningError> {
+        check_context(ctx)?;
 
         let rnd = B32::default();
         let Mp = &[&[&[0], &[Truncate::truncate(ctx.len())], ctx], M];
-        Ok(self.raw_sign_internal(Mp, &rnd))
+        self.raw_sign_internal(Mp, &rnd)
+    }
+
+    /// This method reflects the randomized HashML-DSA.Sign algorithm, for a `digest` of the
+    /// message computed with `PH`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return [`SigningError::ContextTooLong`] if the context string is more
+    /// than 255 bytes long, [`SigningError::InvalidDigestLength`] if `digest` is not the length of
+    /// a `PH` digest, and [`SigningError::RngFailure`] if it fails to get enough randomness.
+    ///
+    /// It also fails if the [rejection budget](SigningKey::with_rejection_budget) runs out, or if
+    /// [fault checks](SigningKey::with_fault_checks) reject the signature.
+    // Algorithm 4 HashML-DSA.Sign
+    #[cfg(feature = "rand_core")]
+    pub fn sign_prehash_randomized<PH: Prehash, R: TryCryptoRng + ?Sized>(
+        &self,
+        digest: &[u8],
+        ctx: &[u8],
+        rng: &mut R,
+    ) -> Result<Signature<P>, SigningError> {
+        let mut rnd = Secret::new(B32::default());
+        rng.try_fill_bytes(&mut rnd)
+            .map_err(|_| SigningError::RngFailure)?;
+
+        self.raw_sign_prehash::<PH>(digest, ctx, &rnd)
+    }
+
+    /// This method reflects the optional deterministic variant of the HashML-DSA.Sign algorithm,
+    /// for a `digest` of the message computed with `PH`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return [`SigningError::ContextTooLong`] if the context string is more
+    /// than 255 bytes long, and [`SigningError::InvalidDigestLength`] if `digest` is not the length
+    /// of a `PH` digest.
+    ///
+    /// It also fails if the [rejection budget](SigningKey::with_rejection_budget) runs out, or if
+    /// [fault checks](SigningKey::with_fault_checks) reject the signature.
+    // Algorithm 4 HashML-DSA.Sign (optional deterministic variant)
+    pub fn sign_prehash_deterministic<PH: Prehash>(
+        &self,
+        digest: &[u8],
+        ctx: &[u8],
+    ) -> Result<Signature<P>, SigningError> {
+        self.raw_sign_prehash::<PH>(digest, ctx, &B32::default())
+    }
+
+    fn raw_sign_prehash<PH: Prehash>(
+        &self,
+        digest: &[u8],
+        ctx: &[u8],
+        rnd: &B32,
+    ) -> Result<Signature<P>, SigningError> {
+        check_context(ctx)?;
+        if digest.len() != PH::OutputSize::USIZE {
+            return Err(SigningError::InvalidDigestLength {
+                expected: PH::OutputSize::USIZE,
+                len: digest.len(),
+            });
+        }
+
+        let Mp: &[&[&[u8]]] = &[&[&[1], &[Truncate::truncate(ctx.len())], ctx, PH::OID, digest]];
+        self.raw_sign_internal(Mp, rnd)
     }
 
     /// Encode the key in a fixed-size byte array.
@@ -555,6 +1186,7 @@ impl<P: MlDsaParams> SigningKey<P> {
             P::decode_s2(s2_enc),
             P::decode_t0(t0_enc),
             None,
+            X::default(),
         )
     }
 
@@ -569,17 +1201,25 @@ impl<P: MlDsaParams> SigningKey<P> {
     /// `SigningKey` implements `signature::Keypair`: this inherent method is
     /// retained for convenience, so it is available for callers even when the
     /// `signature::Keypair` trait is out-of-scope.
-    pub fn verifying_key(&self) -> VerifyingKey<P> {
-        let kp: &dyn signature::Keypair<VerifyingKey = VerifyingKey<P>> = self;
+    pub fn verifying_key(&self) -> VerifyingKey<P, X> {
+        let kp: &dyn signature::Keypair<VerifyingKey = VerifyingKey<P, X>> = self;
 
         kp.verifying_key()
     }
 }
 
+impl<P: MlDsaParams> SigningKey<P, AesXof> {
+    /// Use the specified AES keystream buffering for all further operations with this key.
+    #[must_use]
+    pub fn with_aes_params(self, params: AesParams) -> Self {
+        self.with_xof(AesXof::new(params))
+    }
+}
+
 /// The `Signer` implementation for `SigningKey` uses the optional deterministic variant of ML-DSA, and
 /// only supports signing with an empty context string.  If you would like to include a context
 /// string, use the [`SigningKey::sign_deterministic`] method.
-impl<P: MlDsaParams> signature::Signer<Signature<P>> for SigningKey<P> {
+impl<P: MlDsaParams, X: Xof> signature::Signer<Signature<P>> for SigningKey<P, X> {
     fn try_sign(&self, msg: &[u8]) -> Result<Signature<P>, Error> {
         self.try_multipart_sign(&[msg])
     }
@@ -588,107 +1228,251 @@ impl<P: MlDsaParams> signature::Signer<Signature<P>> for SigningKey<P> {
 /// The `Signer` implementation for `SigningKey` uses the optional deterministic variant of ML-DSA, and
 /// only supports signing with an empty context string.  If you would like to include a context
 /// string, use the [`SigningKey::sign_deterministic`] method.
-impl<P: MlDsaParams> MultipartSigner<Signature<P>> for SigningKey<P> {
+impl<P: MlDsaParams, X: Xof> MultipartSigner<Signature<P>> for SigningKey<P, X> {
     fn try_multipart_sign(&self, msg: &[&[u8]]) -> Result<Signature<P>, Error> {
-        self.raw_sign_deterministic(msg, &[])
+        Ok(self.raw_sign_deterministic(msg, &[])?)
+    }
+}
+
+/// The `PrehashSigner` implementation for `SigningKey` uses the optional deterministic variant of
+/// HashML-DSA, and only supports signing with an empty context string.  If you would like to
+/// include a context string, use the [`SigningKey::sign_prehash_deterministic`] method.
+impl<P: MlDsaParams, X: Xof, PH: Prehash> PrehashSigner<HashSignature<P, PH>> for SigningKey<P, X> {
+    fn sign_prehash(&self, prehash: &[u8]) -> Result<HashSignature<P, PH>, Error> {
+        Ok(HashSignature::new(
+            self.sign_prehash_deterministic::<PH>(prehash, &[])?,
+        ))
+    }
+}
+
+/// The `RandomizedPrehashSigner` implementation for `SigningKey` only supports signing with an
+/// empty context string.  If you would like to include a context string, use the
+/// [`SigningKey::sign_prehash_randomized`] method.
+#[cfg(feature = "rand_core")]
+impl<P: MlDsaParams, X: Xof, PH: Prehash> RandomizedPrehashSigner<HashSignature<P, PH>>
+    for SigningKey<P, X>
+{
+    fn sign_prehash_with_rng<R: TryCryptoRng + ?Sized>(
+        &self,
+        rng: &mut R,
+        prehash: &[u8],
+    ) -> Result<HashSignature<P, PH>, Error> {
+        Ok(HashSignature::new(self.sign_prehash_randomized::<PH, R>(
+            prehash,
+            &[],
+            rng,
+        )?))
     }
 }
 
 /// The `KeyPair` implementation for `SigningKey` allows to derive a `VerifyingKey` from
 /// a bare `SigningKey` (even in the absence of the original seed).
-impl<P: MlDsaParams> signature::Keypair for SigningKey<P> {
-    type VerifyingKey = VerifyingKey<P>;
+impl<P: MlDsaParams, X: Xof> signature::Keypair for SigningKey<P, X> {
+    type VerifyingKey = VerifyingKey<P, X>;
 
     /// This is a utility function that is useful when importing the private key
     /// from an external source which does not export the seed and does not
     /// provide the precomputed public key associated with the private key
     /// itself.
     fn verifying_key(&self) -> Self::VerifyingKey {
-        let As1 = &self.A_hat * &self.s1_hat;
-        let t = &As1.ntt_inverse() + &self.s2;
-
         /* Discard t0 */
-        let (t1, _) = t.power2round();
+        let (t1, _) = compute_t1_t0::<P>(&self.A_hat, &self.s1_hat, &self.s2);
 
-        VerifyingKey::new(self.rho.clone(), t1, Some(self.A_hat.clone()), None)
+        VerifyingKey::new(
+            self.rho.clone(),
+            t1,
+            Some(self.A_hat.clone()),
+            self.xof.clone(),
+        )
     }
 }
 
 /// The `RandomizedSigner` implementation for `SigningKey` only supports signing with an empty
-/// context string. If you would like to include a context string, use the [`SigningKey::sign`]
-/// method.
+/// context string. If you would like to include a context string, use the
+/// [`SigningKey::sign_randomized`] method.
 #[cfg(feature = "rand_core")]
-impl<P: MlDsaParams> signature::RandomizedSigner<Signature<P>> for SigningKey<P> {
+impl<P: MlDsaParams, X: Xof> signature::RandomizedSigner<Signature<P>> for SigningKey<P, X> {
     fn try_sign_with_rng<R: TryCryptoRng + ?Sized>(
         &self,
         rng: &mut R,
         msg: &[u8],
     ) -> Result<Signature<P>, Error> {
-        self.sign_randomized(msg, &[], rng)
+        Ok(self.sign_randomized(msg, &[], rng)?)
     }
 }
 
 #[cfg(feature = "pkcs8")]
-impl<P> SignatureAlgorithmIdentifier for SigningKey<P>
+impl<P, X> SignatureAlgorithmIdentifier for SigningKey<P, X>
 where
     P: MlDsaParams,
     P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
 {
     type Params = AnyRef<'static>;
 
     const SIGNATURE_ALGORITHM_IDENTIFIER: AlgorithmIdentifier<Self::Params> =
-        Signature::<P>::ALGORITHM_IDENTIFIER;
+        algorithm_identifier::<P, X>();
 }
 
 #[cfg(feature = "pkcs8")]
-impl<P> TryFrom<PrivateKeyInfoRef<'_>> for SigningKey<P>
+impl<P, X> TryFrom<PrivateKeyInfoRef<'_>> for SigningKey<P, X>
 where
     P: MlDsaParams,
     P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
 {
     type Error = pkcs8::Error;
 
     fn try_from(private_key_info: pkcs8::PrivateKeyInfoRef<'_>) -> pkcs8::Result<Self> {
+        check_algorithm::<P, X>(private_key_info.algorithm)?;
+
+        if let PrivateKeyChoice::ExpandedKey(expanded_key) =
+            PrivateKeyChoice::from_der(private_key_info.private_key.as_bytes())?
+        {
+            let enc = EncodedSigningKey::<P>::try_from(expanded_key)
+                .map_err(|_| pkcs8::Error::KeyMalformed)?;
+            return Ok(Self::decode(&enc));
+        }
+
         let keypair = KeyPair::try_from(private_key_info)?;
 
         Ok(keypair.signing_key)
     }
 }
 
+/// The `EncodePrivateKey` implementation for `SigningKey` uses the `expandedKey` form, since a
+/// signing key does not retain its seed.  Use [`KeyPair`] to encode the seed.
+#[cfg(all(feature = "alloc", feature = "pkcs8"))]
+impl<P, X> EncodePrivateKey for SigningKey<P, X>
+where
+    P: MlDsaParams,
+    P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
+{
+    fn to_pkcs8_der(&self) -> pkcs8::Result<der::SecretDocument> {
+        let expanded_key = self.encode();
+        encode_private_key::<P, X>(&PrivateKeyChoice::ExpandedKey(&expanded_key))
+    }
+}
+
 /// An ML-DSA verification key
-#[derive(Clone, Debug, PartialEq)]
-pub struct VerifyingKey<P: ParameterSet> {
+///
+/// The `X` parameter selects the hash functions used by the key.  The default, [`ShakeXof`], is
+/// standard ML-DSA.
+///
+/// With the `std` feature, a decoded key only holds its encoded components.  The values derived
+/// from them, most expensively the matrix `A_hat`, are computed by the first operation that needs
+/// them and shared by later ones.
+///
+/// Without `std` there is no thread-safe cell to defer them to, so they are computed when the key
+/// is decoded, and decoding costs about as much as expanding `A_hat`.  Decode keys once and keep
+/// them in that case.
+#[derive(Clone, Debug)]
+pub struct VerifyingKey<P: ParameterSet, X: Xof = ShakeXof> {
     rho: B32,
     t1: Vector<P::K>,
 
     // Derived values
+    expanded: Lazy<ExpandedVerifyingKey<P>>,
+
+    xof: X,
+}
+
+// Keys are equal if their encodings are, whether or not they have been expanded
+impl<P: ParameterSet, X: Xof> PartialEq for VerifyingKey<P, X> {
+    fn eq(&self, other: &Self) -> bool {
+        self.rho == other.rho && self.t1 == other.t1 && self.xof == other.xof
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ExpandedVerifyingKey<P: ParameterSet> {
     A_hat: NttMatrix<P::K, P::L>,
     t1_2d_hat: NttVector<P::K>,
     tr: B64,
 }
 
-impl<P: MlDsaParams> VerifyingKey<P> {
-    fn new(
-        rho: B32,
-        t1: Vector<P::K>,
-        A_hat: Option<NttMatrix<P::K, P::L>>,
-        enc: Option<EncodedVerifyingKey<P>>,
-    ) -> Self {
-        let A_hat = A_hat.unwrap_or_else(|| expand_a(&rho));
-        let enc = enc.unwrap_or_else(|| Self::encode_internal(&rho, &t1));
+// A decoded key is expanded from its encoded components, with the matrix sampled from `rho`
+impl<P: MlDsaParams, X: Xof> Derive<(&B32, &Vector<P::K>, &X)> for ExpandedVerifyingKey<P> {
+    fn derive(&(rho, t1, xof): &(&B32, &Vector<P::K>, &X)) -> Self {
+        let (t1_2d_hat, tr) = VerifyingKey::<P, X>::expand_t1(rho, t1, xof);
+        ExpandedVerifyingKey {
+            A_hat: expand_a::<X, P::K, P::L>(xof, rho),
+            t1_2d_hat,
+            tr,
+        }
+    }
+}
 
-        let t1_2d_hat = (Elem::new(1 << 13) * &t1).ntt();
-        let tr: B64 = H::default().absorb(&enc).squeeze_new();
+impl<P: MlDsaParams, X: Xof> VerifyingKey<P, X> {
+    fn new(rho: B32, t1: Vector<P::K>, mut A_hat: Option<NttMatrix<P::K, P::L>>, xof: X) -> Self {
+        let () = P::VALID;
+
+        // A key that comes with its matrix is expanded right away, since the rest is cheap.  The
+        // frame that initializes the `OnceLock` already holds a copy of the expanded key, so the
+        // rest is computed before it, and the closure borrows the matrix rather than owning it,
+        // since `OnceLock` moves the closure several times before calling it.
+        let expanded = match A_hat {
+            Some(_) => {
+                let (t1_2d_hat, tr) = Self::expand_t1(&rho, &t1, &xof);
+                Lazy::ready(|| ExpandedVerifyingKey {
+                    A_hat: A_hat.take().unwrap(),
+                    t1_2d_hat,
+                    tr,
+                })
+            }
+            None => Lazy::new(&(&rho, &t1, &xof)),
+        };
 
         Self {
             rho,
             t1,
-            A_hat,
-            t1_2d_hat,
-            tr,
+            expanded,
+            xof,
         }
     }
 
+    // The values derived from `t1`, which are cheap next to the matrix
+    fn expand_t1(rho: &B32, t1: &Vector<P::K>, xof: &X) -> (NttVector<P::K>, B64) {
+        let t1_2d_hat = (Elem::new(1 << 13) * t1).ntt();
+        let tr: B64 = xof
+            .h()
+            .absorb(&Self::encode_internal(rho, t1))
+            .squeeze_new();
+
+        (t1_2d_hat, tr)
+    }
+
+    fn expanded(&self) -> &ExpandedVerifyingKey<P> {
+        self.expanded.get(&(&self.rho, &self.t1, &self.xof))
+    }
+
+    /// The hash `tr` of the encoded key
+    pub(crate) fn tr(&self) -> &B64 {
+        &self.expanded().tr
+    }
+
+    /// Compute the values derived from the key now, rather than in the first operation that
+    /// needs them.  This is only useful with the `std` feature, and has no other effect.
+    pub fn precompute(&self) {
+        let _ = self.expanded();
+    }
+
+    /// Use the specified XOF configuration for all further operations with this key.
+    ///
+    /// Keys of the same type always produce the same output, so this is only useful for backends
+    /// that carry tuning parameters, such as [`AesXof`].
+    #[must_use]
+    pub fn with_xof(mut self, xof: X) -> Self {
+        self.xof = xof;
+        self
+    }
+
+    /// The XOF configuration used by this key
+    pub fn xof(&self) -> &X {
+        &self.xof
+    }
+
     /// This algorithm reflects the ML-DSA.Verify_internal algorithm from FIPS 204.  It does not
     /// include the domain separator that distinguishes between the normal and pre-hashed cases,
     /// and it does not separate the context string from the rest of the message.
@@ -705,7 +1489,7 @@ impl<P: MlDsaParams> VerifyingKey<P> {
         P: MlDsaParams,
     {
         // Compute the message representative
-        let mu = message_representative(&self.tr, Mp);
+        let mu = message_representative(&self.xof, self.tr(), Mp);
         self.raw_verify_mu(&mu, sigma)
     }
 
@@ -713,24 +1497,17 @@ impl<P: MlDsaParams> VerifyingKey<P> {
     where
         P: MlDsaParams,
     {
-        // Reconstruct w
-        let c = sample_in_ball(&sigma.c_tilde, P::TAU);
-
-        let z_hat = sigma.z.ntt();
-        let c_hat = c.ntt();
-        let Az_hat = &self.A_hat * &z_hat;
-        let ct1_2d_hat = &c_hat * &self.t1_2d_hat;
-
-        let wp_approx = (&Az_hat - &ct1_2d_hat).ntt_inverse();
-        let w1p = sigma.h.use_hint(&wp_approx);
-
-        let w1p_tilde = P::encode_w1(&w1p);
-        let cp_tilde = H::default()
-            .absorb(mu)
-            .absorb(&w1p_tilde)
-            .squeeze_new::<P::Lambda>();
-
-        sigma.c_tilde == cp_tilde
+        let _timer = PhaseTimer::start(&self.xof, Phase::Verify);
+        let expanded = self.expanded();
+        verify_mu(
+            &self.xof,
+            &expanded.A_hat,
+            &expanded.t1_2d_hat,
+            mu,
+            &sigma.c_tilde,
+            &sigma.z,
+            &sigma.h,
+        )
     }
 
     /// This algorithm reflects the ML-DSA.Verify algorithm from FIPS 204.
@@ -745,6 +1522,23 @@ impl<P: MlDsaParams> VerifyingKey<P> {
         self.raw_verify_mu(mu, sigma)
     }
 
+    /// This algorithm reflects the HashML-DSA.Verify algorithm from FIPS 204, for a `digest` of the
+    /// message computed with `PH`.
+    // Algorithm 5 HashML-DSA.Verify
+    pub fn verify_prehash_with_context<PH: Prehash>(
+        &self,
+        digest: &[u8],
+        ctx: &[u8],
+        sigma: &Signature<P>,
+    ) -> bool {
+        if ctx.len() > 255 || digest.len() != PH::OutputSize::USIZE {
+            return false;
+        }
+
+        let Mp: &[&[&[u8]]] = &[&[&[1], &[Truncate::truncate(ctx.len())], ctx, PH::OID, digest]];
+        self.raw_verify_internal(Mp, sigma)
+    }
+
     fn raw_verify_with_context(&self, M: &[&[u8]], ctx: &[u8], sigma: &Signature<P>) -> bool {
         if ctx.len() > 255 {
             return false;
@@ -766,21 +1560,32 @@ impl<P: MlDsaParams> VerifyingKey<P> {
     }
 
     /// Decode the key from an appropriately sized byte array.
+    ///
+    /// With the `std` feature this is cheap, and the derived values are computed on first use.
+    /// Without it, decoding computes them right away.
     // Algorithm 23 pkDecode
     pub fn decode(enc: &EncodedVerifyingKey<P>) -> Self {
         let (rho, t1_enc) = P::split_vk(enc);
         let t1 = P::decode_t1(t1_enc);
-        Self::new(rho.clone(), t1, None, Some(enc.clone()))
+        Self::new(rho.clone(), t1, None, X::default())
     }
 }
 
-impl<P: MlDsaParams> signature::Verifier<Signature<P>> for VerifyingKey<P> {
+impl<P: MlDsaParams> VerifyingKey<P, AesXof> {
+    /// Use the specified AES keystream buffering for all further operations with this key.
+    #[must_use]
+    pub fn with_aes_params(self, params: AesParams) -> Self {
+        self.with_xof(AesXof::new(params))
+    }
+}
+
+impl<P: MlDsaParams, X: Xof> signature::Verifier<Signature<P>> for VerifyingKey<P, X> {
     fn verify(&self, msg: &[u8], signature: &Signature<P>) -> Result<(), Error> {
         self.multipart_verify(&[msg], signature)
     }
 }
 
-impl<P: MlDsaParams> MultipartVerifier<Signature<P>> for VerifyingKey<P> {
+impl<P: MlDsaParams, X: Xof> MultipartVerifier<Signature<P>> for VerifyingKey<P, X> {
     fn multipart_verify(&self, msg: &[&[u8]], signature: &Signature<P>) -> Result<(), Error> {
         self.raw_verify_with_context(msg, &[], signature)
             .then_some(())
@@ -788,30 +1593,49 @@ impl<P: MlDsaParams> MultipartVerifier<Signature<P>> for VerifyingKey<P> {
     }
 }
 
+/// The `PrehashVerifier` implementation for `VerifyingKey` only supports signatures with an empty
+/// context string.  If the signature has a context string, use the
+/// [`VerifyingKey::verify_prehash_with_context`] method.
+impl<P: MlDsaParams, X: Xof, PH: Prehash> PrehashVerifier<HashSignature<P, PH>>
+    for VerifyingKey<P, X>
+{
+    fn verify_prehash(
+        &self,
+        prehash: &[u8],
+        signature: &HashSignature<P, PH>,
+    ) -> Result<(), Error> {
+        self.verify_prehash_with_context::<PH>(prehash, &[], signature.signature())
+            .then_some(())
+            .ok_or(Error::new())
+    }
+}
+
 #[cfg(feature = "pkcs8")]
-impl<P> SignatureAlgorithmIdentifier for VerifyingKey<P>
+impl<P, X> SignatureAlgorithmIdentifier for VerifyingKey<P, X>
 where
     P: MlDsaParams,
     P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
 {
     type Params = AnyRef<'static>;
 
     const SIGNATURE_ALGORITHM_IDENTIFIER: AlgorithmIdentifier<Self::Params> =
-        Signature::<P>::ALGORITHM_IDENTIFIER;
+        algorithm_identifier::<P, X>();
 }
 
 #[cfg(all(feature = "alloc", feature = "pkcs8"))]
-impl<P> EncodePublicKey for VerifyingKey<P>
+impl<P, X> EncodePublicKey for VerifyingKey<P, X>
 where
     P: MlDsaParams,
     P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
 {
     fn to_public_key_der(&self) -> spki::Result<der::Document> {
         let public_key = self.encode();
         let subject_public_key = BitStringRef::new(0, &public_key)?;
 
         SubjectPublicKeyInfo {
-            algorithm: P::ALGORITHM_IDENTIFIER,
+            algorithm: algorithm_identifier::<P, X>(),
             subject_public_key,
         }
         .try_into()
@@ -819,30 +1643,82 @@ where
 }
 
 #[cfg(feature = "pkcs8")]
-impl<P> TryFrom<SubjectPublicKeyInfoRef<'_>> for VerifyingKey<P>
+impl<P, X> TryFrom<SubjectPublicKeyInfoRef<'_>> for VerifyingKey<P, X>
 where
     P: MlDsaParams,
     P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
 {
     type Error = spki::Error;
 
     fn try_from(spki: SubjectPublicKeyInfoRef<'_>) -> spki::Result<Self> {
-        match spki.algorithm {
-            alg if alg == P::ALGORITHM_IDENTIFIER => {}
-            other => return Err(spki::Error::OidUnknown { oid: other.oid }),
-        }
+        Ok(Self::decode(&encoded_verifying_key::<P, X>(&spki)?))
+    }
+}
 
-        Ok(Self::decode(
-            &EncodedVerifyingKey::<P>::try_from(
-                spki.subject_public_key
-                    .as_bytes()
-                    .ok_or_else(|| der::Tag::BitString.value_error().to_error())?,
-            )
-            .map_err(|_| pkcs8::Error::KeyMalformed)?,
-        ))
+/// The algorithm identifier of keys with parameter set `P` and backend `X`.  This is the FIPS 204
+/// identifier for the standard backend, and one under the backend's arc for the others.
+#[cfg(feature = "pkcs8")]
+const fn algorithm_identifier<P, X>() -> AlgorithmIdentifierRef<'static>
+where
+    P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
+{
+    let Some(arc) = X::ALGORITHM_ARC else {
+        return P::ALGORITHM_IDENTIFIER;
+    };
+
+    // The FIPS 204 identifiers end in an arc that fits in a single byte
+    let standard = P::ALGORITHM_IDENTIFIER.oid.as_bytes();
+    #[allow(clippy::as_conversions)]
+    let last = standard[standard.len() - 1] as u32;
+
+    let Ok(oid) = ObjectIdentifier::new_unwrap(arc).push_arc(last) else {
+        panic!("the algorithm identifier of the backend is too long");
+    };
+
+    AlgorithmIdentifierRef {
+        oid,
+        parameters: None,
     }
 }
 
+/// Check that a key was encoded with parameter set `P` and backend `X`.  A key of another
+/// parameter set or backend, including a standard ML-DSA key given to a non-standard backend and
+/// vice versa, is rejected with [`spki::Error::OidUnknown`], naming the identifier that was found.
+#[cfg(feature = "pkcs8")]
+fn check_algorithm<P, X>(algorithm: AlgorithmIdentifierRef<'_>) -> spki::Result<()>
+where
+    P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
+{
+    if algorithm == algorithm_identifier::<P, X>() {
+        Ok(())
+    } else {
+        Err(spki::Error::OidUnknown { oid: algorithm.oid })
+    }
+}
+
+/// Check the algorithm of a `SubjectPublicKeyInfo` and extract the encoded key
+#[cfg(feature = "pkcs8")]
+fn encoded_verifying_key<P, X>(
+    spki: &SubjectPublicKeyInfoRef<'_>,
+) -> spki::Result<EncodedVerifyingKey<P>>
+where
+    P: MlDsaParams,
+    P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    X: Xof,
+{
+    check_algorithm::<P, X>(spki.algorithm)?;
+
+    EncodedVerifyingKey::<P>::try_from(
+        spki.subject_public_key
+            .as_bytes()
+            .ok_or_else(|| der::Tag::BitString.value_error().to_error())?,
+    )
+    .map_err(|_| pkcs8::Error::KeyMalformed.into())
+}
+
 /// `MlDsa44` is the parameter set for security category 2.
 #[derive(Default, Clone, Debug, PartialEq)]
 pub struct MlDsa44;
@@ -925,6 +1801,9 @@ impl AssociatedAlgorithmIdentifier for MlDsa87 {
 }
 
 /// A parameter set that knows how to generate key pairs
+///
+/// Key pairs generated through this trait use the standard SHAKE hash functions.  To generate a key
+/// pair with another [`Xof`], use [`KeyPair::generate`] or [`KeyPair::from_seed`].
 pub trait KeyGen: MlDsaParams {
     /// The type that is returned by key generation
     type KeyPair: signature::Keypair;
@@ -948,49 +1827,13 @@ where
     // Algorithm 1 ML-DSA.KeyGen()
     #[cfg(feature = "rand_core")]
     fn key_gen<R: CryptoRng + ?Sized>(rng: &mut R) -> KeyPair<P> {
-        let mut xi = B32::default();
-        rng.fill_bytes(&mut xi);
-        Self::key_gen_internal(&xi)
+        KeyPair::generate(rng)
     }
 
     /// Deterministically generate a signing key pair from the specified seed
     // Algorithm 6 ML-DSA.KeyGen_internal
-    fn key_gen_internal(xi: &B32) -> KeyPair<P>
-    where
-        P: MlDsaParams,
-    {
-        // Derive seeds
-        let mut h = H::default()
-            .absorb(xi)
-            .absorb(&[P::K::U8])
-            .absorb(&[P::L::U8]);
-
-        let rho: B32 = h.squeeze_new();
-        let rhop: B64 = h.squeeze_new();
-        let K: B32 = h.squeeze_new();
-
-        // Sample private key components
-        let A_hat = expand_a::<P::K, P::L>(&rho);
-        let s1 = expand_s::<P::L>(&rhop, P::Eta::ETA, 0);
-        let s2 = expand_s::<P::K>(&rhop, P::Eta::ETA, P::L::USIZE);
-
-        // Compute derived values
-        let As1_hat = &A_hat * &s1.ntt();
-        let t = &As1_hat.ntt_inverse() + &s2;
-
-        // Compress and encode
-        let (t1, t0) = t.power2round();
-
-        let verifying_key = VerifyingKey::new(rho, t1, Some(A_hat.clone()), None);
-        let signing_key =
-            SigningKey::new(rho, K, verifying_key.tr.clone(), s1, s2, t0, Some(A_hat));
-
-        KeyPair {
-            signing_key,
-            verifying_key,
-            #[cfg(feature = "pkcs8")]
-            seed: xi.clone(),
-        }
+    fn key_gen_internal(xi: &B32) -> KeyPair<P> {
+        KeyPair::from_seed(xi)
     }
 }
 
@@ -1036,7 +1879,7 @@ mod test {
 
         let M = b"Hello world";
         let rnd = Array([0u8; 32]);
-        let sig = sk.sign_internal(&[M], &rnd);
+        let sig = sk.sign_internal(&[M], &rnd).unwrap();
         let sig_bytes = sig.encode();
         let sig2 = Signature::<P>::decode(&sig_bytes).unwrap();
         assert!(sig == sig2);
@@ -1078,7 +1921,7 @@ mod test {
 
         let M = b"Hello world";
         let rnd = Array([0u8; 32]);
-        let sig = sk.sign_internal(&[M], &rnd);
+        let sig = sk.sign_internal(&[M], &rnd).unwrap();
 
         assert!(vk.verify_internal(&[M], &sig));
     }
@@ -1111,7 +1954,7 @@ mod test {
 
             let M = b"Hello world";
             let rnd = Array([0u8; 32]);
-            let sig = sk.sign_internal(&[M], &rnd);
+            let sig = sk.sign_internal(&[M], &rnd).unwrap();
 
             let sig_enc = sig.encode();
             let sig_dec = Signature::<P>::decode(&sig_enc).unwrap();
@@ -1121,6 +1964,56 @@ mod test {
         }
     }
 
+    fn xof_round_trip_test<P, X>() -> EncodedSignature<P>
+    where
+        P: MlDsaParams,
+        X: Xof,
+    {
+        let kp = KeyPair::<P, X>::from_seed(&Array::default());
+        let sk = kp.signing_key;
+        let vk = kp.verifying_key;
+
+        let vk2 = VerifyingKey::<P, X>::decode(&vk.encode());
+        assert!(vk == vk2);
+        let sk2 = SigningKey::<P, X>::decode(&sk.encode());
+        assert!(sk == sk2);
+
+        let M = b"Hello world";
+        let rnd = Array([0u8; 32]);
+        let sig = sk.sign_internal(&[M], &rnd).unwrap();
+        assert!(vk.verify_internal(&[M], &sig));
+
+        sig.encode()
+    }
+
+    #[test]
+    fn xof_backends_coexist() {
+        let shake = xof_round_trip_test::<MlDsa65, ShakeXof>();
+        let aes = xof_round_trip_test::<MlDsa65, AesXof>();
+        let blake3 = xof_round_trip_test::<MlDsa65, Blake3Xof>();
+        #[cfg(feature = "std")]
+        let blake3_optimized = xof_round_trip_test::<MlDsa65, Blake3OptimizedXof>();
+
+        // The default parameter is the standard hash function
+        let kp = MlDsa65::key_gen_internal(&Array::default());
+        let sig = kp
+            .signing_key
+            .sign_internal(&[b"Hello world"], &Array([0u8; 32]))
+            .unwrap();
+        assert_eq!(sig.encode(), shake);
+
+        // The two BLAKE3 implementations compute the same function; the others are distinct
+        #[cfg(feature = "std")]
+        assert_eq!(blake3, blake3_optimized);
+        assert_ne!(shake, aes);
+        assert_ne!(shake, blake3);
+        assert_ne!(aes, blake3);
+
+        // A signature produced with one backend does not verify under another
+        let sig = Signature::<MlDsa65>::decode(&blake3).unwrap();
+        assert!(!kp.verifying_key.verify_internal(&[b"Hello world"], &sig));
+    }
+
     #[test]
     fn many_round_trip() {
         many_round_trip_test::<MlDsa44>();
@@ -1140,8 +2033,8 @@ mod test {
 
             let M = b"Hello world";
             let rnd = Array([0u8; 32]);
-            let mu = message_representative(&sk.tr, &[&[M]]);
-            let sig = sk.raw_sign_mu(&mu, &rnd);
+            let mu = message_representative(&ShakeXof, &sk.tr, &[&[M]]);
+            let sig = sk.raw_sign_mu(&mu, &rnd).unwrap();
 
             assert!(vk.raw_verify_mu(&mu, &sig));
         }
@@ -1162,8 +2055,8 @@ mod test {
 
             let M = b"Hello world";
             let rnd = Array([0u8; 32]);
-            let mu = message_representative(&sk.tr, &[&[M]]);
-            let sig = sk.raw_sign_mu(&mu, &rnd);
+            let mu = message_representative(&ShakeXof, &sk.tr, &[&[M]]);
+            let sig = sk.raw_sign_mu(&mu, &rnd).unwrap();
 
             assert!(vk.verify_internal(&[M], &sig));
         }
@@ -1184,8 +2077,8 @@ mod test {
 
             let M = b"Hello world";
             let rnd = Array([0u8; 32]);
-            let mu = message_representative(&sk.tr, &[&[M]]);
-            let sig = sk.sign_internal(&[M], &rnd);
+            let mu = message_representative(&ShakeXof, &sk.tr, &[&[M]]);
+            let sig = sk.sign_internal(&[M], &rnd).unwrap();
 
             assert!(vk.raw_verify_mu(&mu, &sig));
         }
@@ -1193,4 +2086,113 @@ mod test {
         sign_internal_verify_mu::<MlDsa65>();
         sign_internal_verify_mu::<MlDsa87>();
     }
+
+    #[test]
+    fn rejection_budget() {
+        let sk = MlDsa65::key_gen_internal(&Array([6u8; 32])).signing_key;
+        assert_eq!(sk.rejection_budget(), None);
+
+        let none = sk.clone().with_rejection_budget(0);
+        assert_eq!(none.rejection_budget(), Some(0));
+        assert_eq!(
+            none.sign_deterministic(b"msg", &[]),
+            Err(SigningError::RejectionBudgetExceeded { iterations: 0 })
+        );
+
+        // With a budget of one iteration, each message either gets its usual signature or fails
+        let one = sk.clone().with_rejection_budget(1);
+        let mut failures = 0;
+        for i in 0u8..16 {
+            match one.sign_deterministic(&[i], &[]) {
+                Ok(sig) => assert_eq!(sig, sk.sign_deterministic(&[i], &[]).unwrap()),
+                Err(err) => {
+                    assert_eq!(err, SigningError::RejectionBudgetExceeded { iterations: 1 });
+                    failures += 1;
+                }
+            }
+        }
+        assert!(0 < failures && failures < 16);
+
+        // The traits of the `signature` crate report the error as their source
+        let err = signature::Signer::<Signature<MlDsa65>>::try_sign(&none, b"msg").unwrap_err();
+        #[cfg(feature = "alloc")]
+        assert_eq!(
+            core::error::Error::source(&err)
+                .and_then(<dyn core::error::Error>::downcast_ref::<SigningError>),
+            Some(&SigningError::RejectionBudgetExceeded { iterations: 0 })
+        );
+        let _ = err;
+    }
+
+    #[test]
+    fn signing_errors() {
+        let sk = MlDsa44::key_gen_internal(&Array([7u8; 32])).signing_key;
+
+        let long = [0u8; 256];
+        assert_eq!(
+            sk.sign_deterministic(b"msg", &long),
+            Err(SigningError::ContextTooLong { len: 256 })
+        );
+        assert_eq!(
+            sk.sign_prehash_deterministic::<prehash::Sha256>(&[0; 32], &long),
+            Err(SigningError::ContextTooLong { len: 256 })
+        );
+        assert_eq!(
+            sk.sign_prehash_deterministic::<prehash::Sha256>(&[0; 31], &[]),
+            Err(SigningError::InvalidDigestLength {
+                expected: 32,
+                len: 31
+            })
+        );
+        assert!(sk.sign_deterministic(b"msg", &long[..255]).is_ok());
+    }
+
+    #[cfg(feature = "rand_core")]
+    #[test]
+    fn rng_failure() {
+        struct FailingRng;
+
+        impl rand_core::TryRngCore for FailingRng {
+            type Error = core::fmt::Error;
+
+            fn try_next_u32(&mut self) -> Result<u32, Self::Error> {
+                Err(core::fmt::Error)
+            }
+
+            fn try_next_u64(&mut self) -> Result<u64, Self::Error> {
+                Err(core::fmt::Error)
+            }
+
+            fn try_fill_bytes(&mut self, _: &mut [u8]) -> Result<(), Self::Error> {
+                Err(core::fmt::Error)
+            }
+        }
+
+        impl TryCryptoRng for FailingRng {}
+
+        let sk = MlDsa44::key_gen_internal(&Array([8u8; 32])).signing_key;
+        let mu = B64::default();
+        assert_eq!(
+            sk.sign_randomized(b"msg", &[], &mut FailingRng),
+            Err(SigningError::RngFailure)
+        );
+        assert_eq!(
+            sk.sign_mu_randomized(&mu, &mut FailingRng),
+            Err(SigningError::RngFailure)
+        );
+        assert_eq!(
+            sk.sign_prehash_randomized::<prehash::Sha256, _>(&[0; 32], &[], &mut FailingRng),
+            Err(SigningError::RngFailure)
+        );
+        assert_eq!(
+            sk.presign(&mut FailingRng).map(|_| ()),
+            Err(SigningError::RngFailure)
+        );
+
+        // The context string is checked first
+        assert_eq!(
+            sk.sign_randomized(b"msg", &[0; 256], &mut FailingRng),
+            Err(SigningError::ContextTooLong { len: 256 })
+        );
+    }
 }
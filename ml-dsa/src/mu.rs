@@ -0,0 +1,124 @@
+use crate::algebra::Truncate;
+use crate::crypto::ShakeXof;
+use crate::param::MlDsaParams;
+use crate::util::B64;
+use crate::xof::{Xof, XofState};
+use crate::{SigningError, VerifyingKey, check_context};
+
+/// Computes the message representative μ of ML-DSA.Sign for a message that arrives in pieces.
+///
+/// The result can be passed to [`SigningKey::sign_mu_deterministic`],
+/// [`SigningKey::sign_mu_randomized`] or [`VerifyingKey::verify_mu`], so that the message itself
+/// never has to reach the holder of the signing key.  Only the verifying key is needed to compute
+/// μ, and the builder uses the same XOF backend as the key.
+///
+/// ```
+/// use ml_dsa::{KeyGen, MlDsa65, MuBuilder};
+///
+/// let kp = MlDsa65::key_gen_internal(&Default::default());
+/// let vk = kp.verifying_key();
+///
+/// let mut mu = MuBuilder::new(vk, b"context").unwrap();
+/// mu.update(b"Hello ");
+/// mu.update(b"world");
+/// let mu = mu.finalize();
+///
+/// let sig = kp.signing_key().sign_mu_deterministic(&mu).unwrap();
+/// assert!(vk.verify_with_context(b"Hello world", b"context", &sig));
+/// ```
+///
+/// [`SigningKey::sign_mu_deterministic`]: crate::SigningKey::sign_mu_deterministic
+/// [`SigningKey::sign_mu_randomized`]: crate::SigningKey::sign_mu_randomized
+pub struct MuBuilder<X: Xof = ShakeXof> {
+    // Always `Some` outside of `update`
+    h: Option<X::H>,
+}
+
+impl<X: Xof> MuBuilder<X> {
+    /// Start computing μ for a message signed by `vk` with the context string `ctx`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return [`SigningError::ContextTooLong`] if the context string is more
+    /// than 255 bytes long.
+    // Algorithm 2 ML-DSA.Sign, lines 10 and 23 (M' = 0 || |ctx| || ctx || M)
+    pub fn new<P: MlDsaParams>(vk: &VerifyingKey<P, X>, ctx: &[u8]) -> Result<Self, SigningError> {
+        check_context(ctx)?;
+
+        let h = vk
+            .xof
+            .h()
+            .absorb(vk.tr())
+            .absorb(&[0])
+            .absorb(&[Truncate::truncate(ctx.len())])
+            .absorb(ctx);
+        Ok(Self { h: Some(h) })
+    }
+
+    /// Absorb the next piece of the message
+    pub fn update(&mut self, data: &[u8]) -> &mut Self {
+        self.h = self.h.take().map(|h| h.absorb(data));
+        self
+    }
+
+    /// Finish the message and return μ
+    #[must_use]
+    pub fn finalize(self) -> B64 {
+        let Some(mut h) = self.h else {
+            unreachable!("MuBuilder state is only taken within update")
+        };
+        h.squeeze_new()
+    }
+}
+
+impl<X: Xof> core::fmt::Debug for MuBuilder<X> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MuBuilder")
+            .field("xof", &X::NAME)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AesXof, Blake3Xof, KeyPair, MlDsa44, MlDsa65, MlDsa87, message_representative};
+    use hybrid_array::Array;
+
+    fn streamed_mu<P: MlDsaParams, X: Xof>() {
+        let kp = KeyPair::<P, X>::from_seed(&Array([7u8; 32]));
+        let (sk, vk) = (kp.signing_key(), kp.verifying_key());
+        let msg = [0x5au8; 1000];
+        let ctx = b"streamed";
+
+        let mut builder = MuBuilder::new(vk, ctx).unwrap();
+        for chunk in msg.chunks(77) {
+            builder.update(chunk);
+        }
+        let mu = builder.finalize();
+
+        let expected = message_representative(
+            &vk.xof,
+            vk.tr(),
+            &[&[&[0], &[Truncate::truncate(ctx.len())], ctx], &[&msg]],
+        );
+        assert_eq!(mu, expected);
+
+        let sig = sk.sign_mu_deterministic(&mu).unwrap();
+        assert_eq!(sig, sk.sign_deterministic(&msg, ctx).unwrap());
+        assert!(vk.verify_with_context(&msg, ctx, &sig));
+        assert!(vk.verify_mu(&mu, &sig));
+
+        assert_eq!(
+            MuBuilder::new(vk, &[0; 256]).unwrap_err(),
+            SigningError::ContextTooLong { len: 256 }
+        );
+    }
+
+    #[test]
+    fn streamed_mu_matches_sign() {
+        streamed_mu::<MlDsa44, ShakeXof>();
+        streamed_mu::<MlDsa65, Blake3Xof>();
+        streamed_mu::<MlDsa87, AesXof>();
+    }
+}
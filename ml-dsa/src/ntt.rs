@@ -3,6 +3,8 @@ use crate::module_lattice::encode::ArraySize;
 use core::ops::Mul;
 
 use crate::algebra::{BaseField, Elem, NttPolynomial, NttVector, Polynomial, Vector};
+use crate::avx2::Avx2;
+use crate::secret::Secret;
 
 // Since the powers of zeta used in the NTT and MultiplyNTTs are fixed, we use pre-computed tables
 // to avoid the need to compute the exponetiations at runtime.
@@ -17,7 +19,7 @@ use crate::algebra::{BaseField, Elem, NttPolynomial, NttVector, Polynomial, Vect
 #[allow(clippy::cast_possible_truncation)]
 #[allow(clippy::as_conversions)]
 #[allow(clippy::integer_division_remainder_used)]
-const ZETA_POW_BITREV: [Elem; 256] = {
+pub(crate) const ZETA_POW_BITREV: [Elem; 256] = {
     const ZETA: u64 = 1753;
     const fn bitrev8(x: usize) -> usize {
         (x as u8).reverse_bits() as usize
@@ -53,28 +55,36 @@ pub trait Ntt {
 impl Ntt for Polynomial {
     type Output = NttPolynomial;
 
-    // Algorithm 41 NTT
     fn ntt(&self) -> Self::Output {
-        let mut w = self.0.clone();
-
-        let mut m = 0;
-        for len in [128, 64, 32, 16, 8, 4, 2, 1] {
-            for start in (0..256).step_by(2 * len) {
-                m += 1;
-                let z = ZETA_POW_BITREV[m];
-
-                for j in start..(start + len) {
-                    let t = z * w[j + len];
-                    w[j + len] = w[j] - t;
-                    w[j] = w[j] + t;
-                }
-            }
+        if let Some(avx2) = Avx2::detect() {
+            return avx2.ntt(self);
         }
 
-        NttPolynomial::new(w)
+        ntt_scalar(self)
     }
 }
 
+// Algorithm 41 NTT
+pub(crate) fn ntt_scalar(f: &Polynomial) -> NttPolynomial {
+    let mut w = f.0.clone();
+
+    let mut m = 0;
+    for len in [128, 64, 32, 16, 8, 4, 2, 1] {
+        for start in (0..256).step_by(2 * len) {
+            m += 1;
+            let z = ZETA_POW_BITREV[m];
+
+            for j in start..(start + len) {
+                let t = z * w[j + len];
+                w[j + len] = w[j] - t;
+                w[j] = w[j] + t;
+            }
+        }
+    }
+
+    NttPolynomial::new(w)
+}
+
 impl<K: ArraySize> Ntt for Vector<K> {
     type Output = NttVector<K>;
 
@@ -92,28 +102,36 @@ pub trait NttInverse {
 impl NttInverse for NttPolynomial {
     type Output = Polynomial;
 
-    // Algorithm 42 NTT^{−1}
     fn ntt_inverse(&self) -> Self::Output {
-        const INVERSE_256: Elem = Elem::new(8_347_681);
+        if let Some(avx2) = Avx2::detect() {
+            return avx2.ntt_inverse(self);
+        }
 
-        let mut w = self.0.clone();
+        ntt_inverse_scalar(self)
+    }
+}
 
-        let mut m = 256;
-        for len in [1, 2, 4, 8, 16, 32, 64, 128] {
-            for start in (0..256).step_by(2 * len) {
-                m -= 1;
-                let z = -ZETA_POW_BITREV[m];
+// Algorithm 42 NTT^{−1}
+pub(crate) fn ntt_inverse_scalar(f_hat: &NttPolynomial) -> Polynomial {
+    const INVERSE_256: Elem = Elem::new(8_347_681);
 
-                for j in start..(start + len) {
-                    let t = w[j];
-                    w[j] = t + w[j + len];
-                    w[j + len] = z * (t - w[j + len]);
-                }
+    let mut w = f_hat.0.clone();
+
+    let mut m = 256;
+    for len in [1, 2, 4, 8, 16, 32, 64, 128] {
+        for start in (0..256).step_by(2 * len) {
+            m -= 1;
+            let z = -ZETA_POW_BITREV[m];
+
+            for j in start..(start + len) {
+                let t = w[j];
+                w[j] = t + w[j + len];
+                w[j + len] = z * (t - w[j + len]);
             }
         }
-
-        INVERSE_256 * &Polynomial::new(w)
     }
+
+    INVERSE_256 * &*Secret::new(Polynomial::new(w))
 }
 
 impl<K: ArraySize> NttInverse for NttVector<K> {
@@ -127,18 +145,27 @@ impl<K: ArraySize> NttInverse for NttVector<K> {
 impl Mul<&NttPolynomial> for &NttPolynomial {
     type Output = NttPolynomial;
 
-    // Algorithm 45 MultiplyNTT
     fn mul(self, rhs: &NttPolynomial) -> NttPolynomial {
-        NttPolynomial::new(
-            self.0
-                .iter()
-                .zip(rhs.0.iter())
-                .map(|(&x, &y)| x * y)
-                .collect(),
-        )
+        if let Some(avx2) = Avx2::detect() {
+            return avx2.multiply_ntts(self, rhs);
+        }
+
+        multiply_ntts_scalar(self, rhs)
     }
 }
 
+// Algorithm 45 MultiplyNTT
+pub(crate) fn multiply_ntts_scalar(f_hat: &NttPolynomial, g_hat: &NttPolynomial) -> NttPolynomial {
+    NttPolynomial::new(
+        f_hat
+            .0
+            .iter()
+            .zip(g_hat.0.iter())
+            .map(|(&x, &y)| x * y)
+            .collect(),
+    )
+}
+
 #[cfg(test)]
 #[allow(clippy::as_conversions)]
 #[allow(clippy::cast_possible_truncation)]
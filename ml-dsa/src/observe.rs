@@ -0,0 +1,227 @@
+use core::fmt;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use crate::xof::{Xof, XofState};
+
+/// The hash function role that a state fills
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    /// The role of SHAKE128 (`ExpandA`)
+    G,
+    /// The role of SHAKE256 (everything except `ExpandA`)
+    H,
+}
+
+/// A timed step of an ML-DSA operation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// Key generation from a seed, including `ExpandA`
+    KeyGen,
+    /// Expansion of the public matrix `A_hat` from `rho`
+    ExpandA,
+    /// The rejection sampling loop of signing, from `mu` to a signature
+    Sign,
+    /// The computation of a candidate for a later signature, by `SigningKey::presign`
+    Presign,
+    /// Verification of a signature against `mu`
+    Verify,
+}
+
+/// Receives reports of what an ML-DSA key is doing.
+///
+/// All methods do nothing by default.  An observer is attached to a key by using the
+/// [`Observed`] backend, so keys without one pay nothing.
+pub trait Observer {
+    /// A hash state was started with the named backend
+    fn start(&self, backend: &'static str, role: Role) {
+        let _ = (backend, role);
+    }
+
+    /// Bytes were absorbed into a hash state
+    fn absorb(&self, role: Role, bytes: usize) {
+        let _ = (role, bytes);
+    }
+
+    /// Bytes were squeezed from a hash state
+    fn squeeze(&self, role: Role, bytes: usize) {
+        let _ = (role, bytes);
+    }
+
+    /// Signing found a valid signature on the `iterations`-th candidate
+    fn rejection_sampling(&self, iterations: usize) {
+        let _ = iterations;
+    }
+
+    /// A phase of an operation finished after `elapsed`.  Only reported with the `std` feature,
+    /// which provides the clock.
+    fn phase(&self, phase: Phase, elapsed: Duration) {
+        let _ = (phase, elapsed);
+    }
+}
+
+/// A backend that reports the activity of another backend `X` to an [`Observer`].
+///
+/// The output is exactly that of `X`.  Each hash state holds a clone of the observer, so `O` should
+/// be cheap to clone, e.g. a unit struct or an `Arc`.
+#[derive(Clone, Default)]
+pub struct Observed<X, O> {
+    xof: X,
+    observer: O,
+}
+
+impl<X: Xof, O: Observer> Observed<X, O> {
+    /// Report the activity of `xof` to `observer`
+    pub fn new(xof: X, observer: O) -> Self {
+        Self { xof, observer }
+    }
+
+    /// The observed backend
+    pub fn inner(&self) -> &X {
+        &self.xof
+    }
+}
+
+impl<X: fmt::Debug, O> fmt::Debug for Observed<X, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Observed")
+            .field("xof", &self.xof)
+            .finish_non_exhaustive()
+    }
+}
+
+// The observer has no bearing on the output, so it is not compared.
+impl<X: PartialEq, O> PartialEq for Observed<X, O> {
+    fn eq(&self, other: &Self) -> bool {
+        self.xof == other.xof
+    }
+}
+
+impl<X: Eq, O> Eq for Observed<X, O> {}
+
+impl<X, O> Xof for Observed<X, O>
+where
+    X: Xof,
+    O: Observer + Clone + Default + 'static,
+{
+    type G = ObservedState<X::G, O>;
+    type H = ObservedState<X::H, O>;
+
+    const NAME: &'static str = X::NAME;
+    const ALGORITHM_ARC: Option<&'static str> = X::ALGORITHM_ARC;
+
+    fn g(&self) -> Self::G {
+        self.observer.start(X::NAME, Role::G);
+        ObservedState::new(self.xof.g(), self.observer.clone(), Role::G)
+    }
+
+    fn h(&self) -> Self::H {
+        self.observer.start(X::NAME, Role::H);
+        ObservedState::new(self.xof.h(), self.observer.clone(), Role::H)
+    }
+
+    fn observer(&self) -> Option<&dyn Observer> {
+        Some(&self.observer)
+    }
+}
+
+/// A hash state that reports its activity to an [`Observer`]
+pub struct ObservedState<S, O> {
+    state: S,
+    observer: O,
+    role: Role,
+}
+
+impl<S, O: Observer> ObservedState<S, O> {
+    fn new(state: S, observer: O, role: Role) -> Self {
+        Self {
+            state,
+            observer,
+            role,
+        }
+    }
+}
+
+impl<S: XofState, O: Observer> XofState for ObservedState<S, O> {
+    fn absorb(self, input: &[u8]) -> Self {
+        self.observer.absorb(self.role, input.len());
+        Self {
+            state: self.state.absorb(input),
+            ..self
+        }
+    }
+
+    fn squeeze(&mut self, output: &mut [u8]) -> &mut Self {
+        self.observer.squeeze(self.role, output.len());
+        self.state.squeeze(output);
+        self
+    }
+}
+
+/// Reports the time from its creation to its drop as a [`Phase`], if the backend has an observer
+#[cfg(feature = "std")]
+pub(crate) struct PhaseTimer<'a> {
+    observer: Option<(&'a dyn Observer, Instant)>,
+    phase: Phase,
+}
+
+#[cfg(feature = "std")]
+impl<'a> PhaseTimer<'a> {
+    pub(crate) fn start<X: Xof>(xof: &'a X, phase: Phase) -> Self {
+        Self {
+            observer: xof.observer().map(|observer| (observer, Instant::now())),
+            phase,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for PhaseTimer<'_> {
+    fn drop(&mut self) {
+        if let Some((observer, start)) = self.observer {
+            observer.phase(self.phase, start.elapsed());
+        }
+    }
+}
+
+/// Without a clock, phases are not timed
+#[cfg(not(feature = "std"))]
+pub(crate) struct PhaseTimer;
+
+#[cfg(not(feature = "std"))]
+impl PhaseTimer {
+    pub(crate) fn start<X: Xof>(_xof: &X, _phase: Phase) -> Self {
+        Self
+    }
+}
+
+/// Reports everything to [`tracing`] at the `TRACE` level, except for rejection sampling and phase
+/// timing, which are reported at the `DEBUG` level.  Nothing is emitted unless a subscriber is
+/// installed.
+#[cfg(feature = "tracing")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TracingObserver;
+
+#[cfg(feature = "tracing")]
+impl Observer for TracingObserver {
+    fn start(&self, backend: &'static str, role: Role) {
+        tracing::trace!(backend, ?role, "start");
+    }
+
+    fn absorb(&self, role: Role, bytes: usize) {
+        tracing::trace!(?role, bytes, "absorb");
+    }
+
+    fn squeeze(&self, role: Role, bytes: usize) {
+        tracing::trace!(?role, bytes, "squeeze");
+    }
+
+    fn rejection_sampling(&self, iterations: usize) {
+        tracing::debug!(iterations, "rejection sampling");
+    }
+
+    fn phase(&self, phase: Phase, elapsed: Duration) {
+        tracing::debug!(?phase, ?elapsed, "phase");
+    }
+}
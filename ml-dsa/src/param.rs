@@ -33,6 +33,7 @@ use crate::util::{B32, B64};
 /// Some useful compile-time constants
 pub type SpecQ = Sum<Diff<Shleft<U1, U23>, Shleft<U1, U13>>, U1>;
 pub type SpecD = U13;
+/// q - 1, of which γ2 is a fraction, as in `Quot<QMinus1, U88>` for ML-DSA-44
 pub type QMinus1 = Diff<SpecQ, U1>;
 pub type BitlenQMinusD = Diff<Length<SpecQ>, SpecD>;
 pub type Pow2DMinus1 = Shleft<U1, Diff<SpecD, U1>>;
@@ -76,8 +77,79 @@ where
     }
 }
 
-/// A `ParameterSet` captures the parameters that describe a particular instance of ML-DSA.  There
-/// are three variants, corresponding to three different security levels.
+/// A `ParameterSet` captures the parameters that describe a particular instance of ML-DSA.
+///
+/// FIPS 204 defines three, [`MlDsa44`], [`MlDsa65`] and [`MlDsa87`], corresponding to three
+/// different security levels.  Other sets can be defined for research, by implementing this trait
+/// on a type that also derives `Clone`, `Debug`, `Default` and `PartialEq`:
+///
+/// ```
+/// use ml_dsa::typenum::{Diff, Length, Prod, Quot, Shleft, U1, U2, U4, U5, U17, U32, U75, U88};
+/// use ml_dsa::{KeyPair, ParameterSet, QMinus1};
+///
+/// /// ML-DSA-44 with a fifth row in the matrix
+/// #[derive(Clone, Debug, Default, PartialEq)]
+/// struct MlDsa54;
+///
+/// impl ParameterSet for MlDsa54 {
+///     type K = U5;
+///     type L = U4;
+///     type Eta = U2;
+///     type Gamma1 = Shleft<U1, U17>;
+///     type Gamma2 = Quot<QMinus1, U88>;
+///     type TwoGamma2 = Prod<U2, Self::Gamma2>;
+///     type W1Bits = Length<Diff<Quot<U88, U2>, U1>>;
+///     type Lambda = U32;
+///     type Omega = U75;
+///     const TAU: usize = 39;
+/// }
+///
+/// let kp = KeyPair::<MlDsa54>::from_seed(&Default::default());
+/// let sig = kp.signing_key().sign_deterministic(b"message", &[]).unwrap();
+/// assert!(kp.verifying_key().verify_with_context(b"message", &[], &sig));
+/// assert_eq!(sig.encode().len(), 2416);
+/// ```
+///
+/// The sizes of keys and signatures are derived from the parameters.  Each of them, and the sizes
+/// of their components, must be a length that `hybrid-array` supports, which otherwise causes
+/// errors about missing `ArraySize` implementations.  That is the case for all lengths up to 512
+/// bytes, and for multiples of 16 bytes up to 4080.  Beyond that, parameter sets are checked when
+/// keys and signatures are built, and inconsistent ones are rejected at compile time:
+///
+/// * `K` and `L` must be between 1 and 256, and `Eta` must be 2 or 4.
+/// * `TAU` must be between 1 and 64, and β = τ·η must be less than both γ1 and γ2.
+/// * γ1 must be a power of two, below (q - 1) / 2.
+/// * `TwoGamma2` must be 2·γ2, must divide q - 1, and must be greater than √q.
+/// * `W1Bits` must be the bit length of (q - 1) / (2·γ2) - 1, the largest coefficient of w1.
+/// * `Lambda` must be at least 32 bytes, for a collision strength of at least 128 bits.
+/// * `Omega` must be between 1 and 255, so that hint positions fit in a byte.
+///
+/// ```compile_fail
+/// # use ml_dsa::typenum::{Prod, Quot, Shleft, U1, U2, U4, U17, U32, U80, U88};
+/// # use ml_dsa::{KeyPair, ParameterSet, QMinus1};
+/// #[derive(Clone, Debug, Default, PartialEq)]
+/// struct Inconsistent;
+///
+/// impl ParameterSet for Inconsistent {
+///     type K = U4;
+///     type L = U4;
+///     type Eta = U2;
+///     type Gamma1 = Shleft<U1, U17>;
+///     type Gamma2 = Quot<QMinus1, U88>;
+///     type TwoGamma2 = Prod<U2, Self::Gamma2>;
+///     // The width for ML-DSA-65, but w1 needs 6 bits with this GAMMA2
+///     type W1Bits = U4;
+///     type Lambda = U32;
+///     type Omega = U80;
+///     const TAU: usize = 39;
+/// }
+///
+/// let kp = KeyPair::<Inconsistent>::from_seed(&Default::default());
+/// ```
+///
+/// [`MlDsa44`]: crate::MlDsa44
+/// [`MlDsa65`]: crate::MlDsa65
+/// [`MlDsa87`]: crate::MlDsa87
 pub trait ParameterSet {
     /// Number of rows in the A matrix
     type K: ArraySize;
@@ -115,6 +187,72 @@ pub trait ParameterSet {
     const BETA: u32 = (Self::TAU as u32) * Self::Eta::U32;
 }
 
+/// The consistency checks of a `ParameterSet`, which fail to compile for a set that does not pass.
+/// The constructors of keys and signatures refer to `VALID`, so every set in use is checked.
+pub(crate) trait Validate: ParameterSet {
+    const VALID: ();
+}
+
+impl<P: ParameterSet> Validate for P {
+    const VALID: () = validate::<P>();
+}
+
+#[allow(clippy::as_conversions)]
+#[allow(clippy::integer_division_remainder_used)]
+const fn validate<P: ParameterSet>() {
+    let q = SpecQ::U32;
+    let gamma1 = P::Gamma1::U32;
+    let gamma2 = P::Gamma2::U32;
+    let two_gamma2 = P::TwoGamma2::U32;
+
+    assert!(
+        P::K::USIZE >= 1 && P::K::USIZE <= 256,
+        "K must be between 1 and 256"
+    );
+    assert!(
+        P::L::USIZE >= 1 && P::L::USIZE <= 256,
+        "L must be between 1 and 256"
+    );
+
+    // SampleInBall takes the signs of the nonzero coefficients from 64 bits
+    assert!(P::TAU >= 1 && P::TAU <= 64, "TAU must be between 1 and 64");
+    assert!(
+        P::BETA < gamma1,
+        "BETA = TAU * ETA must be less than GAMMA1"
+    );
+    assert!(
+        P::BETA < gamma2,
+        "BETA = TAU * ETA must be less than GAMMA2"
+    );
+
+    // ExpandMask samples y uniformly by taking bitlen(2 * gamma1 - 1) bits for each coefficient
+    assert!(gamma1.is_power_of_two(), "GAMMA1 must be a power of two");
+    assert!(gamma1 < (q - 1) / 2, "GAMMA1 must be less than (q - 1) / 2");
+
+    // Decompose and UseHint need 2 * gamma2 to divide q - 1, and the Barrett reduction by 2 *
+    // gamma2 needs it to be greater than sqrt(q)
+    assert!(two_gamma2 == 2 * gamma2, "TwoGamma2 must be 2 * GAMMA2");
+    assert!((q - 1) % two_gamma2 == 0, "2 * GAMMA2 must divide q - 1");
+    assert!(
+        (two_gamma2 as u64) * (two_gamma2 as u64) > (q as u64),
+        "2 * GAMMA2 must be greater than sqrt(q)"
+    );
+
+    let w1_max = (q - 1) / two_gamma2 - 1;
+    assert!(
+        P::W1Bits::U32 == u32::BITS - w1_max.leading_zeros(),
+        "W1Bits must be bitlen((q - 1) / (2 * GAMMA2) - 1)"
+    );
+
+    assert!(P::Lambda::USIZE >= 32, "Lambda must be at least 32 bytes");
+
+    // Hints are encoded as byte positions, followed by one running count per row
+    assert!(
+        P::Omega::USIZE >= 1 && P::Omega::USIZE <= 255,
+        "Omega must be between 1 and 255"
+    );
+}
+
 pub trait SigningKeyParams: ParameterSet {
     type S1Size: ArraySize;
     type S2Size: ArraySize;
@@ -0,0 +1,202 @@
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use hybrid_array::typenum::{U28, U32, U48, U64};
+
+use crate::module_lattice::encode::ArraySize;
+use crate::param::{EncodedSignature, MlDsaParams};
+use crate::{Error, Signature};
+
+#[cfg(feature = "pkcs8")]
+use {
+    crate::{MlDsa44, MlDsa65, MlDsa87},
+    const_oid::db::fips204,
+    pkcs8::{AlgorithmIdentifierRef, der::AnyRef, spki::AssociatedAlgorithmIdentifier},
+};
+
+#[cfg(all(feature = "alloc", feature = "pkcs8"))]
+use pkcs8::{
+    der::{self, asn1::BitString},
+    spki::SignatureBitStringEncoding,
+};
+
+/// A hash function or XOF that HashML-DSA may apply to a message before it is signed
+pub trait Prehash: Clone + Copy + Debug + Default + PartialEq + Eq + 'static {
+    /// The DER encoding of the function's object identifier, which is part of the signed message
+    const OID: &'static [u8];
+
+    /// The length of the digest.  SHAKE128 and SHAKE256 are used with 256- and 512-bit outputs.
+    type OutputSize: ArraySize;
+}
+
+macro_rules! prehash {
+    ($(#[$meta:meta])* $name:ident, $arc:literal, $size:ty) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+        pub struct $name;
+
+        impl Prehash for $name {
+            // 2.16.840.1.101.3.4.2.$arc (NIST hash algorithms)
+            const OID: &'static [u8] =
+                &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, $arc];
+
+            type OutputSize = $size;
+        }
+    };
+}
+
+prehash!(
+    /// SHA-224
+    Sha224, 0x04, U28
+);
+prehash!(
+    /// SHA-256
+    Sha256, 0x01, U32
+);
+prehash!(
+    /// SHA-384
+    Sha384, 0x02, U48
+);
+prehash!(
+    /// SHA-512
+    Sha512, 0x03, U64
+);
+prehash!(
+    /// SHA-512/224
+    Sha512_224, 0x05, U28
+);
+prehash!(
+    /// SHA-512/256
+    Sha512_256, 0x06, U32
+);
+prehash!(
+    /// SHA3-224
+    Sha3_224, 0x07, U28
+);
+prehash!(
+    /// SHA3-256
+    Sha3_256, 0x08, U32
+);
+prehash!(
+    /// SHA3-384
+    Sha3_384, 0x09, U48
+);
+prehash!(
+    /// SHA3-512
+    Sha3_512, 0x0a, U64
+);
+prehash!(
+    /// SHAKE128 with 256 bits of output
+    Shake128, 0x0b, U32
+);
+prehash!(
+    /// SHAKE256 with 512 bits of output
+    Shake256, 0x0c, U64
+);
+
+/// An ML-DSA signature over a digest computed with `PH` (HashML-DSA)
+///
+/// The encoding is the same as that of a [`Signature`]; the type records which hash function the
+/// signer applied, so that it can be used with the [`PrehashSigner`] and [`PrehashVerifier`]
+/// traits.
+///
+/// [`PrehashSigner`]: signature::hazmat::PrehashSigner
+/// [`PrehashVerifier`]: signature::hazmat::PrehashVerifier
+#[derive(Clone, PartialEq, Debug)]
+pub struct HashSignature<P: MlDsaParams, PH: Prehash> {
+    signature: Signature<P>,
+    prehash: PhantomData<PH>,
+}
+
+impl<P: MlDsaParams, PH: Prehash> HashSignature<P, PH> {
+    /// Mark `signature` as a HashML-DSA signature with `PH`
+    pub fn new(signature: Signature<P>) -> Self {
+        Self {
+            signature,
+            prehash: PhantomData,
+        }
+    }
+
+    /// The underlying signature
+    pub fn signature(&self) -> &Signature<P> {
+        &self.signature
+    }
+
+    /// Encode the signature in a fixed-size byte array.
+    pub fn encode(&self) -> EncodedSignature<P> {
+        self.signature.encode()
+    }
+
+    /// Decode the signature from an appropriately sized byte array.
+    pub fn decode(enc: &EncodedSignature<P>) -> Option<Self> {
+        Signature::decode(enc).map(Self::new)
+    }
+}
+
+impl<P: MlDsaParams, PH: Prehash> From<HashSignature<P, PH>> for Signature<P> {
+    fn from(signature: HashSignature<P, PH>) -> Self {
+        signature.signature
+    }
+}
+
+impl<'a, P: MlDsaParams, PH: Prehash> TryFrom<&'a [u8]> for HashSignature<P, PH> {
+    type Error = Error;
+
+    fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
+        Signature::try_from(value).map(Self::new)
+    }
+}
+
+impl<P: MlDsaParams, PH: Prehash> TryInto<EncodedSignature<P>> for HashSignature<P, PH> {
+    type Error = Error;
+
+    fn try_into(self) -> Result<EncodedSignature<P>, Self::Error> {
+        Ok(self.encode())
+    }
+}
+
+impl<P: MlDsaParams, PH: Prehash> signature::SignatureEncoding for HashSignature<P, PH> {
+    type Repr = EncodedSignature<P>;
+}
+
+#[cfg(all(feature = "alloc", feature = "pkcs8"))]
+impl<P: MlDsaParams, PH: Prehash> SignatureBitStringEncoding for HashSignature<P, PH> {
+    fn to_bitstring(&self) -> der::Result<BitString> {
+        self.signature.to_bitstring()
+    }
+}
+
+#[cfg(feature = "pkcs8")]
+macro_rules! hash_algorithm_identifier {
+    ($params:ty, $oid:expr) => {
+        impl AssociatedAlgorithmIdentifier for HashSignature<$params, Sha512> {
+            type Params = AnyRef<'static>;
+
+            const ALGORITHM_IDENTIFIER: AlgorithmIdentifierRef<'static> = AlgorithmIdentifierRef {
+                oid: $oid,
+                parameters: None,
+            };
+        }
+    };
+}
+
+// Object identifiers are only assigned for HashML-DSA with SHA-512
+#[cfg(feature = "pkcs8")]
+hash_algorithm_identifier!(MlDsa44, fips204::ID_HASH_ML_DSA_44_WITH_SHA_512);
+#[cfg(feature = "pkcs8")]
+hash_algorithm_identifier!(MlDsa65, fips204::ID_HASH_ML_DSA_65_WITH_SHA_512);
+#[cfg(feature = "pkcs8")]
+hash_algorithm_identifier!(MlDsa87, fips204::ID_HASH_ML_DSA_87_WITH_SHA_512);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn oids() {
+        // The encodings listed in FIPS 204 Section 5.4.1
+        assert_eq!(Sha256::OID, hex!("0609608648016503040201"));
+        assert_eq!(Sha512::OID, hex!("0609608648016503040203"));
+        assert_eq!(Shake128::OID, hex!("060960864801650304020B"));
+    }
+}
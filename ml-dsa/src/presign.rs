@@ -0,0 +1,284 @@
+use core::fmt;
+use core::marker::PhantomData;
+
+use hybrid_array::typenum::U64;
+use rand_core::TryCryptoRng;
+
+use crate::algebra::Truncate;
+use crate::crypto::ShakeXof;
+use crate::observe::PhaseTimer;
+use crate::param::MlDsaParams;
+use crate::sampling::expand_mask;
+use crate::secret::Secret;
+use crate::util::{B32, B64};
+use crate::xof::{Xof, XofState};
+use crate::{
+    Commitment, Phase, Signature, SigningError, SigningKey, check_context, message_representative,
+};
+
+/// A candidate for one signature, computed by [`SigningKey::presign`] before the message is known.
+///
+/// Most of the cost of signing is in the commitment `w = A·y` of each iteration of the rejection
+/// sampling loop, and `y` only depends on the message through the seed ρ'' derived from it.  A
+/// presignature instead draws `y` from fresh randomness, mixed with the private seed `K` of the key
+/// in the same way as the hedged variant of ML-DSA.Sign, and computes `w` and the encoding of its
+/// high bits ahead of time.  Once the message arrives, [`SigningKey::sign_presigned`] only has to
+/// check candidates until one of them is accepted, which takes a few small NTTs each.
+///
+/// The signatures are valid ML-DSA signatures, with the same distribution as those of the hedged
+/// variant as long as the random number generator is sound.  They are not produced by
+/// ML-DSA.Sign_internal as FIPS 204 specifies it, however, since `y` does not depend on μ.
+///
+/// Each candidate is consumed by the signature that tries it, whether it is accepted or not.  This
+/// is essential: a candidate that was rejected for one message carries information about the
+/// secret key that a signature of another message would reveal.  A `Presignature` is therefore
+/// neither `Clone` nor `Copy`, and signing takes it by value:
+///
+/// ```compile_fail
+/// # use ml_dsa::{KeyGen, MlDsa44};
+/// # let mut rng = rand::rng();
+/// # let kp = MlDsa44::key_gen(&mut rng);
+/// # let sk = kp.signing_key();
+/// let presignature = sk.presign(&mut rng).unwrap();
+/// let _ = sk.sign_presigned(b"first", &[], &mut core::iter::once(presignature));
+/// let _ = sk.sign_presigned(b"second", &[], &mut core::iter::once(presignature));
+/// ```
+///
+/// A signature needs about 4.25, 5.1 and 3.85 candidates on average for [`MlDsa44`],
+/// [`MlDsa65`] and [`MlDsa87`] respectively, and a candidate takes the memory of `K + L`
+/// polynomials, or 1 KiB for each.
+///
+/// ```
+/// use ml_dsa::{KeyGen, MlDsa65};
+///
+/// let mut rng = rand::rng();
+/// let kp = MlDsa65::key_gen(&mut rng);
+/// let sk = kp.signing_key();
+///
+/// // While idle
+/// let mut pool: Vec<_> = (0..32).map(|_| sk.presign(&mut rng).unwrap()).collect();
+///
+/// // Once the message arrives
+/// let mut candidates = core::iter::from_fn(|| pool.pop());
+/// let sig = sk.sign_presigned(b"Hello world", &[], &mut candidates).unwrap();
+/// assert!(kp.verifying_key().verify_with_context(b"Hello world", &[], &sig));
+/// ```
+///
+/// With the `zeroize` feature, candidates are zeroized when they are dropped.
+///
+/// [`MlDsa44`]: crate::MlDsa44
+/// [`MlDsa65`]: crate::MlDsa65
+/// [`MlDsa87`]: crate::MlDsa87
+pub struct Presignature<P: MlDsaParams, X: Xof = ShakeXof> {
+    // The seed of the matrix of the key that made the candidate
+    rho: B32,
+    commitment: Commitment<P>,
+    _xof: PhantomData<X>,
+}
+
+impl<P: MlDsaParams, X: Xof> fmt::Debug for Presignature<P, X> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Presignature").finish_non_exhaustive()
+    }
+}
+
+impl<P: MlDsaParams, X: Xof> SigningKey<P, X> {
+    /// Compute a candidate for a later signature, from fresh randomness.  See [`Presignature`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return [`SigningError::RngFailure`] if it fails to get enough randomness.
+    pub fn presign<R: TryCryptoRng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Result<Presignature<P, X>, SigningError> {
+        let _timer = PhaseTimer::start(&self.xof, Phase::Presign);
+
+        let mut rnd = Secret::new(B32::default());
+        rng.try_fill_bytes(&mut rnd)
+            .map_err(|_| SigningError::RngFailure)?;
+
+        // As in ML-DSA.Sign_internal, but without μ, which is not known yet
+        let rhopp = Secret::new(
+            self.xof
+                .h()
+                .absorb(&self.K)
+                .absorb(&rnd)
+                .squeeze_new::<U64>(),
+        );
+        let y = expand_mask::<X, P::L, P::Gamma1>(&self.xof, &rhopp, 0);
+
+        Ok(Presignature {
+            rho: self.rho.clone(),
+            commitment: Commitment::new(&self.A_hat, y),
+            _xof: PhantomData,
+        })
+    }
+
+    /// Sign with the ML-DSA.Sign interface, taking candidates from `presignatures` until one of them
+    /// is accepted.  The candidates that are taken are consumed, and the rest are left in the
+    /// iterator.
+    ///
+    /// # Errors
+    ///
+    /// This method will return [`SigningError::ContextTooLong`] if the context string is more
+    /// than 255 bytes long, and otherwise fails like [`SigningKey::sign_mu_presigned`].
+    pub fn sign_presigned<I>(
+        &self,
+        M: &[u8],
+        ctx: &[u8],
+        presignatures: &mut I,
+    ) -> Result<Signature<P>, SigningError>
+    where
+        I: Iterator<Item = Presignature<P, X>>,
+    {
+        check_context(ctx)?;
+
+        let Mp: &[&[&[u8]]] = &[&[&[0], &[Truncate::truncate(ctx.len())], ctx, M]];
+        let mu = message_representative(&self.xof, &self.tr, Mp);
+        self.sign_mu_presigned(&mu, presignatures)
+    }
+
+    /// Sign a pre-computed μ like [`SigningKey::sign_presigned`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return [`SigningError::PresignaturesExhausted`] if `presignatures` runs out
+    /// before a candidate is accepted, [`SigningError::ForeignPresignature`] if a candidate was
+    /// made by another key, and [`SigningError::RejectionBudgetExceeded`] if the
+    /// [rejection budget](SigningKey::with_rejection_budget) runs out, with each candidate counting
+    /// as one iteration.
+    ///
+    /// It also fails if [fault checks](SigningKey::with_fault_checks) reject the signature.
+    pub fn sign_mu_presigned<I>(
+        &self,
+        mu: &B64,
+        presignatures: &mut I,
+    ) -> Result<Signature<P>, SigningError>
+    where
+        I: Iterator<Item = Presignature<P, X>>,
+    {
+        let _timer = PhaseTimer::start(&self.xof, Phase::Sign);
+
+        let budget = self.rejection_budget.unwrap_or(usize::MAX);
+        let mut tried = 0;
+        while tried < budget {
+            let Some(presignature) = presignatures.next() else {
+                return Err(SigningError::PresignaturesExhausted { tried });
+            };
+            tried += 1;
+
+            if presignature.rho != self.rho {
+                return Err(SigningError::ForeignPresignature);
+            }
+
+            let Some((c_tilde, z, h)) = presignature.commitment.respond(
+                &self.xof,
+                &self.s1_hat,
+                &self.s2_hat,
+                &self.t0_hat,
+                mu,
+            ) else {
+                continue;
+            };
+
+            if let Some(observer) = self.xof.observer() {
+                observer.rejection_sampling(tried);
+            }
+
+            return self.check_signature(mu, Signature { c_tilde, z, h });
+        }
+
+        Err(SigningError::RejectionBudgetExceeded { iterations: tried })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Blake3Xof, KeyPair, MlDsa44, MlDsa65, MlDsa87};
+    use hybrid_array::Array;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    fn check_presign<P: MlDsaParams, X: Xof>() {
+        let mut rng = StdRng::seed_from_u64(20);
+        let kp = KeyPair::<P, X>::from_seed(&Array([2u8; 32]));
+        let (sk, vk) = (kp.signing_key(), kp.verifying_key());
+
+        // Candidates are made as they are taken, to keep them off the stack
+        let mut pool = (0..64).map(|_| sk.presign(&mut rng).unwrap());
+        for i in 0u8..4 {
+            let before = pool.len();
+            let msg = [i; 10];
+            let sig = sk.sign_presigned(&msg, b"ctx", &mut pool).unwrap();
+            assert!(vk.verify_with_context(&msg, b"ctx", &sig));
+            assert!(pool.len() < before);
+        }
+
+        let mu = message_representative(vk.xof(), vk.tr(), &[&[b"mu"]]);
+        let sig = sk.sign_mu_presigned(&mu, &mut pool).unwrap();
+        assert!(vk.verify_mu(&mu, &sig));
+    }
+
+    #[test]
+    fn presigned_signatures_verify() {
+        check_presign::<MlDsa44, ShakeXof>();
+        check_presign::<MlDsa65, ShakeXof>();
+        check_presign::<MlDsa87, ShakeXof>();
+        check_presign::<MlDsa65, Blake3Xof>();
+    }
+
+    #[test]
+    fn presigned_errors() {
+        let mut rng = StdRng::seed_from_u64(21);
+        let sk = KeyPair::<MlDsa44>::from_seed(&Array([3u8; 32]))
+            .signing_key()
+            .clone();
+        let other = KeyPair::<MlDsa44>::from_seed(&Array([4u8; 32]))
+            .signing_key()
+            .clone();
+
+        // Running out of candidates
+        assert_eq!(
+            sk.sign_presigned(b"msg", &[], &mut core::iter::empty()),
+            Err(SigningError::PresignaturesExhausted { tried: 0 })
+        );
+
+        // A candidate of another key
+        let mut candidates = [other.presign(&mut rng).unwrap()].into_iter();
+        assert_eq!(
+            sk.sign_presigned(b"msg", &[], &mut candidates),
+            Err(SigningError::ForeignPresignature)
+        );
+        assert_eq!(candidates.len(), 0);
+
+        // A long context leaves the candidates alone
+        let mut candidates = [sk.presign(&mut rng).unwrap()].into_iter();
+        assert_eq!(
+            sk.sign_presigned(b"msg", &[0; 256], &mut candidates),
+            Err(SigningError::ContextTooLong { len: 256 })
+        );
+        assert_eq!(candidates.len(), 1);
+
+        // The rejection budget counts candidates, and leaves the rest in the iterator
+        let sk = sk.with_rejection_budget(0);
+        assert_eq!(
+            sk.sign_presigned(b"msg", &[], &mut candidates),
+            Err(SigningError::RejectionBudgetExceeded { iterations: 0 })
+        );
+        assert_eq!(candidates.len(), 1);
+    }
+
+    #[test]
+    fn presigned_with_fault_checks() {
+        let mut rng = StdRng::seed_from_u64(22);
+        let kp = KeyPair::<MlDsa65>::from_seed(&Array([5u8; 32]));
+        let sk = kp.signing_key().clone().with_fault_checks();
+        let faulty = sk.clone().with_fault(crate::fault::Fault::FlipCTilde);
+
+        let mut pool = (0..32).map(|_| sk.presign(&mut rng).unwrap());
+        let sig = sk.sign_presigned(b"msg", &[], &mut pool).unwrap();
+        assert!(kp.verifying_key().verify_with_context(b"msg", &[], &sig));
+        assert!(faulty.sign_presigned(b"msg", &[], &mut pool).is_err());
+    }
+}
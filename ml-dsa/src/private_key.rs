@@ -0,0 +1,100 @@
+// The `privateKey` field of a PKCS#8 ML-DSA private key:
+//
+// ML-DSA-PrivateKey ::= CHOICE {
+//   seed [0] IMPLICIT OCTET STRING (SIZE (32)),
+//   expandedKey OCTET STRING (SIZE (2560 | 4032 | 4896)),
+//   both SEQUENCE {
+//     seed OCTET STRING (SIZE (32)),
+//     expandedKey OCTET STRING (SIZE (2560 | 4032 | 4896))
+//   }
+// }
+
+use pkcs8::der::{
+    self, Decode, Encode, Header, Length, Tag, TagNumber, Tagged, Writer,
+    asn1::{AnyRef, OctetStringRef},
+};
+
+const SEED_TAG: Tag = Tag::ContextSpecific {
+    constructed: false,
+    number: TagNumber(0),
+};
+
+/// The form of the private key to use when encoding a [`KeyPair`](crate::KeyPair) as PKCS#8
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PrivateKeyFormat {
+    /// Only the 32-byte seed `xi` (`seed`)
+    #[default]
+    Seed,
+    /// Only the encoded signing key (`expandedKey`)
+    ExpandedKey,
+    /// Both the seed and the encoded signing key (`both`)
+    Both,
+}
+
+/// A decoded `ML-DSA-PrivateKey`.  The lengths of the fields are not checked.
+pub(crate) enum PrivateKeyChoice<'a> {
+    Seed(&'a [u8]),
+    ExpandedKey(&'a [u8]),
+    Both {
+        seed: &'a [u8],
+        expanded_key: &'a [u8],
+    },
+}
+
+impl<'a> PrivateKeyChoice<'a> {
+    pub(crate) fn from_der(bytes: &'a [u8]) -> der::Result<Self> {
+        // Early drafts of the format stored the bare seed.  No DER encoding of the CHOICE is 32
+        // bytes long, so these keys are still accepted.
+        if bytes.len() == 32 {
+            return Ok(Self::Seed(bytes));
+        }
+
+        let any = AnyRef::from_der(bytes)?;
+        match any.tag() {
+            SEED_TAG => Ok(Self::Seed(any.value())),
+            Tag::OctetString => Ok(Self::ExpandedKey(any.value())),
+            Tag::Sequence => any.sequence(|reader| {
+                let seed = OctetStringRef::decode(reader)?.as_bytes();
+                let expanded_key = OctetStringRef::decode(reader)?.as_bytes();
+                Ok(Self::Both { seed, expanded_key })
+            }),
+            tag => Err(tag.unexpected_error(None).to_error()),
+        }
+    }
+
+    fn both_len(seed: &[u8], expanded_key: &[u8]) -> der::Result<Length> {
+        OctetStringRef::new(seed)?.encoded_len()?
+            + OctetStringRef::new(expanded_key)?.encoded_len()?
+    }
+}
+
+impl Encode for PrivateKeyChoice<'_> {
+    fn encoded_len(&self) -> der::Result<Length> {
+        match *self {
+            Self::Seed(seed) => {
+                let len = Length::try_from(seed.len())?;
+                Header::new(SEED_TAG, len).encoded_len()? + len
+            }
+            Self::ExpandedKey(expanded_key) => OctetStringRef::new(expanded_key)?.encoded_len(),
+            Self::Both { seed, expanded_key } => {
+                let len = Self::both_len(seed, expanded_key)?;
+                Header::new(Tag::Sequence, len).encoded_len()? + len
+            }
+        }
+    }
+
+    fn encode(&self, writer: &mut impl Writer) -> der::Result<()> {
+        match *self {
+            Self::Seed(seed) => {
+                Header::new(SEED_TAG, Length::try_from(seed.len())?).encode(writer)?;
+                writer.write(seed)
+            }
+            Self::ExpandedKey(expanded_key) => OctetStringRef::new(expanded_key)?.encode(writer),
+            Self::Both { seed, expanded_key } => {
+                Header::new(Tag::Sequence, Self::both_len(seed, expanded_key)?).encode(writer)?;
+                OctetStringRef::new(seed)?.encode(writer)?;
+                OctetStringRef::new(expanded_key)?.encode(writer)
+            }
+        }
+    }
+}
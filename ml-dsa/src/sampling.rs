@@ -5,21 +5,11 @@ use hybrid_array::Array;
 use crate::algebra::{
     BaseField, Elem, Field, Int, NttMatrix, NttPolynomial, NttVector, Polynomial, Vector,
 };
-// CD: Added feature flag to allow for different hash functions
-#[cfg(all(
-    feature = "hash-shake",
-    not(feature = "hash-blake3-niave"),
-    not(feature = "hash-blake3-optimized"),
-    not(feature = "hash-aes")
-))]
-use crate::crypto::{G, H};
-#[cfg(feature = "hash-aes")]
-use crate::crypto_aes::{G, H};
-#[cfg(feature = "hash-blake3-niave")]
-use crate::crypto_blake3_niave::{G, H};
-#[cfg(feature = "hash-blake3-optimized")]
-use crate::crypto_blake3_optimized::{G, H};
+use crate::keccak_x4::{SHAKE128_RATE, SHAKE256_RATE, ShakeX4};
+use crate::observe::{Phase, PhaseTimer};
 use crate::param::{Eta, MaskSamplingSize};
+use crate::secret::{Secret, Wipe, wipe};
+use crate::xof::{Xof, XofState};
 
 // Algorithm 13 BytesToBits
 fn bit_set(z: &[u8], i: usize) -> bool {
@@ -76,12 +66,12 @@ fn coeffs_from_byte(z: u8, eta: Eta) -> (Option<Elem>, Option<Elem>) {
 }
 
 // Algorithm 29 SampleInBall
-pub fn sample_in_ball(rho: &[u8], tau: usize) -> Polynomial {
+pub fn sample_in_ball<X: Xof>(xof: &X, rho: &[u8], tau: usize) -> Polynomial {
     const ONE: Elem = Elem::new(1);
     const MINUS_ONE: Elem = Elem::new(BaseField::Q - 1);
 
     let mut c = Polynomial::default();
-    let mut ctx = H::default().absorb(rho);
+    let mut ctx = xof.h().absorb(rho);
 
     let mut s = [0u8; 8];
     ctx.squeeze(&mut s);
@@ -107,14 +97,20 @@ pub fn sample_in_ball(rho: &[u8], tau: usize) -> Polynomial {
 }
 
 // Algorithm 30 RejNTTPoly
-fn rej_ntt_poly(rho: &[u8], r: u8, s: u8) -> NttPolynomial {
-    let mut j = 0;
-    let mut ctx = G::default().absorb(rho).absorb(&[s]).absorb(&[r]);
+pub(crate) fn rej_ntt_poly<X: Xof>(xof: &X, rho: &[u8], r: u8, s: u8) -> NttPolynomial {
+    let mut ctx = xof.g().absorb(rho).absorb(&[s]).absorb(&[r]);
+    rej_ntt_poly_from(|b| {
+        ctx.squeeze(b);
+    })
+}
 
+// The rejection loop of RejNTTPoly, reading from a stream that has already been seeded
+pub(crate) fn rej_ntt_poly_from(mut squeeze: impl FnMut(&mut [u8])) -> NttPolynomial {
+    let mut j = 0;
     let mut a = NttPolynomial::default();
     let mut s = [0u8; 3];
     while j < 256 {
-        ctx.squeeze(&mut s);
+        squeeze(&mut s);
         if let Some(x) = coeff_from_three_bytes(s) {
             a.0[j] = x;
             j += 1;
@@ -125,14 +121,20 @@ fn rej_ntt_poly(rho: &[u8], r: u8, s: u8) -> NttPolynomial {
 }
 
 // Algorithm 31 RejBoundedPoly
-fn rej_bounded_poly(rho: &[u8], eta: Eta, r: u16) -> Polynomial {
-    let mut j = 0;
-    let mut ctx = H::default().absorb(rho).absorb(&r.to_le_bytes());
+fn rej_bounded_poly<X: Xof>(xof: &X, rho: &[u8], eta: Eta, r: u16) -> Polynomial {
+    let mut ctx = xof.h().absorb(rho).absorb(&r.to_le_bytes());
+    rej_bounded_poly_from(eta, |b| {
+        ctx.squeeze(b);
+    })
+}
 
+// The rejection loop of RejBoundedPoly, reading from a stream that has already been seeded
+pub(crate) fn rej_bounded_poly_from(eta: Eta, mut squeeze: impl FnMut(&mut [u8])) -> Polynomial {
+    let mut j = 0;
     let mut a = Polynomial::default();
-    let mut z = [0u8];
+    let mut z = Secret::new([0u8]);
     while j < 256 {
-        ctx.squeeze(&mut z);
+        squeeze(&mut *z);
         let (z0, z1) = coeffs_from_byte(z[0], eta);
 
         if let Some(z) = z0 {
@@ -153,11 +155,148 @@ fn rej_bounded_poly(rho: &[u8], eta: Eta, r: u16) -> Polynomial {
     a
 }
 
+// Algorithm 30 RejNTTPoly, with SHAKE128, for four pairs (r, s) at once
+fn rej_ntt_poly_x4(rho: &[u8], rs: [(u8, u8); 4]) -> [NttPolynomial; 4] {
+    let mut ctx = ShakeX4::<SHAKE128_RATE>::new()
+        .absorb([rho; 4])
+        .absorb(rs.each_ref().map(|(_, s)| core::slice::from_ref(s)))
+        .absorb(rs.each_ref().map(|(r, _)| core::slice::from_ref(r)));
+
+    let mut a: [NttPolynomial; 4] = Default::default();
+    let mut j = [0; 4];
+    while j.iter().any(|&j| j < 256) {
+        let blocks = ctx.squeeze_block();
+        for ((a, j), block) in a.iter_mut().zip(&mut j).zip(&blocks) {
+            // The rate is a multiple of three, so no candidate straddles two blocks
+            for s in block.chunks_exact(3) {
+                if *j == 256 {
+                    break;
+                }
+
+                if let Some(x) = coeff_from_three_bytes([s[0], s[1], s[2]]) {
+                    a.0[*j] = x;
+                    *j += 1;
+                }
+            }
+        }
+    }
+
+    a
+}
+
+// Algorithm 31 RejBoundedPoly, with SHAKE256, for four values of r at once
+fn rej_bounded_poly_x4(rho: &[u8], eta: Eta, r: [u16; 4]) -> [Polynomial; 4] {
+    let r = r.map(u16::to_le_bytes);
+    let mut ctx = ShakeX4::<SHAKE256_RATE>::new()
+        .absorb([rho; 4])
+        .absorb(r.each_ref().map(<[u8; 2]>::as_slice));
+
+    let mut a: [Polynomial; 4] = Default::default();
+    let mut j = [0; 4];
+    while j.iter().any(|&j| j < 256) {
+        let blocks = Secret::new(ctx.squeeze_block());
+        for ((a, j), block) in a.iter_mut().zip(&mut j).zip(blocks.iter()) {
+            for &z in block {
+                if *j == 256 {
+                    break;
+                }
+
+                let (z0, z1) = coeffs_from_byte(z, eta);
+
+                if let Some(z) = z0 {
+                    a.0[*j] = z;
+                    *j += 1;
+                }
+
+                if *j == 256 {
+                    break;
+                }
+
+                if let Some(z) = z1 {
+                    a.0[*j] = z;
+                    *j += 1;
+                }
+            }
+        }
+    }
+
+    a
+}
+
+// The first `N` bytes of output from each of four SHAKE256 states, which are secret
+fn squeeze_x4<N: ArraySize>(ctx: &mut ShakeX4<SHAKE256_RATE>) -> Secret<[Array<u8, N>; 4]> {
+    let mut v: Secret<[Array<u8, N>; 4]> = Secret::new(Default::default());
+    for start in (0..N::USIZE).step_by(SHAKE256_RATE) {
+        let len = SHAKE256_RATE.min(N::USIZE - start);
+        let blocks = Secret::new(ctx.squeeze_block());
+        for (v, block) in v.iter_mut().zip(blocks.iter()) {
+            v[start..start + len].copy_from_slice(&block[..len]);
+        }
+    }
+
+    v
+}
+
+// Hands out values that are computed four at a time, for indices 0, 1, 2, ... in order.  The last
+// batch repeats the final index in its unused lanes, and the values that are left over are wiped.
+struct Batched<T: Wipe, F> {
+    len: usize,
+    f: F,
+    batch: [T; 4],
+}
+
+impl<T: Wipe, F> Drop for Batched<T, F> {
+    fn drop(&mut self) {
+        wipe(&mut self.batch);
+    }
+}
+
+impl<T: Default + Wipe, F: FnMut([usize; 4]) -> [T; 4]> Batched<T, F> {
+    fn new(len: usize, f: F) -> Self {
+        Self {
+            len,
+            f,
+            batch: Default::default(),
+        }
+    }
+
+    fn get(&mut self, i: usize) -> T {
+        let lane = i & 3;
+        if lane == 0 {
+            let last = self.len - 1;
+            self.batch = (self.f)(core::array::from_fn(|k| (i + k).min(last)));
+        }
+
+        core::mem::take(&mut self.batch[lane])
+    }
+}
+
 // Algorithm 32 ExpandA
-pub fn expand_a<K: ArraySize, L: ArraySize>(rho: &[u8]) -> NttMatrix<K, L> {
+//
+// With SHAKE, the entries are sampled four at a time, in row-major order.
+#[allow(clippy::integer_division_remainder_used)] // The indices are public
+pub fn expand_a<X: Xof, K: ArraySize, L: ArraySize>(xof: &X, rho: &[u8]) -> NttMatrix<K, L> {
+    let _timer = PhaseTimer::start(xof, Phase::ExpandA);
+
+    if X::SHAKE {
+        let mut entries = Batched::new(K::USIZE * L::USIZE, |i: [usize; 4]| {
+            rej_ntt_poly_x4(
+                rho,
+                i.map(|i| {
+                    let (r, s) = (i / L::USIZE, i % L::USIZE);
+                    (Truncate::truncate(r), Truncate::truncate(s))
+                }),
+            )
+        });
+
+        return NttMatrix::new(Array::from_fn(|r| {
+            NttVector::new(Array::from_fn(|s| entries.get(r * L::USIZE + s)))
+        }));
+    }
+
     NttMatrix::new(Array::from_fn(|r| {
         NttVector::new(Array::from_fn(|s| {
-            rej_ntt_poly(rho, Truncate::truncate(r), Truncate::truncate(s))
+            rej_ntt_poly(xof, rho, Truncate::truncate(r), Truncate::truncate(s))
         }))
     }))
 }
@@ -167,27 +306,53 @@ pub fn expand_a<K: ArraySize, L: ArraySize>(rho: &[u8]) -> NttMatrix<K, L> {
 // We only do half of the algorithm here, because it's inconvenient to return two vectors of
 // different sizes.  So the caller has to call twice:
 //
-//    let s1 = Vector::<K>::expand_s(rho, 0);
-//    let s2 = Vector::<L>::expand_s(rho, L::USIZE);
-pub fn expand_s<K: ArraySize>(rho: &[u8], eta: Eta, base: usize) -> Vector<K> {
+//    let s1 = expand_s::<X, L>(xof, rho, eta, 0);
+//    let s2 = expand_s::<X, K>(xof, rho, eta, L::USIZE);
+pub fn expand_s<X: Xof, K: ArraySize>(xof: &X, rho: &[u8], eta: Eta, base: usize) -> Vector<K> {
+    if X::SHAKE {
+        let mut entries = Batched::new(K::USIZE, |i: [usize; 4]| {
+            rej_bounded_poly_x4(rho, eta, i.map(|i| Truncate::truncate(i + base)))
+        });
+        return Vector::new(Array::from_fn(|r| entries.get(r)));
+    }
+
     Vector::new(Array::from_fn(|r| {
         let r = Truncate::truncate(r + base);
-        rej_bounded_poly(rho, eta, r)
+        rej_bounded_poly(xof, rho, eta, r)
     }))
 }
 
 // Algorithm 34 ExpandMask
-pub fn expand_mask<K, Gamma1>(rho: &[u8], mu: u16) -> Vector<K>
+pub fn expand_mask<X, K, Gamma1>(xof: &X, rho: &[u8], mu: u16) -> Vector<K>
 where
+    X: Xof,
     K: ArraySize,
     Gamma1: MaskSamplingSize,
 {
+    if X::SHAKE {
+        let mut entries = Batched::new(K::USIZE, |i: [usize; 4]| {
+            let r = i.map(|i| {
+                let r: u16 = Truncate::truncate(i);
+                (mu + r).to_le_bytes()
+            });
+            let mut ctx = ShakeX4::<SHAKE256_RATE>::new()
+                .absorb([rho; 4])
+                .absorb(r.each_ref().map(<[u8; 2]>::as_slice));
+            squeeze_x4::<Gamma1::SampleSize>(&mut ctx)
+                .each_ref()
+                .map(|v| Gamma1::unpack(v))
+        });
+        return Vector::new(Array::from_fn(|r| entries.get(r)));
+    }
+
     Vector::new(Array::from_fn(|r| {
         let r: u16 = Truncate::truncate(r);
-        let v = H::default()
-            .absorb(rho)
-            .absorb(&(mu + r).to_le_bytes())
-            .squeeze_new::<Gamma1::SampleSize>();
+        let v = Secret::new(
+            xof.h()
+                .absorb(rho)
+                .absorb(&(mu + r).to_le_bytes())
+                .squeeze_new::<Gamma1::SampleSize>(),
+        );
 
         Gamma1::unpack(&v)
     }))
@@ -198,6 +363,7 @@ where
 #[allow(clippy::cast_possible_truncation)]
 mod test {
     use super::*;
+    use crate::ShakeXof;
     use hybrid_array::typenum::{U16, U256};
 
     fn max_abs_1(p: &Polynomial) -> bool {
@@ -219,7 +385,7 @@ mod test {
         for tau in 1..65 {
             for seed in 0_usize..255 {
                 let rho = ((tau as u16) << 8) + (seed as u16);
-                let p = sample_in_ball(&rho.to_be_bytes(), tau);
+                let p = sample_in_ball(&ShakeXof, &rho.to_be_bytes(), tau);
                 assert_eq!(hamming_weight(&p), tau);
                 assert!(max_abs_1(&p));
             }
@@ -233,7 +399,7 @@ mod test {
         let sample: Array<Array<Elem, U256>, U16> = Array::from_fn(|i| {
             let i = i as u8;
             let rho = [i; 32];
-            rej_ntt_poly(&rho, i, i + 1).0
+            rej_ntt_poly(&ShakeXof, &rho, i, i + 1).0
         });
 
         let sample = sample.as_flattened();
@@ -249,15 +415,54 @@ mod test {
         let rho = [0; 32];
 
         // Eta = 2
-        let sample = rej_bounded_poly(&rho, Eta::Two, 0).0;
+        let sample = rej_bounded_poly(&ShakeXof, &rho, Eta::Two, 0).0;
         let all_in_range = sample.iter().map(|x| *x + Elem::new(2)).all(|x| x.0 < 5);
         assert!(all_in_range);
         // TODO measure uniformity
 
         // Eta = 4
-        let sample = rej_bounded_poly(&rho, Eta::Four, 0).0;
+        let sample = rej_bounded_poly(&ShakeXof, &rho, Eta::Four, 0).0;
         let all_in_range = sample.iter().map(|x| *x + Elem::new(4)).all(|x| x.0 < 9);
         assert!(all_in_range);
         // TODO measure uniformity
     }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        use crate::{MlDsa87, ParameterSet};
+        type K = <MlDsa87 as ParameterSet>::K;
+        type L = <MlDsa87 as ParameterSet>::L;
+        type Gamma1 = <MlDsa87 as ParameterSet>::Gamma1;
+
+        let rho = [7u8; 32];
+        let rhop = [9u8; 64];
+
+        // Seven columns, so that batches of four cross rows and the last batch is partial
+        let a = expand_a::<ShakeXof, K, L>(&ShakeXof, &rho);
+        for (r, row) in a.0.iter().enumerate() {
+            for (s, entry) in row.0.iter().enumerate() {
+                assert_eq!(*entry, rej_ntt_poly(&ShakeXof, &rho, r as u8, s as u8));
+            }
+        }
+
+        for eta in [Eta::Two, Eta::Four] {
+            let s2 = expand_s::<ShakeXof, K>(&ShakeXof, &rhop, eta, 7);
+            for (r, entry) in s2.0.iter().enumerate() {
+                assert_eq!(
+                    *entry,
+                    rej_bounded_poly(&ShakeXof, &rhop, eta, r as u16 + 7)
+                );
+            }
+        }
+
+        let y = expand_mask::<ShakeXof, L, Gamma1>(&ShakeXof, &rhop, 700);
+        for (r, entry) in y.0.iter().enumerate() {
+            let v = ShakeXof
+                .h()
+                .absorb(&rhop)
+                .absorb(&(700 + r as u16).to_le_bytes())
+                .squeeze_new::<<Gamma1 as MaskSamplingSize>::SampleSize>();
+            assert_eq!(*entry, Gamma1::unpack(&v));
+        }
+    }
 }
@@ -0,0 +1,69 @@
+// Values derived from secret data, which are zeroized when they are dropped if the `zeroize`
+// feature is enabled.
+//
+// Without the feature, `Wipe` holds for every type and wiping does nothing, so that the signing
+// and key generation code is the same either way.  Rust may still leave copies of a value that is
+// moved, so this is a best effort for the stack.  Heap buffers are reliably cleared.
+
+use core::ops::{Deref, DerefMut};
+
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+/// The values that can be wiped
+#[cfg(feature = "zeroize")]
+pub(crate) trait Wipe: Zeroize {}
+
+#[cfg(feature = "zeroize")]
+impl<T: Zeroize + ?Sized> Wipe for T {}
+
+/// The values that can be wiped
+#[cfg(not(feature = "zeroize"))]
+pub(crate) trait Wipe {}
+
+#[cfg(not(feature = "zeroize"))]
+impl<T: ?Sized> Wipe for T {}
+
+/// Zeroize `value` in place
+#[cfg_attr(not(feature = "zeroize"), allow(unused_variables))]
+pub(crate) fn wipe<T: Wipe + ?Sized>(value: &mut T) {
+    #[cfg(feature = "zeroize")]
+    value.zeroize();
+}
+
+/// A value that is wiped when it is dropped
+pub(crate) struct Secret<T: Wipe>(T);
+
+impl<T: Wipe> Secret<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Move the value out, leaving its default behind to be wiped
+    pub(crate) fn take(mut self) -> T
+    where
+        T: Default,
+    {
+        core::mem::take(&mut self.0)
+    }
+}
+
+impl<T: Wipe> Deref for Secret<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Wipe> DerefMut for Secret<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Wipe> Drop for Secret<T> {
+    fn drop(&mut self) {
+        wipe(&mut self.0);
+    }
+}
@@ -0,0 +1,142 @@
+// Text and serde encodings of signatures and verifying keys.
+//
+// Both types are written as hexadecimal by `Display`, `LowerHex` and `UpperHex`, and read back by
+// `FromStr`, which accepts either case.  With the `serde` feature they are serialized as hex in
+// human-readable formats and as raw bytes in binary ones.  Encoded signing keys are plain arrays,
+// which `hybrid-array` serializes as a tuple of bytes.
+
+use core::fmt;
+use core::str::FromStr;
+
+use crate::param::{EncodedSignature, EncodedVerifyingKey, MlDsaParams};
+use crate::xof::Xof;
+use crate::{Error, Signature, VerifyingKey};
+
+#[cfg(feature = "serde")]
+use serdect::serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+/// Deserialize hex or bytes into `enc`, which must be exactly filled
+#[cfg(feature = "serde")]
+fn deserialize_exact<'de, D: Deserializer<'de>>(
+    enc: &mut [u8],
+    deserializer: D,
+) -> Result<(), D::Error> {
+    let len = enc.len();
+    let decoded = serdect::array::deserialize_hex_or_bin(enc, deserializer)?;
+    if decoded.len() == len {
+        Ok(())
+    } else {
+        Err(de::Error::invalid_length(
+            decoded.len(),
+            &"an encoded key or signature",
+        ))
+    }
+}
+
+/// Decode hexadecimal in either case into `enc`, which must be exactly filled
+fn decode_hex(s: &str, enc: &mut [u8]) -> Result<(), Error> {
+    let len = enc.len();
+    match base16ct::mixed::decode(s, enc) {
+        Ok(decoded) if decoded.len() == len => Ok(()),
+        _ => Err(Error::new()),
+    }
+}
+
+macro_rules! hex_impls {
+    ($type:ident<$($param:ident: $bound:path),*>) => {
+        impl<$($param: $bound),*> fmt::Display for $type<$($param),*> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{self:x}")
+            }
+        }
+
+        impl<$($param: $bound),*> fmt::LowerHex for $type<$($param),*> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{:x}", base16ct::HexDisplay(&self.encode()))
+            }
+        }
+
+        impl<$($param: $bound),*> fmt::UpperHex for $type<$($param),*> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{:X}", base16ct::HexDisplay(&self.encode()))
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<$($param: $bound),*> Serialize for $type<$($param),*> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serdect::array::serialize_hex_lower_or_bin(&self.encode(), serializer)
+            }
+        }
+    };
+}
+
+hex_impls!(Signature<P: MlDsaParams>);
+hex_impls!(VerifyingKey<P: MlDsaParams, X: Xof>);
+
+/// Signatures are parsed from hexadecimal, in either case.
+impl<P: MlDsaParams> FromStr for Signature<P> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let mut enc = EncodedSignature::<P>::default();
+        decode_hex(s, &mut enc)?;
+        Self::decode(&enc).ok_or(Error::new())
+    }
+}
+
+/// Verifying keys are parsed from hexadecimal, in either case.
+impl<P: MlDsaParams, X: Xof> FromStr for VerifyingKey<P, X> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let mut enc = EncodedVerifyingKey::<P>::default();
+        decode_hex(s, &mut enc)?;
+        Ok(Self::decode(&enc))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, P: MlDsaParams> Deserialize<'de> for Signature<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut enc = EncodedSignature::<P>::default();
+        deserialize_exact(&mut enc, deserializer)?;
+        Self::decode(&enc).ok_or_else(|| de::Error::custom("invalid ML-DSA signature"))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, P: MlDsaParams, X: Xof> Deserialize<'de> for VerifyingKey<P, X> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut enc = EncodedVerifyingKey::<P>::default();
+        deserialize_exact(&mut enc, deserializer)?;
+        Ok(Self::decode(&enc))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{KeyGen, MlDsa44};
+    use hybrid_array::Array;
+
+    #[test]
+    fn hex_round_trip() {
+        let kp = MlDsa44::key_gen_internal(&Array::default());
+        let vk = kp.verifying_key();
+        let sig = kp.signing_key().sign_deterministic(b"hex", &[]).unwrap();
+
+        let mut sig_buf = [0u8; 2 * 2420];
+        let lower = base16ct::lower::encode_str(&sig.encode(), &mut sig_buf).unwrap();
+        assert_eq!(Signature::<MlDsa44>::from_str(lower).unwrap(), sig);
+
+        let mut vk_buf = [0u8; 2 * 1312];
+        let upper = base16ct::upper::encode_str(&vk.encode(), &mut vk_buf).unwrap();
+        assert!(VerifyingKey::<MlDsa44>::from_str(upper).unwrap() == *vk);
+
+        // Truncated or odd-length input is rejected
+        assert!(Signature::<MlDsa44>::from_str(&lower[..lower.len() - 2]).is_err());
+        assert!(VerifyingKey::<MlDsa44>::from_str(&upper[1..]).is_err());
+        assert!(VerifyingKey::<MlDsa44>::from_str("zz").is_err());
+    }
+}
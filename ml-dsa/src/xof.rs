@@ -0,0 +1,127 @@
+use core::fmt::Debug;
+use hybrid_array::Array;
+
+use crate::module_lattice::encode::ArraySize;
+use crate::observe::Observer;
+
+/// The absorb-then-squeeze interface that ML-DSA requires of an extendable-output function.
+///
+/// A state starts out absorbing.  The first call to [`XofState::squeeze`] finalizes the input, and
+/// any further input is a logic error.
+pub trait XofState {
+    /// Absorb input into the hash state
+    #[must_use]
+    fn absorb(self, input: &[u8]) -> Self;
+
+    /// Squeeze output from the hash state
+    fn squeeze(&mut self, output: &mut [u8]) -> &mut Self;
+
+    /// Squeeze output from the hash state into a new array
+    fn squeeze_new<N: ArraySize>(&mut self) -> Array<u8, N> {
+        let mut v = Array::default();
+        self.squeeze(&mut v);
+        v
+    }
+}
+
+// The arc of `PRIVATE_ARC`, followed by `suffix`
+macro_rules! private_arc {
+    ($suffix:literal) => {
+        concat!(
+            "1.2.840.113556.1.8000.2554.63952.18939.8846.16756.38443.782279.304911.1",
+            $suffix
+        )
+    };
+}
+pub(crate) use private_arc;
+
+/// The object identifier arc under which this project identifies ML-DSA with its non-standard
+/// backends.  Each backend has an arc below it:
+///
+/// * `1` is BLAKE3, with [`Blake3Xof`](crate::Blake3Xof) and
+///   [`Blake3OptimizedXof`](crate::Blake3OptimizedXof)
+/// * `2` is AES, with [`AesXof`](crate::AesXof)
+///
+/// The project has no Private Enterprise Number, and `2.25` arcs made from a UUID do not fit the
+/// 32-bit arcs of `const-oid`.  The arc is instead derived from the UUID
+/// `f9d049fb-228e-4174-962b-0befc704a70f` under `1.2.840.113556.1.8000.2554`, which is delegated
+/// for identifiers made from a UUID, so that it cannot collide with anyone else's.  The final `1`
+/// is ML-DSA; `2` is SLH-DSA, used by the `slh-dsa` crate.
+///
+/// See [`Xof::ALGORITHM_ARC`] for how keys are identified.
+pub const PRIVATE_ARC: &str = private_arc!("");
+
+/// A family of extendable-output functions that fills the two hash roles in FIPS 204.
+///
+/// `G` takes the place of SHAKE128, which is only used to expand the public matrix `A_hat`.  `H`
+/// takes the place of SHAKE256, which is used for everything else: key derivation, secret and
+/// mask sampling, `tr`, `mu` and the challenge.  [`ShakeXof`](crate::ShakeXof) is the standard
+/// choice; the other implementations produce signatures that only this crate can verify.
+///
+/// A value of this type is carried by each key, and every hash state the key uses is started from
+/// it.  This lets a backend hold per-key configuration, such as [`AesParams`](crate::AesParams),
+/// without any process-wide state.  Such configuration must not change the output.
+pub trait Xof: Clone + Debug + Default + PartialEq + Eq + 'static {
+    /// The XOF used in the role of SHAKE128
+    type G: XofState;
+
+    /// The XOF used in the role of SHAKE256
+    type H: XofState;
+
+    /// A short name for the backend, for diagnostics
+    const NAME: &'static str;
+
+    /// The object identifier arc of the backend, in dotted decimal notation, or `None` for the
+    /// standard SHAKE functions.
+    ///
+    /// Keys that use a backend with an arc are identified in PKCS#8, SPKI and X.509 by the arc
+    /// followed by the last arc of the FIPS 204 identifier of their parameter set, such as `17`
+    /// for ML-DSA-44.  Backends whose output differs must have different arcs.  The backends of
+    /// this crate use arcs under [`PRIVATE_ARC`](crate::PRIVATE_ARC).
+    const ALGORITHM_ARC: Option<&'static str>;
+
+    /// Whether `G` and `H` are plain SHAKE128 and SHAKE256, with nothing observing them.  Sampling
+    /// then computes four independent states at a time rather than going through [`Xof::g`] and
+    /// [`Xof::h`].
+    #[doc(hidden)]
+    const SHAKE: bool = false;
+
+    /// Start a new `G` state
+    fn g(&self) -> Self::G;
+
+    /// Start a new `H` state
+    fn h(&self) -> Self::H;
+
+    /// The observer to report activity to, if any
+    fn observer(&self) -> Option<&dyn Observer> {
+        None
+    }
+}
+
+/// Declares an [`XofState`] newtype that pins a context-keyed state to one domain.  The inner type
+/// must provide `fn new(context: &'static str) -> Self` and implement [`XofState`].
+macro_rules! domain_state {
+    ($(#[$meta:meta])* $name:ident, $inner:ty, $context:expr) => {
+        $(#[$meta])*
+        pub struct $name($inner);
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self(<$inner>::new($context))
+            }
+        }
+
+        impl $crate::xof::XofState for $name {
+            fn absorb(self, input: &[u8]) -> Self {
+                Self(self.0.absorb(input))
+            }
+
+            fn squeeze(&mut self, output: &mut [u8]) -> &mut Self {
+                self.0.squeeze(output);
+                self
+            }
+        }
+    };
+}
+
+pub(crate) use domain_state;
@@ -0,0 +1,82 @@
+use hybrid_array::Array;
+use ml_dsa::*;
+use std::thread;
+
+const PARAMS: [AesParams; 6] = [
+    AesParams::DEFAULT,
+    AesParams {
+        cache_bytes: 16,
+        initial_fill: 16,
+        min_refill: 16,
+    },
+    AesParams {
+        cache_bytes: 0,
+        initial_fill: 0,
+        min_refill: 0,
+    },
+    AesParams {
+        cache_bytes: 4096,
+        initial_fill: 4096,
+        min_refill: 64,
+    },
+    AesParams {
+        cache_bytes: 65_536,
+        initial_fill: 16,
+        min_refill: 32_768,
+    },
+    AesParams {
+        cache_bytes: 100,
+        initial_fill: 7,
+        min_refill: 33,
+    },
+];
+
+#[test]
+fn per_key_params_do_not_interfere() {
+    let seed = Array([7u8; 32]);
+    let msg = b"per-key AES tuning";
+    let kp = KeyPair::<MlDsa65, AesXof>::from_seed(&seed);
+    let expected = kp.signing_key().sign_deterministic(msg, &[]).unwrap();
+
+    thread::scope(|s| {
+        for i in 0..4 * PARAMS.len() {
+            let params = PARAMS[i % PARAMS.len()];
+            let expected = &expected;
+
+            s.spawn(move || {
+                let xof = AesXof::new(params);
+                let kp = KeyPair::<MlDsa65, AesXof>::from_seed_with_xof(&seed, xof);
+                let sk = kp.signing_key().clone();
+                let vk = kp.verifying_key().clone();
+
+                for _ in 0..8 {
+                    let sig = sk.sign_deterministic(msg, &[]).unwrap();
+                    assert_eq!(&sig, expected);
+                    assert!(vk.verify_with_context(msg, &[], &sig));
+
+                    // No other thread can change the settings of this key
+                    assert_eq!(sk.xof(), &xof);
+                    assert_eq!(vk.xof(), &xof);
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn builders_carry_params() {
+    let params = PARAMS[1];
+    let kp = KeyPair::<MlDsa44, AesXof>::from_seed(&Array::default());
+
+    let sk = kp.signing_key().clone().with_aes_params(params);
+    let vk = kp.verifying_key().clone().with_aes_params(params);
+    assert_eq!(sk.xof().params(), params);
+    assert_eq!(vk.xof().params(), params);
+
+    // Keys derived from a tuned key keep its tuning
+    assert_eq!(sk.verifying_key().xof().params(), params);
+
+    let sig = sk.sign_deterministic(b"msg", &[]).unwrap();
+    assert!(kp.verifying_key().verify_with_context(b"msg", &[], &sig));
+    assert!(vk.verify_with_context(b"msg", &[], &sig));
+}
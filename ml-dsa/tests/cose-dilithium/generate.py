@@ -0,0 +1,107 @@
+#!/usr/bin/env python3
+# Generates the JOSE and COSE examples used by jose.rs and cose.rs.
+#
+# The examples of draft-ietf-cose-dilithium are not in this repository, so these come from OpenSSL
+# instead (3.5.6 when they were generated), in the formats of the draft.  The JWK, JWS, COSE_Key
+# and COSE_Sign1 encodings are this script's, while key generation and signing are OpenSSL's.  The
+# signatures are hedged, with fixed randomness, so that they differ from the deterministic ones of
+# this crate.  The script is deterministic: running it again must reproduce the checked-in files.
+#
+# Usage: python3 tests/cose-dilithium/generate.py
+
+import base64
+import json
+import os
+import subprocess
+import tempfile
+
+PAYLOAD = "It’s a dangerous business, Frodo, going out your door.".encode()
+SEED = bytes(range(32))
+KID = b"example"
+PARAMETER_SETS = [  # name, COSE algorithm, size of the verifying key
+    ("ML-DSA-44", -48, 1312),
+    ("ML-DSA-65", -49, 1952),
+    ("ML-DSA-87", -50, 2592),
+]
+
+DIR = os.path.dirname(os.path.abspath(__file__))
+
+
+def run(*args, stdin=None):
+    return subprocess.run(args, input=stdin, check=True, capture_output=True).stdout
+
+
+def b64url(data):
+    return base64.urlsafe_b64encode(data).rstrip(b"=").decode()
+
+
+# The parts of CBOR that the examples need
+def cbor_head(major, n):
+    if n < 24:
+        return bytes([major << 5 | n])
+    for info, size in ((24, 1), (25, 2), (26, 4)):
+        if n < 1 << (8 * size):
+            return bytes([major << 5 | info]) + n.to_bytes(size, "big")
+    raise ValueError(n)
+
+
+def cbor_int(n):
+    return cbor_head(0, n) if n >= 0 else cbor_head(1, -1 - n)
+
+
+def cbor_bytes(b):
+    return cbor_head(2, len(b)) + b
+
+
+def cbor_map(items):
+    return cbor_head(5, len(items)) + b"".join(cbor_int(k) + v for k, v in items)
+
+
+def write(name, text):
+    with open(os.path.join(DIR, name), "w") as f:
+        f.write(text + "\n")
+
+
+def wrap(data):
+    h = data.hex()
+    return "\n".join(h[i : i + 64] for i in range(0, len(h), 64))
+
+
+def main():
+    tmp = tempfile.mkdtemp()
+    key, msg_file = os.path.join(tmp, "k.pem"), os.path.join(tmp, "m.bin")
+
+    for i, (name, alg, vk_len) in enumerate(PARAMETER_SETS):
+        run("openssl", "genpkey", "-algorithm", name, "-pkeyopt", "hexseed:" + SEED.hex(), "-out", key)
+        vk = run("openssl", "pkey", "-in", key, "-pubout", "-outform", "DER")[-vk_len:]
+
+        def sign(message, rnd):
+            with open(msg_file, "wb") as f:
+                f.write(message)
+            return run("openssl", "pkeyutl", "-sign", "-inkey", key, "-rawin", "-in", msg_file,
+                       "-pkeyopt", "hextest-entropy:" + rnd.hex())
+
+        # JOSE
+        jwk = {"kty": "AKP", "alg": name, "pub": b64url(vk), "priv": b64url(SEED)}
+        write(name + ".jwk", json.dumps(jwk, indent=2))
+        header = b64url(json.dumps({"alg": name, "kid": KID.decode()}).encode())
+        signing_input = header + "." + b64url(PAYLOAD)
+        sig = sign(signing_input.encode(), bytes([2 * i + 1]) * 32)
+        write(name + ".jws", signing_input + "." + b64url(sig))
+
+        # COSE: {1: 7, 3: alg, -1: pub, -2: priv}, and
+        # 18([protected, {4: kid}, payload, signature]) over ["Signature1", protected, h'', payload]
+        cose_key = cbor_map([(1, cbor_int(7)), (3, cbor_int(alg)), (-1, cbor_bytes(vk)),
+                             (-2, cbor_bytes(SEED))])
+        write(name + ".cose-key", wrap(cose_key))
+        protected = cbor_map([(1, cbor_int(alg))])
+        sig_structure = (cbor_head(4, 4) + cbor_head(3, 10) + b"Signature1" + cbor_bytes(protected)
+                         + cbor_bytes(b"") + cbor_bytes(PAYLOAD))
+        sig = sign(sig_structure, bytes([2 * i + 2]) * 32)
+        sign1 = (cbor_head(6, 18) + cbor_head(4, 4) + cbor_bytes(protected)
+                 + cbor_map([(4, cbor_bytes(KID))]) + cbor_bytes(PAYLOAD) + cbor_bytes(sig))
+        write(name + ".cose-sign1", wrap(sign1))
+
+
+if __name__ == "__main__":
+    main()
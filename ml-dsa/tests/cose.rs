@@ -0,0 +1,339 @@
+//! COSE keys and `COSE_Sign1` signatures, in the formats of draft-ietf-cose-dilithium
+#![cfg(all(feature = "cose", feature = "rand_core"))]
+
+use ciborium::Value;
+use hex_literal::hex;
+use ml_dsa::cose::CoseAlgorithm;
+use ml_dsa::{B32, KeyPair, MlDsa44, MlDsa65, MlDsa87, VerifyingKey};
+use std::path::PathBuf;
+
+const PAYLOAD: &[u8] = "It’s a dangerous business, Frodo, going out your door.".as_bytes();
+
+// The seed 00 01 02 ... 1f
+fn seed() -> B32 {
+    B32::from_fn(|i| u8::try_from(i).unwrap())
+}
+
+fn key_pair<P: CoseAlgorithm>() -> KeyPair<P> {
+    KeyPair::from_seed(&seed())
+}
+
+// An example made by OpenSSL, from `tests/cose-dilithium`, in hex.  A missing example fails the
+// test.
+fn example(name: &str) -> Vec<u8> {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("tests/cose-dilithium");
+    p.push(name);
+    let example = std::fs::read_to_string(&p).unwrap_or_else(|err| {
+        panic!(
+            "cannot read {}: {err}; see tests/cose-dilithium/README.md",
+            p.display()
+        )
+    });
+    hex::decode(example.split_whitespace().collect::<String>()).unwrap()
+}
+
+fn encode(value: &Value) -> Vec<u8> {
+    let mut encoded = Vec::new();
+    ciborium::into_writer(value, &mut encoded).unwrap();
+    encoded
+}
+
+fn decode(bytes: &[u8]) -> Value {
+    ciborium::from_reader(bytes).unwrap()
+}
+
+// For each parameter set, the CBOR heads of the `alg` value and of the byte strings of the
+// verifying key and the signature
+struct Heads {
+    alg: [u8; 2],
+    vk: [u8; 3],
+    sig: [u8; 3],
+}
+
+#[test]
+fn keys() {
+    fn check<P: CoseAlgorithm>(heads: &Heads) {
+        let kp = key_pair::<P>();
+        let vk = kp.verifying_key().encode();
+
+        // {1: 7, 3: alg, -1: pub} and {1: 7, 3: alg, -1: pub, -2: priv}
+        let public = [
+            &hex!("a3 01 07 03")[..],
+            &heads.alg,
+            &[0x20],
+            &heads.vk,
+            &vk,
+        ]
+        .concat();
+        let private = [
+            &hex!("a4 01 07 03")[..],
+            &heads.alg,
+            &[0x20],
+            &heads.vk,
+            &vk,
+            &hex!("21 58 20"),
+            &seed(),
+        ]
+        .concat();
+        assert_eq!(kp.verifying_key().to_cose_key(), public);
+        assert_eq!(kp.to_cose_key(), private);
+
+        // Both round trip, and the public key ignores `priv`
+        let decoded = KeyPair::<P>::from_cose_key(&private).unwrap();
+        assert_eq!(decoded.verifying_key(), kp.verifying_key());
+        assert!(decoded.signing_key() == kp.signing_key());
+        for key in [&public, &private] {
+            assert_eq!(
+                &VerifyingKey::<P>::from_cose_key(key).unwrap(),
+                kp.verifying_key()
+            );
+        }
+
+        // A key pair needs its seed
+        assert!(KeyPair::<P>::from_cose_key(&public).is_err());
+    }
+
+    check::<MlDsa44>(&Heads {
+        alg: hex!("38 2f"),
+        vk: hex!("59 05 20"),
+        sig: hex!("59 09 74"),
+    });
+    check::<MlDsa65>(&Heads {
+        alg: hex!("38 30"),
+        vk: hex!("59 07 a0"),
+        sig: hex!("59 0c ed"),
+    });
+    check::<MlDsa87>(&Heads {
+        alg: hex!("38 31"),
+        vk: hex!("59 0a 20"),
+        sig: hex!("59 12 13"),
+    });
+}
+
+#[test]
+fn openssl_examples() {
+    fn check<P: CoseAlgorithm>(name: &str) {
+        let key = example(&format!("{name}.cose-key"));
+        let msg = example(&format!("{name}.cose-sign1"));
+
+        // The key pair decodes, which checks `pub` against the seed, and encodes to the same bytes
+        let kp = KeyPair::<P>::from_cose_key(&key).unwrap();
+        assert_eq!(kp.to_cose_key(), key);
+
+        // The example signature verifies with the public key
+        let vk = VerifyingKey::<P>::from_cose_key(&kp.verifying_key().to_cose_key()).unwrap();
+        vk.verify_cose_sign1(&msg).unwrap();
+    }
+
+    check::<MlDsa44>("ML-DSA-44");
+    check::<MlDsa65>("ML-DSA-65");
+    check::<MlDsa87>("ML-DSA-87");
+}
+
+#[test]
+fn malformed_keys() {
+    let kp = key_pair::<MlDsa44>();
+    let Value::Map(key) = decode(&kp.to_cose_key()) else {
+        panic!("not a map");
+    };
+
+    // Change or drop each parameter in turn
+    let changes: [(i64, Option<Value>); 8] = [
+        (1, Some(1.into())),
+        (1, None),
+        (3, Some((-49).into())),
+        (3, None),
+        (-1, Some(Value::Bytes(vec![0; 32]))),
+        (-1, Some(Value::Text("pub".into()))),
+        (-2, Some(Value::Bytes(vec![1; 32]))),
+        (-2, Some(Value::Bytes(vec![0; 31]))),
+    ];
+    for (label, value) in changes {
+        let changed = key
+            .iter()
+            .filter_map(|(l, v)| {
+                if *l != label.into() {
+                    Some((l.clone(), v.clone()))
+                } else {
+                    value.clone().map(|value| (l.clone(), value))
+                }
+            })
+            .collect();
+        let changed = encode(&Value::Map(changed));
+        assert!(
+            KeyPair::<MlDsa44>::from_cose_key(&changed).is_err(),
+            "{label}"
+        );
+    }
+
+    // Labels may not repeat, and nothing may follow the key
+    let mut repeated = key.clone();
+    repeated.push((1.into(), 7.into()));
+    assert!(VerifyingKey::<MlDsa44>::from_cose_key(&encode(&Value::Map(repeated))).is_err());
+    let trailing = [kp.to_cose_key(), vec![0]].concat();
+    assert!(VerifyingKey::<MlDsa44>::from_cose_key(&trailing).is_err());
+
+    // The parameter set comes from the type, and has to match `alg`
+    assert!(VerifyingKey::<MlDsa65>::from_cose_key(&kp.verifying_key().to_cose_key()).is_err());
+    assert!(VerifyingKey::<MlDsa44>::from_cose_key(&hex!("ff")).is_err());
+}
+
+#[test]
+fn signatures() {
+    fn check<P: CoseAlgorithm>(heads: &Heads) {
+        let kp = key_pair::<P>();
+        let msg = kp
+            .signing_key()
+            .sign_cose_sign1_deterministic(PAYLOAD)
+            .unwrap();
+
+        // The protected header is {1: alg}, and the signature is of
+        // ["Signature1", protected, h'', payload] with an empty context
+        let protected = [&[0xa1, 0x01], &heads.alg[..]].concat();
+        let sig_structure = [
+            &hex!("84 6a")[..],
+            b"Signature1",
+            &[0x44],
+            &protected,
+            &hex!("40 58 38"),
+            PAYLOAD,
+        ]
+        .concat();
+        let signature = kp
+            .signing_key()
+            .sign_deterministic(&sig_structure, &[])
+            .unwrap();
+
+        // 18([protected, {}, payload, signature])
+        let expected = [
+            &hex!("d2 84 44")[..],
+            &protected,
+            &hex!("a0 58 38"),
+            PAYLOAD,
+            &heads.sig,
+            &signature.encode(),
+        ]
+        .concat();
+        assert_eq!(msg, expected);
+        assert_eq!(kp.verifying_key().verify_cose_sign1(&msg).unwrap(), PAYLOAD);
+
+        // The tag is optional
+        assert_eq!(
+            kp.verifying_key().verify_cose_sign1(&msg[1..]).unwrap(),
+            PAYLOAD
+        );
+
+        let mut rng = rand::rng();
+        let msg = kp
+            .signing_key()
+            .sign_cose_sign1_randomized(PAYLOAD, &mut rng)
+            .unwrap();
+        assert_eq!(kp.verifying_key().verify_cose_sign1(&msg).unwrap(), PAYLOAD);
+    }
+
+    check::<MlDsa44>(&Heads {
+        alg: hex!("38 2f"),
+        vk: hex!("59 05 20"),
+        sig: hex!("59 09 74"),
+    });
+    check::<MlDsa65>(&Heads {
+        alg: hex!("38 30"),
+        vk: hex!("59 07 a0"),
+        sig: hex!("59 0c ed"),
+    });
+    check::<MlDsa87>(&Heads {
+        alg: hex!("38 31"),
+        vk: hex!("59 0a 20"),
+        sig: hex!("59 12 13"),
+    });
+}
+
+#[test]
+fn invalid_signatures() {
+    let kp = key_pair::<MlDsa44>();
+    let vk = kp.verifying_key();
+
+    // A message signed over another protected header, which is otherwise well formed
+    let sign = |protected: Value, unprotected: Vec<(Value, Value)>, payload: Value| {
+        let protected = encode(&protected);
+        let Value::Bytes(signed) = &payload else {
+            panic!("detached payload");
+        };
+        let sig_structure = encode(&Value::Array(vec![
+            Value::Text("Signature1".into()),
+            Value::Bytes(protected.clone()),
+            Value::Bytes(Vec::new()),
+            Value::Bytes(signed.clone()),
+        ]));
+        let signature = kp
+            .signing_key()
+            .sign_deterministic(&sig_structure, &[])
+            .unwrap();
+        encode(&Value::Tag(
+            18,
+            Box::new(Value::Array(vec![
+                Value::Bytes(protected),
+                Value::Map(unprotected),
+                payload,
+                Value::Bytes(signature.encode().to_vec()),
+            ])),
+        ))
+    };
+    let alg = |alg: i64| Value::Map(vec![(1.into(), alg.into())]);
+    let payload = || Value::Bytes(PAYLOAD.to_vec());
+
+    let kid = vec![(4.into(), Value::Bytes(b"1".to_vec()))];
+    assert!(
+        vk.verify_cose_sign1(&sign(alg(-48), kid, payload()))
+            .is_ok()
+    );
+    let crit = Value::Map(vec![
+        (1.into(), (-48).into()),
+        (2.into(), Value::Array(vec![99.into()])),
+        (99.into(), 0.into()),
+    ]);
+    for msg in [
+        sign(alg(-49), Vec::new(), payload()),
+        sign(
+            Value::Map(Vec::new()),
+            vec![(1.into(), (-48).into())],
+            payload(),
+        ),
+        sign(alg(-48), vec![(1.into(), (-48).into())], payload()),
+        sign(crit, Vec::new(), payload()),
+    ] {
+        assert!(vk.verify_cose_sign1(&msg).is_err());
+    }
+
+    let msg = kp
+        .signing_key()
+        .sign_cose_sign1_deterministic(PAYLOAD)
+        .unwrap();
+    let Value::Tag(18, inner) = decode(&msg) else {
+        panic!("not tagged");
+    };
+    let Value::Array(parts) = *inner else {
+        panic!("not an array");
+    };
+    let with = |i: usize, value: Value| {
+        let mut parts = parts.clone();
+        parts[i] = value;
+        encode(&Value::Tag(18, Box::new(Value::Array(parts))))
+    };
+    for msg in [
+        with(2, Value::Bytes(b"another payload".to_vec())),
+        with(2, Value::Null),
+        with(3, Value::Bytes(vec![0; 2420])),
+        with(3, Value::Bytes(vec![0; 10])),
+        encode(&Value::Tag(17, Box::new(Value::Array(parts.clone())))),
+        encode(&Value::Tag(18, Box::new(Value::Array(parts[..3].to_vec())))),
+        [msg.clone(), vec![0]].concat(),
+    ] {
+        assert!(vk.verify_cose_sign1(&msg).is_err());
+    }
+
+    // Another key
+    let other = KeyPair::<MlDsa44>::from_seed(&[1; 32].into());
+    assert!(other.verifying_key().verify_cose_sign1(&msg).is_err());
+}
@@ -0,0 +1,134 @@
+//! Round trips with parameter sets defined outside the crate
+
+use hybrid_array::AsArrayRef;
+use ml_dsa::typenum::{
+    Diff, Length, Prod, Quot, Shleft, U1, U2, U4, U5, U17, U19, U32, U44, U48, U75, U88, U92, U128,
+};
+use ml_dsa::{
+    Blake3Xof, CompactVerifyingKey, EncodedSignature, EncodedSigningKey, EncodedVerifyingKey,
+    KeyPair, MlDsaParams, ParameterSet, QMinus1, Signature, SigningKey, VerifyingKey, Xof,
+    signature::{Signer, Verifier},
+};
+use proptest::prelude::*;
+
+/// Example message
+const MSG: &[u8] = b"Hello world";
+
+/// ML-DSA-44 with a fifth row in the matrix
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Wide;
+
+impl ParameterSet for Wide {
+    type K = U5;
+    type L = U4;
+    type Eta = U2;
+    type Gamma1 = Shleft<U1, U17>;
+    type Gamma2 = Quot<QMinus1, U88>;
+    type TwoGamma2 = Prod<U2, Self::Gamma2>;
+    type W1Bits = Length<Diff<Quot<U88, U2>, U1>>;
+    type Lambda = U32;
+    type Omega = U75;
+    const TAU: usize = 39;
+}
+
+/// ML-DSA-44 with a finer decomposition of w, which is not one of the FIPS 204 values of γ2
+#[derive(Clone, Debug, Default, PartialEq)]
+struct FineGamma2;
+
+impl ParameterSet for FineGamma2 {
+    type K = U4;
+    type L = U4;
+    type Eta = U2;
+    type Gamma1 = Shleft<U1, U17>;
+    type Gamma2 = Quot<QMinus1, U128>;
+    type TwoGamma2 = Prod<U2, Self::Gamma2>;
+    type W1Bits = Length<Diff<Quot<U128, U2>, U1>>;
+    type Lambda = U32;
+    type Omega = U92;
+    const TAU: usize = 39;
+}
+
+/// The ML-DSA-65 distributions on a 4×4 matrix
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Square65;
+
+impl ParameterSet for Square65 {
+    type K = U4;
+    type L = U4;
+    type Eta = U4;
+    type Gamma1 = Shleft<U1, U19>;
+    type Gamma2 = Quot<QMinus1, U32>;
+    type TwoGamma2 = Prod<U2, Self::Gamma2>;
+    type W1Bits = Length<Diff<Quot<U32, U2>, U1>>;
+    type Lambda = U48;
+    type Omega = U44;
+    const TAU: usize = 49;
+}
+
+#[test]
+fn output_sizes() {
+    fn sizes<P: MlDsaParams>() -> [usize; 3] {
+        [
+            EncodedSigningKey::<P>::default().len(),
+            EncodedVerifyingKey::<P>::default().len(),
+            EncodedSignature::<P>::default().len(),
+        ]
+    }
+
+    //            priv pub  sig
+    assert_eq!(sizes::<Wide>(), [3072, 1632, 2416]);
+    assert_eq!(sizes::<FineGamma2>(), [2560, 1312, 2432]);
+    assert_eq!(sizes::<Square65>(), [2816, 1312, 2656]);
+}
+
+fn keypair<P: MlDsaParams, X: Xof>() -> impl Strategy<Value = KeyPair<P, X>> {
+    any::<[u8; 32]>().prop_map(|seed| KeyPair::from_seed(seed.as_array_ref()))
+}
+
+fn round_trip<P: MlDsaParams, X: Xof>(keypair: &KeyPair<P, X>) -> Result<(), TestCaseError> {
+    let sig = keypair.signing_key().sign(MSG);
+
+    // Check signature verification
+    let vk = keypair.verifying_key();
+    prop_assert!(vk.verify(MSG, &sig).is_ok());
+    prop_assert!(vk.verify(b"Goodbye world", &sig).is_err());
+
+    // Check the low-memory verifier
+    let compact = CompactVerifyingKey::from(vk);
+    prop_assert!(compact.verify_with_context(MSG, &[], &sig.encode()));
+    prop_assert!(!compact.verify_with_context(b"Goodbye world", &[], &sig.encode()));
+
+    // Check signature encoding round trip
+    let sig_decoded = Signature::<P>::decode(&sig.encode());
+    prop_assert_eq!(Some(&sig), sig_decoded.as_ref());
+
+    // Check key encoding round trips
+    let vk_decoded = VerifyingKey::<P, X>::decode(&vk.encode());
+    prop_assert!(vk_decoded.verify(MSG, &sig).is_ok());
+    let sk_decoded = SigningKey::<P, X>::decode(&keypair.signing_key().encode());
+    prop_assert!(sk_decoded == *keypair.signing_key());
+    prop_assert!(sk_decoded.verifying_key() == *vk);
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn wide_round_trip(keypair in keypair::<Wide, ml_dsa::ShakeXof>()) {
+        round_trip(&keypair)?;
+    }
+
+    #[test]
+    fn fine_gamma2_round_trip(keypair in keypair::<FineGamma2, ml_dsa::ShakeXof>()) {
+        round_trip(&keypair)?;
+    }
+
+    #[test]
+    fn square65_round_trip(keypair in keypair::<Square65, ml_dsa::ShakeXof>()) {
+        round_trip(&keypair)?;
+    }
+
+    #[test]
+    fn wide_blake3_round_trip(keypair in keypair::<Wide, Blake3Xof>()) {
+        round_trip(&keypair)?;
+    }
+}
@@ -0,0 +1,162 @@
+//! Test vectors for hierarchical key derivation
+#![cfg(feature = "derive")]
+
+use hex_literal::hex;
+use ml_dsa::derive::DerivationKey;
+use ml_dsa::{AesParams, AesXof, B32, CompactSigningKey, MlDsa44, MlDsa65, MlDsa87, MlDsaParams};
+
+// The master secret 00 01 02 ... 1f
+fn master() -> DerivationKey {
+    DerivationKey::new(&B32::from_fn(|i| u8::try_from(i).unwrap()))
+}
+
+// For each path, the seeds ξ for ML-DSA-44, -65 and -87, and the first 16 bytes of the verifying
+// keys that they generate.  The seeds were checked against an independent implementation of the
+// KDF described in the module documentation.
+#[allow(clippy::type_complexity)]
+const VECTORS: &[(&str, [([u8; 32], [u8; 16]); 3])] = &[
+    (
+        "m",
+        [
+            (
+                hex!("5d46e6c155b829f7f05b050c30598df97de9dacaa8d2d5692c5de668947763d4"),
+                hex!("2d90aa000799cde588cb1b7c19e7e792"),
+            ),
+            (
+                hex!("d83cb225abcd2d0e1832acfa14a847286728755c99cd7b22ec7461ffeacdaded"),
+                hex!("fc52340335f3772dcae355aeffd36f20"),
+            ),
+            (
+                hex!("b3eb495f9c2d277d6b1e10e73a3249f118c620cccf6949d3466604175b8e9b68"),
+                hex!("54ed513bd0b381c8b9532df3ee2d47c0"),
+            ),
+        ],
+    ),
+    (
+        "m/tenant/3",
+        [
+            (
+                hex!("8c684520260d32cdbefaca6dbf4cf0ef3e044c09cc36763d92a350f504137a7d"),
+                hex!("143541452375a285825f0352001b6704"),
+            ),
+            (
+                hex!("1df6fc4688e1352e77d9dfda5f41823c648715afcff4b1f6019c2a8b172daed9"),
+                hex!("6e1babd3d2c91ba0e27235f74e52a713"),
+            ),
+            (
+                hex!("904ce6525e0cce1032fd49b91eb0cee048c79ab40efd6b60de1bf341ae96a9a2"),
+                hex!("6b65e7eb679cdbd7486dbe1cc736fbc2"),
+            ),
+        ],
+    ),
+    (
+        "m/tenant/3/device/17",
+        [
+            (
+                hex!("949ad38ffe4ee321d0e7b1064e22ecc8f666d51b457dda430230b6c9accb9bb4"),
+                hex!("f46b074e5b31060ccc2d6ac5279961a8"),
+            ),
+            (
+                hex!("3f49084c49af07d60def8e38b979800ae63a6709a63f17266cd261e67557fdb3"),
+                hex!("b1a06b976a1ef1295ec262300e198bcf"),
+            ),
+            (
+                hex!("c1e7855217b9624ee835df249c984524beb26c727c99a8581428df1f9b3074d2"),
+                hex!("05c234a784796bf1ac5700bda0ac25c6"),
+            ),
+        ],
+    ),
+];
+
+fn check<P: MlDsaParams>(node: &DerivationKey, (xi, vk): &([u8; 32], [u8; 16])) {
+    assert_eq!(node.seed::<P>().as_slice(), xi);
+    let kp = node.key_pair::<P>();
+    assert_eq!(&kp.verifying_key().encode()[..16], vk);
+
+    let sk = node.compact_signing_key::<P>();
+    assert!(sk.expand() == *kp.signing_key());
+}
+
+#[test]
+fn derivation_vectors() {
+    for (path, [v44, v65, v87]) in VECTORS {
+        let node = master().derive(path).unwrap();
+        check::<MlDsa44>(&node, v44);
+        check::<MlDsa65>(&node, v65);
+        check::<MlDsa87>(&node, v87);
+    }
+}
+
+#[test]
+fn subtrees() {
+    let master = master();
+    let device = master.derive("m/tenant/3/device/17").unwrap();
+    let seed = device.seed::<MlDsa65>();
+
+    // A path is the same as its segments one at a time, or as a path from an intermediate node
+    let step = master.child(b"tenant").unwrap().child(b"3").unwrap();
+    assert_eq!(step.derive("m/device/17").unwrap().seed::<MlDsa65>(), seed);
+    assert_eq!(
+        step.child(b"device")
+            .unwrap()
+            .child(b"17")
+            .unwrap()
+            .seed::<MlDsa65>(),
+        seed
+    );
+    assert_eq!(
+        master.derive("m").unwrap().seed::<MlDsa65>(),
+        master.seed::<MlDsa65>()
+    );
+
+    // Segments are length-prefixed, so moving a byte across a boundary changes the node
+    assert_ne!(
+        master
+            .derive("m/tenant/31/device/7")
+            .unwrap()
+            .seed::<MlDsa65>(),
+        seed
+    );
+    assert_ne!(
+        master
+            .derive("m/tenant/3/device/17/0")
+            .unwrap()
+            .seed::<MlDsa65>(),
+        seed
+    );
+
+    // The key pair of a node matches one generated from its seed
+    let kp = device.key_pair::<MlDsa87>();
+    let compact = CompactSigningKey::<MlDsa87>::from_seed(&device.seed::<MlDsa87>());
+    assert_eq!(compact.verifying_key(), *kp.verifying_key());
+
+    // The compact key keeps the backend it is given, including its configuration
+    let xof = AesXof::new(AesParams {
+        cache_bytes: 256,
+        ..AesParams::DEFAULT
+    });
+    let compact = device.compact_signing_key_with_xof::<MlDsa65, _>(xof);
+    assert_eq!(*compact.xof(), xof);
+    assert!(compact.expand() == *device.key_pair_with_xof::<MlDsa65, _>(xof).signing_key());
+}
+
+#[test]
+fn invalid_paths() {
+    let master = master();
+    for path in [
+        "",
+        "tenant/3",
+        "/tenant/3",
+        "m/",
+        "m//3",
+        "M/tenant",
+        "n/tenant",
+    ] {
+        assert!(master.derive(path).is_err(), "{path:?}");
+    }
+
+    assert!(master.child(&[]).is_err());
+    assert!(master.child(&[b'a'; 255]).is_ok());
+    assert!(master.child(&[b'a'; 256]).is_err());
+    assert!(master.derive(&format!("m/{}", "a".repeat(256))).is_err());
+}
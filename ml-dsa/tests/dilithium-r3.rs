@@ -0,0 +1,173 @@
+use ml_dsa::dilithium_r3::*;
+
+use aes::{
+    Aes256,
+    cipher::{BlockEncrypt, KeyInit},
+};
+use hybrid_array::Array;
+use std::{fs::read_to_string, path::PathBuf};
+
+// Known answers from the round-3 submission package.  See `tests/dilithium-r3/README.md` for where
+// the `.rsp` files come from.  A missing file fails the test rather than being skipped, so that the
+// module is never reported as checked against the reference implementation when it was not.
+#[test]
+fn dilithium_r3_kat() {
+    verify_file::<Dilithium2, Shake>("Dilithium2");
+    verify_file::<Dilithium3, Shake>("Dilithium3");
+    verify_file::<Dilithium5, Shake>("Dilithium5");
+    verify_file::<Dilithium2, Aes>("Dilithium2-AES");
+    verify_file::<Dilithium3, Aes>("Dilithium3-AES");
+    verify_file::<Dilithium5, Aes>("Dilithium5-AES");
+}
+
+// The first seed of every `PQCsignKAT_*.rsp` file, which checks the DRBG even without the files
+#[test]
+fn drbg_first_seed() {
+    let mut drbg = kat::Drbg::new(&core::array::from_fn(|i| u8::try_from(i).unwrap()));
+    let mut seed = [0u8; 48];
+    drbg.fill(&mut seed);
+    assert_eq!(
+        seed,
+        hex_literal::hex!(
+            "061550234D158C5EC95595FE04EF7A25767F2E24CC2BC479D09D86DC9ABCFDE7"
+            "056A8C266F9EF97ED08541DBD2E1FFA1"
+        )
+    );
+}
+
+fn verify_file<P: DilithiumParams, V: Variant>(name: &str) {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push(format!("tests/dilithium-r3/{name}.rsp"));
+    let rsp = read_to_string(p.as_path()).unwrap_or_else(|err| {
+        panic!(
+            "{name}: cannot read {}: {err}; see tests/dilithium-r3/README.md",
+            p.display()
+        )
+    });
+
+    let test_cases = kat::parse(&rsp);
+    assert!(!test_cases.is_empty(), "{name}: no test cases");
+    for tc in test_cases {
+        verify::<P, V>(&tc);
+    }
+}
+
+fn verify<P: DilithiumParams, V: Variant>(tc: &kat::TestCase) {
+    // The reference implementation draws the key generation seed from a DRBG seeded per test
+    let mut drbg = kat::Drbg::new(&tc.seed.as_slice().try_into().unwrap());
+    let mut zeta = Array::default();
+    drbg.fill(&mut zeta);
+
+    // Key generation
+    let kp = KeyPair::<P, V>::from_seed(&zeta);
+    let sk = kp.signing_key();
+    let vk = kp.verifying_key();
+    assert_eq!(
+        vk.encode().as_slice(),
+        tc.pk.as_slice(),
+        "count {}",
+        tc.count
+    );
+    assert_eq!(sk.encode().as_ref(), tc.sk.as_slice(), "count {}", tc.count);
+
+    // Deterministic signing.  The signed message is the signature followed by the message.
+    let sig = sk.sign_deterministic(&tc.msg).unwrap();
+    let (sig_bytes, msg) = tc.sm.split_at(tc.sm.len() - tc.msg.len());
+    assert_eq!(msg, tc.msg.as_slice());
+    assert_eq!(sig.encode().as_ref(), sig_bytes, "count {}", tc.count);
+
+    // Verification, from the decoded encodings
+    let vk = VerifyingKey::<P, V>::decode(&tc.pk.as_slice().try_into().unwrap());
+    let sig = Signature::<P>::try_from(sig_bytes).unwrap();
+    assert!(vk.verify_message(&tc.msg, &sig));
+    assert!(!vk.verify_message(b"not the message", &sig));
+}
+
+mod kat {
+    use super::*;
+
+    pub struct TestCase {
+        pub count: usize,
+        pub seed: Vec<u8>,
+        pub msg: Vec<u8>,
+        pub pk: Vec<u8>,
+        pub sk: Vec<u8>,
+        pub sm: Vec<u8>,
+    }
+
+    // Parse the `key = value` blocks of a `.rsp` file, which are separated by blank lines
+    pub fn parse(rsp: &str) -> Vec<TestCase> {
+        rsp.split("\n\n")
+            .filter(|block| block.contains("count ="))
+            .map(|block| {
+                let field = |name: &str| {
+                    block
+                        .lines()
+                        .find_map(|line| line.strip_prefix(name)?.strip_prefix(" = "))
+                        .unwrap_or_else(|| panic!("missing {name}"))
+                        .trim()
+                };
+                let bytes = |name: &str| hex::decode(field(name)).unwrap();
+
+                TestCase {
+                    count: field("count").parse().unwrap(),
+                    seed: bytes("seed"),
+                    msg: bytes("msg"),
+                    pk: bytes("pk"),
+                    sk: bytes("sk"),
+                    sm: bytes("sm"),
+                }
+            })
+            .collect()
+    }
+
+    /// The AES-256 CTR_DRBG of `rng.c` in the NIST submission packages, without derivation
+    /// function or prediction resistance
+    pub struct Drbg {
+        key: [u8; 32],
+        v: [u8; 16],
+    }
+
+    impl Drbg {
+        pub fn new(entropy: &[u8; 48]) -> Self {
+            let mut drbg = Self {
+                key: [0; 32],
+                v: [0; 16],
+            };
+            drbg.update(Some(entropy));
+            drbg
+        }
+
+        pub fn fill(&mut self, output: &mut [u8]) {
+            for chunk in output.chunks_mut(16) {
+                let block = self.next_block();
+                chunk.copy_from_slice(&block[..chunk.len()]);
+            }
+            self.update(None);
+        }
+
+        fn next_block(&mut self) -> [u8; 16] {
+            let v = u128::from_be_bytes(self.v).wrapping_add(1);
+            self.v = v.to_be_bytes();
+
+            let mut block = self.v.into();
+            Aes256::new(&self.key.into()).encrypt_block(&mut block);
+            block.into()
+        }
+
+        fn update(&mut self, provided: Option<&[u8; 48]>) {
+            let mut temp = [0u8; 48];
+            for chunk in temp.chunks_mut(16) {
+                chunk.copy_from_slice(&self.next_block());
+            }
+            if let Some(provided) = provided {
+                for (t, p) in temp.iter_mut().zip(provided) {
+                    *t ^= p;
+                }
+            }
+
+            self.key.copy_from_slice(&temp[..32]);
+            self.v.copy_from_slice(&temp[32..]);
+        }
+    }
+}
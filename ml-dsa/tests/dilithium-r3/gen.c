@@ -0,0 +1,223 @@
+/*
+ * Writes the first KAT_COUNT test cases of PQCgenKAT_sign for CRYSTALS-Dilithium round 3
+ * (version 3.1), using a copy of mldsa-native patched back to round 3 by r3.patch.
+ *
+ * The DRBG is rng.c of the NIST submission packages, and the loop follows PQCgenKAT_sign.c:
+ * the request file and the response file are produced in one pass, since the DRBG that draws
+ * the seeds and messages is not used for anything else.
+ *
+ * Build with -DMLD_CONFIG_PARAMETER_SET=44|65|87, and -DR3_AES for Dilithium-AES.
+ */
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include <openssl/evp.h>
+
+#define MLD_CONFIG_SERIAL_FIPS202_ONLY
+#define MLD_CONFIG_NO_RANDOMIZED_API
+#define MLD_CONFIG_INTERNAL_API_QUALIFIER static
+#define MLD_CONFIG_EXTERNAL_API_QUALIFIER static
+#define MLD_CONFIG_NAMESPACE_PREFIX r3
+#include "mldsa/mldsa_native.c"
+
+/* The sizes of round 3, or of ML-DSA with -DCHECK_ML_DSA to check the unpatched sources */
+#if MLD_CONFIG_PARAMETER_SET == 44
+#define PK_BYTES 1312
+#define SK_BYTES (2528 + TR_PADDING)
+#define SIG_BYTES 2420
+#elif MLD_CONFIG_PARAMETER_SET == 65
+#define PK_BYTES 1952
+#define SK_BYTES (4000 + TR_PADDING)
+#define SIG_BYTES (3293 + 16 * CHECK_ML_DSA)
+#else
+#define PK_BYTES 2592
+#define SK_BYTES (4864 + TR_PADDING)
+#define SIG_BYTES (4595 + 32 * CHECK_ML_DSA)
+#endif
+#define TR_PADDING (32 * CHECK_ML_DSA)
+#ifndef CHECK_ML_DSA
+#define CHECK_ML_DSA 0
+#endif
+
+#ifndef KAT_COUNT
+#define KAT_COUNT 3
+#endif
+
+/* rng.c */
+
+static unsigned char drbg_key[32];
+static unsigned char drbg_v[16];
+
+static void aes256_ecb(const unsigned char key[32], const unsigned char in[16],
+                       unsigned char out[16])
+{
+  EVP_CIPHER_CTX *ctx = EVP_CIPHER_CTX_new();
+  int len;
+  if (ctx == NULL || EVP_EncryptInit_ex(ctx, EVP_aes_256_ecb(), NULL, key, NULL) != 1 ||
+      EVP_CIPHER_CTX_set_padding(ctx, 0) != 1 ||
+      EVP_EncryptUpdate(ctx, out, &len, in, 16) != 1)
+  {
+    fprintf(stderr, "AES failed\n");
+    exit(1);
+  }
+  EVP_CIPHER_CTX_free(ctx);
+}
+
+static void increment_v(void)
+{
+  int j;
+  for (j = 15; j >= 0; j--)
+  {
+    if (drbg_v[j] == 0xff)
+    {
+      drbg_v[j] = 0x00;
+    }
+    else
+    {
+      drbg_v[j]++;
+      break;
+    }
+  }
+}
+
+static void drbg_update(const unsigned char *provided_data)
+{
+  unsigned char temp[48];
+  int i;
+  for (i = 0; i < 3; i++)
+  {
+    increment_v();
+    aes256_ecb(drbg_key, drbg_v, temp + 16 * i);
+  }
+  if (provided_data != NULL)
+  {
+    for (i = 0; i < 48; i++)
+    {
+      temp[i] ^= provided_data[i];
+    }
+  }
+  memcpy(drbg_key, temp, 32);
+  memcpy(drbg_v, temp + 32, 16);
+}
+
+static void randombytes_init(const unsigned char entropy_input[48])
+{
+  memset(drbg_key, 0x00, 32);
+  memset(drbg_v, 0x00, 16);
+  drbg_update(entropy_input);
+}
+
+static void randombytes(unsigned char *x, size_t xlen)
+{
+  unsigned char block[16];
+  size_t i = 0;
+  while (xlen > 0)
+  {
+    increment_v();
+    aes256_ecb(drbg_key, drbg_v, block);
+    if (xlen > 15)
+    {
+      memcpy(x + i, block, 16);
+      i += 16;
+      xlen -= 16;
+    }
+    else
+    {
+      memcpy(x + i, block, xlen);
+      xlen = 0;
+    }
+  }
+  drbg_update(NULL);
+}
+
+/* PQCgenKAT_sign.c */
+
+static void print_bstr(const char *s, const unsigned char *a, size_t l)
+{
+  size_t i;
+  printf("%s", s);
+  for (i = 0; i < l; i++)
+  {
+    printf("%02X", a[i]);
+  }
+  if (l == 0)
+  {
+    printf("00");
+  }
+  printf("\n");
+}
+
+int main(void)
+{
+  static unsigned char seeds[KAT_COUNT][48];
+  static unsigned char msgs[KAT_COUNT][33 * KAT_COUNT];
+  static uint8_t pk[PK_BYTES];
+  static uint8_t sk[SK_BYTES];
+  static uint8_t sm[SIG_BYTES + 33 * KAT_COUNT];
+  static const uint8_t rnd[32] = {0};
+  unsigned char entropy_input[48];
+  uint8_t zeta[32];
+  int i;
+
+  for (i = 0; i < 48; i++)
+  {
+    entropy_input[i] = (unsigned char)i;
+  }
+  randombytes_init(entropy_input);
+  for (i = 0; i < KAT_COUNT; i++)
+  {
+    randombytes(seeds[i], 48);
+    randombytes(msgs[i], 33 * (size_t)(i + 1));
+  }
+
+#ifdef R3_AES
+  printf("# Dilithium%d-AES\n\n", MLD_CONFIG_PARAMETER_SET == 44   ? 2
+                                  : MLD_CONFIG_PARAMETER_SET == 65 ? 3
+                                                                   : 5);
+#else
+  printf("# Dilithium%d\n\n", MLD_CONFIG_PARAMETER_SET == 44   ? 2
+                              : MLD_CONFIG_PARAMETER_SET == 65 ? 3
+                                                               : 5);
+#endif
+
+  for (i = 0; i < KAT_COUNT; i++)
+  {
+    size_t mlen = 33 * (size_t)(i + 1);
+
+    printf("count = %d\n", i);
+    randombytes_init(seeds[i]);
+    print_bstr("seed = ", seeds[i], 48);
+    printf("mlen = %zu\n", mlen);
+    print_bstr("msg = ", msgs[i], mlen);
+
+    /* crypto_sign_keypair draws its seed from the DRBG */
+    randombytes(zeta, 32);
+    if (r3_keypair_internal(pk, sk, zeta) != 0)
+    {
+      fprintf(stderr, "keypair failed\n");
+      return 1;
+    }
+    print_bstr("pk = ", pk, PK_BYTES);
+    print_bstr("sk = ", sk, SK_BYTES);
+
+    /* crypto_sign: the signature followed by the message, signed deterministically */
+    memcpy(sm + SIG_BYTES, msgs[i], mlen);
+    if (r3_signature_internal(sm, msgs[i], mlen, NULL, 0, rnd, sk, 0) != 0)
+    {
+      fprintf(stderr, "sign failed\n");
+      return 1;
+    }
+    printf("smlen = %zu\n", SIG_BYTES + mlen);
+    print_bstr("sm = ", sm, SIG_BYTES + mlen);
+    printf("\n");
+
+    if (r3_verify_internal(sm, msgs[i], mlen, NULL, 0, pk, 0) != 0)
+    {
+      fprintf(stderr, "verify failed\n");
+      return 1;
+    }
+  }
+
+  return 0;
+}
@@ -0,0 +1,119 @@
+#!/usr/bin/env python3
+# Generates hash-sig-gen.json, the HashML-DSA sigGen vectors used by hash-sig-gen.rs.
+#
+# The NIST ACVP server's preHash sigGen groups are not in this repository, so these vectors come
+# from OpenSSL instead (3.5.6 when they were generated).  OpenSSL signs the message representative
+# M' = 1 || len(ctx) || ctx || OID || PH(M) built here with `message-encoding:0`, so the HashML-DSA
+# encoding is this script's, while key expansion and signing are OpenSSL's.  The output is in the
+# format of the ACVP sigGen files, and the script is deterministic: running it again must reproduce
+# the checked-in file.
+#
+# Usage: python3 tests/hash-sig-gen.py
+
+import hashlib
+import json
+import os
+import random
+import re
+import subprocess
+import tempfile
+
+HASHES = [  # ACVP name, last arc of the OID under 2.16.840.1.101.3.4.2, hash function
+    ("SHA2-224", 4, lambda m: hashlib.sha224(m).digest()),
+    ("SHA2-256", 1, lambda m: hashlib.sha256(m).digest()),
+    ("SHA2-384", 2, lambda m: hashlib.sha384(m).digest()),
+    ("SHA2-512", 3, lambda m: hashlib.sha512(m).digest()),
+    ("SHA2-512/224", 5, lambda m: hashlib.new("sha512_224", m).digest()),
+    ("SHA2-512/256", 6, lambda m: hashlib.new("sha512_256", m).digest()),
+    ("SHA3-224", 7, lambda m: hashlib.sha3_224(m).digest()),
+    ("SHA3-256", 8, lambda m: hashlib.sha3_256(m).digest()),
+    ("SHA3-384", 9, lambda m: hashlib.sha3_384(m).digest()),
+    ("SHA3-512", 10, lambda m: hashlib.sha3_512(m).digest()),
+    ("SHAKE-128", 11, lambda m: hashlib.shake_128(m).digest(32)),
+    ("SHAKE-256", 12, lambda m: hashlib.shake_256(m).digest(64)),
+]
+PARAMETER_SETS = ["ML-DSA-44", "ML-DSA-65", "ML-DSA-87"]
+
+rng = random.Random(204)
+
+
+def random_bytes(n):
+    return bytes(rng.getrandbits(8) for _ in range(n))
+
+
+def run(*args):
+    return subprocess.run(args, check=True, capture_output=True).stdout
+
+
+def main():
+    tmp = tempfile.mkdtemp()
+    key, mp_file, sig_file = (os.path.join(tmp, f) for f in ("k.pem", "m.bin", "s.bin"))
+
+    groups = {}
+    for i, (name, arc, digest) in enumerate(HASHES):
+        ps = PARAMETER_SETS[i % 3]
+        deterministic = i % 2 == 0
+
+        seed = random_bytes(32)
+        run("openssl", "genpkey", "-algorithm", ps, "-pkeyopt", "hexseed:" + seed.hex(), "-out", key)
+        text = run("openssl", "pkey", "-in", key, "-text", "-noout").decode()
+        sk = bytes.fromhex(re.sub(r"[^0-9a-f]", "", text.split("priv:")[1].split("pub:")[0]))
+
+        msg = random_bytes(rng.randrange(1, 300))
+        ctx = random_bytes([0, 255, rng.randrange(1, 64)][i % 3])
+        oid = bytes([0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, arc])
+        with open(mp_file, "wb") as f:
+            f.write(bytes([1, len(ctx)]) + ctx + oid + digest(msg))
+
+        args = ["openssl", "pkeyutl", "-sign", "-inkey", key, "-rawin", "-in", mp_file,
+                "-pkeyopt", "message-encoding:0", "-out", sig_file]
+        if deterministic:
+            args += ["-pkeyopt", "deterministic:1"]
+        else:
+            rnd = random_bytes(32)
+            args += ["-pkeyopt", "hextest-entropy:" + rnd.hex()]
+        run(*args)
+        with open(sig_file, "rb") as f:
+            sig = f.read()
+
+        case = {
+            "tcId": i + 1,
+            "sk": sk.hex().upper(),
+            "message": msg.hex().upper(),
+            "context": ctx.hex().upper(),
+            "hashAlg": name,
+        }
+        if not deterministic:
+            case["rnd"] = rnd.hex().upper()
+        case["signature"] = sig.hex().upper()
+        groups.setdefault((ps, deterministic), []).append(case)
+
+    out = {
+        "vsId": 0,
+        "algorithm": "ML-DSA",
+        "mode": "sigGen",
+        "revision": "FIPS204",
+        "isSample": True,
+        "testGroups": [],
+    }
+    ordered = sorted(groups.items(), key=lambda kv: (PARAMETER_SETS.index(kv[0][0]), not kv[0][1]))
+    for tg_id, ((ps, deterministic), tests) in enumerate(ordered, 1):
+        out["testGroups"].append({
+            "tgId": tg_id,
+            "testType": "AFT",
+            "parameterSet": ps,
+            "deterministic": deterministic,
+            "signatureInterface": "external",
+            "preHash": "preHash",
+            "externalMu": False,
+            "tests": tests,
+        })
+
+    path = os.path.join(os.path.dirname(os.path.abspath(__file__)), "hash-sig-gen.json")
+    with open(path, "w") as f:
+        json.dump(out, f, indent=2)
+        f.write("\n")
+
+
+if __name__ == "__main__":
+    main()
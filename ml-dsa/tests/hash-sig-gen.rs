@@ -0,0 +1,244 @@
+// The randomized cases need an RNG
+#![cfg(feature = "rand_core")]
+
+use ml_dsa::{
+    prehash::*,
+    signature::hazmat::{PrehashSigner, PrehashVerifier},
+    *,
+};
+use sha2::Digest;
+use sha3::digest::{ExtendableOutput, Update};
+
+use std::{fs::read_to_string, path::PathBuf};
+
+// These vectors are in the ACVP sigGen format, but they are not from the NIST ACVP server: they
+// were generated with OpenSSL 3.5 by `tests/hash-sig-gen.py`.  See `tests/README.md`.
+#[test]
+fn acvp_hash_sig_gen() {
+    // Load the JSON test file
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("tests/hash-sig-gen.json");
+    let tv_json = read_to_string(p.as_path()).unwrap();
+
+    // Parse the test vectors
+    let tv: acvp::TestVectorFile = serde_json::from_str(&tv_json).unwrap();
+
+    // Verify the test vectors
+    for tg in tv.test_groups {
+        for tc in tg.tests {
+            match tg.parameter_set {
+                acvp::ParameterSet::MlDsa44 => verify_hash::<MlDsa44>(&tc, tg.deterministic),
+                acvp::ParameterSet::MlDsa65 => verify_hash::<MlDsa65>(&tc, tg.deterministic),
+                acvp::ParameterSet::MlDsa87 => verify_hash::<MlDsa87>(&tc, tg.deterministic),
+            }
+        }
+    }
+}
+
+fn verify_hash<P: MlDsaParams>(tc: &acvp::TestCase, deterministic: bool) {
+    let msg = tc.message.as_slice();
+    match tc.hash_alg {
+        acvp::HashAlg::Sha224 => verify::<P, Sha224>(tc, deterministic, &sha2::Sha224::digest(msg)),
+        acvp::HashAlg::Sha256 => verify::<P, Sha256>(tc, deterministic, &sha2::Sha256::digest(msg)),
+        acvp::HashAlg::Sha384 => verify::<P, Sha384>(tc, deterministic, &sha2::Sha384::digest(msg)),
+        acvp::HashAlg::Sha512 => verify::<P, Sha512>(tc, deterministic, &sha2::Sha512::digest(msg)),
+        acvp::HashAlg::Sha512_224 => {
+            verify::<P, Sha512_224>(tc, deterministic, &sha2::Sha512_224::digest(msg));
+        }
+        acvp::HashAlg::Sha512_256 => {
+            verify::<P, Sha512_256>(tc, deterministic, &sha2::Sha512_256::digest(msg));
+        }
+        acvp::HashAlg::Sha3_224 => {
+            verify::<P, Sha3_224>(tc, deterministic, &sha3::Sha3_224::digest(msg));
+        }
+        acvp::HashAlg::Sha3_256 => {
+            verify::<P, Sha3_256>(tc, deterministic, &sha3::Sha3_256::digest(msg));
+        }
+        acvp::HashAlg::Sha3_384 => {
+            verify::<P, Sha3_384>(tc, deterministic, &sha3::Sha3_384::digest(msg));
+        }
+        acvp::HashAlg::Sha3_512 => {
+            verify::<P, Sha3_512>(tc, deterministic, &sha3::Sha3_512::digest(msg));
+        }
+        acvp::HashAlg::Shake128 => {
+            let mut digest = [0u8; 32];
+            sha3::Shake128::default()
+                .chain(msg)
+                .finalize_xof_into(&mut digest);
+            verify::<P, Shake128>(tc, deterministic, &digest);
+        }
+        acvp::HashAlg::Shake256 => {
+            let mut digest = [0u8; 64];
+            sha3::Shake256::default()
+                .chain(msg)
+                .finalize_xof_into(&mut digest);
+            verify::<P, Shake256>(tc, deterministic, &digest);
+        }
+    }
+}
+
+fn verify<P: MlDsaParams, PH: Prehash>(tc: &acvp::TestCase, deterministic: bool, digest: &[u8]) {
+    // Import the signing key
+    let sk_bytes = EncodedSigningKey::<P>::try_from(tc.sk.as_slice()).unwrap();
+    let sk = SigningKey::<P>::decode(&sk_bytes);
+    let vk = sk.verifying_key();
+
+    // Verify correctness
+    let sig = if deterministic {
+        sk.sign_prehash_deterministic::<PH>(digest, &tc.context)
+            .unwrap()
+    } else {
+        let mut rng = FixedRng(tc.rnd.clone());
+        sk.sign_prehash_randomized::<PH, _>(digest, &tc.context, &mut rng)
+            .unwrap()
+    };
+    assert_eq!(tc.signature.as_slice(), sig.encode().as_slice());
+
+    assert!(vk.verify_prehash_with_context::<PH>(digest, &tc.context, &sig));
+
+    // The signature is bound to the context, the hash function and the message
+    assert!(!vk.verify_prehash_with_context::<PH>(digest, b"other context", &sig));
+    if PH::OID != Sha512::OID {
+        assert!(!vk.verify_prehash_with_context::<Sha512>(&[0; 64], &tc.context, &sig));
+    }
+    let mut other = digest.to_vec();
+    other[0] ^= 1;
+    assert!(!vk.verify_prehash_with_context::<PH>(&other, &tc.context, &sig));
+
+    // HashML-DSA and ML-DSA signatures are not interchangeable
+    assert!(!vk.verify_with_context(&tc.message, &tc.context, &sig));
+
+    // The digest must have the right length
+    assert!(
+        sk.sign_prehash_deterministic::<PH>(&digest[1..], &tc.context)
+            .is_err()
+    );
+    assert!(!vk.verify_prehash_with_context::<PH>(&digest[1..], &tc.context, &sig));
+
+    // The hazmat traits sign deterministically with an empty context
+    let sig: HashSignature<P, PH> = sk.sign_prehash(digest).unwrap();
+    assert_eq!(
+        sig.signature(),
+        &sk.sign_prehash_deterministic::<PH>(digest, &[]).unwrap()
+    );
+    assert!(vk.verify_prehash(digest, &sig).is_ok());
+    assert!(vk.verify_prehash(&other, &sig).is_err());
+}
+
+/// Supplies the `rnd` of a test case
+struct FixedRng(Vec<u8>);
+
+impl rand_core::RngCore for FixedRng {
+    fn next_u32(&mut self) -> u32 {
+        unimplemented!()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        unimplemented!()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        dest.copy_from_slice(&self.0[..dest.len()]);
+        self.0.drain(..dest.len());
+    }
+}
+
+impl rand_core::CryptoRng for FixedRng {}
+
+mod acvp {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Deserialize, Serialize)]
+    pub struct TestVectorFile {
+        #[serde(rename = "testGroups")]
+        pub test_groups: Vec<TestGroup>,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    pub struct TestGroup {
+        #[serde(rename = "tgId")]
+        pub id: usize,
+
+        #[serde(rename = "parameterSet")]
+        pub parameter_set: ParameterSet,
+
+        pub deterministic: bool,
+
+        pub tests: Vec<TestCase>,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    pub enum ParameterSet {
+        #[serde(rename = "ML-DSA-44")]
+        MlDsa44,
+
+        #[serde(rename = "ML-DSA-65")]
+        MlDsa65,
+
+        #[serde(rename = "ML-DSA-87")]
+        MlDsa87,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    pub enum HashAlg {
+        #[serde(rename = "SHA2-224")]
+        Sha224,
+
+        #[serde(rename = "SHA2-256")]
+        Sha256,
+
+        #[serde(rename = "SHA2-384")]
+        Sha384,
+
+        #[serde(rename = "SHA2-512")]
+        Sha512,
+
+        #[serde(rename = "SHA2-512/224")]
+        Sha512_224,
+
+        #[serde(rename = "SHA2-512/256")]
+        Sha512_256,
+
+        #[serde(rename = "SHA3-224")]
+        Sha3_224,
+
+        #[serde(rename = "SHA3-256")]
+        Sha3_256,
+
+        #[serde(rename = "SHA3-384")]
+        Sha3_384,
+
+        #[serde(rename = "SHA3-512")]
+        Sha3_512,
+
+        #[serde(rename = "SHAKE-128")]
+        Shake128,
+
+        #[serde(rename = "SHAKE-256")]
+        Shake256,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    pub struct TestCase {
+        #[serde(rename = "tcId")]
+        pub id: usize,
+
+        #[serde(with = "hex::serde")]
+        pub sk: Vec<u8>,
+
+        #[serde(with = "hex::serde")]
+        pub message: Vec<u8>,
+
+        #[serde(with = "hex::serde")]
+        pub context: Vec<u8>,
+
+        #[serde(rename = "hashAlg")]
+        pub hash_alg: HashAlg,
+
+        #[serde(with = "hex::serde")]
+        pub signature: Vec<u8>,
+
+        #[serde(default, with = "hex::serde")]
+        pub rnd: Vec<u8>,
+    }
+}
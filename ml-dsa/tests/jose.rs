@@ -0,0 +1,225 @@
+//! JWKs and JWS signatures, in the formats of draft-ietf-cose-dilithium
+#![cfg(all(feature = "jose", feature = "rand_core"))]
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use ml_dsa::jose::JoseAlgorithm;
+use ml_dsa::{B32, KeyPair, MlDsa44, MlDsa65, MlDsa87, Signature, VerifyingKey};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+
+const PAYLOAD: &[u8] = "It’s a dangerous business, Frodo, going out your door.".as_bytes();
+
+// The seed 00 01 02 ... 1f
+fn seed() -> B32 {
+    B32::from_fn(|i| u8::try_from(i).unwrap())
+}
+
+fn key_pair<P: JoseAlgorithm>() -> KeyPair<P> {
+    KeyPair::from_seed(&seed())
+}
+
+fn jwk<P: JoseAlgorithm>(kp: &KeyPair<P>) -> Value {
+    json!({
+        "kty": "AKP",
+        "alg": P::ALGORITHM,
+        "pub": Base64UrlUnpadded::encode_string(&kp.verifying_key().encode()),
+        "priv": Base64UrlUnpadded::encode_string(&seed()),
+    })
+}
+
+// An example made by OpenSSL, from `tests/cose-dilithium`.  A missing example fails the test.
+fn example(name: &str) -> String {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("tests/cose-dilithium");
+    p.push(name);
+    std::fs::read_to_string(&p).unwrap_or_else(|err| {
+        panic!(
+            "cannot read {}: {err}; see tests/cose-dilithium/README.md",
+            p.display()
+        )
+    })
+}
+
+fn encode(value: &Value) -> String {
+    Base64UrlUnpadded::encode_string(value.to_string().as_bytes())
+}
+
+#[test]
+fn keys() {
+    fn check<P: JoseAlgorithm>() {
+        let kp = key_pair::<P>();
+        let alg = P::ALGORITHM;
+        let vk = Base64UrlUnpadded::encode_string(&kp.verifying_key().encode());
+        let seed = Base64UrlUnpadded::encode_string(&seed());
+
+        // The JWKs have the members of the draft, in its order
+        assert_eq!(
+            kp.to_jwk(),
+            format!(r#"{{"kty":"AKP","alg":"{alg}","pub":"{vk}","priv":"{seed}"}}"#)
+        );
+        assert_eq!(
+            kp.verifying_key().to_jwk(),
+            format!(r#"{{"kty":"AKP","alg":"{alg}","pub":"{vk}"}}"#)
+        );
+
+        // Both round trip, and the public JWK ignores `priv`
+        let decoded = KeyPair::<P>::from_jwk(&kp.to_jwk()).unwrap();
+        assert_eq!(decoded.verifying_key(), kp.verifying_key());
+        assert!(decoded.signing_key() == kp.signing_key());
+        for jwk in [kp.to_jwk(), kp.verifying_key().to_jwk()] {
+            assert_eq!(
+                &VerifyingKey::<P>::from_jwk(&jwk).unwrap(),
+                kp.verifying_key()
+            );
+        }
+
+        // A key pair needs its seed
+        assert!(KeyPair::<P>::from_jwk(&kp.verifying_key().to_jwk()).is_err());
+    }
+
+    check::<MlDsa44>();
+    check::<MlDsa65>();
+    check::<MlDsa87>();
+}
+
+#[test]
+fn openssl_examples() {
+    fn check<P: JoseAlgorithm>() {
+        let jwk = example(&format!("{}.jwk", P::ALGORITHM));
+        let jws = example(&format!("{}.jws", P::ALGORITHM));
+        let expected: Value = serde_json::from_str(&jwk).unwrap();
+
+        // The key pair decodes, which checks `pub` against the seed, and encodes to the same
+        // members
+        let kp = KeyPair::<P>::from_jwk(&jwk).unwrap();
+        assert_eq!(
+            Base64UrlUnpadded::decode_vec(expected["pub"].as_str().unwrap()).unwrap(),
+            kp.verifying_key().encode().as_slice()
+        );
+        let encoded: Value = serde_json::from_str(&kp.to_jwk()).unwrap();
+        for member in ["kty", "alg", "pub", "priv"] {
+            assert_eq!(encoded[member], expected[member], "{member}");
+        }
+
+        // The example signature verifies with the public key
+        let vk = VerifyingKey::<P>::from_jwk(&kp.verifying_key().to_jwk()).unwrap();
+        let jws = jws.trim();
+        let payload = vk.verify_jws(jws).unwrap();
+        let parts: Vec<&str> = jws.split('.').collect();
+        assert_eq!(Base64UrlUnpadded::encode_string(&payload), parts[1]);
+    }
+
+    check::<MlDsa44>();
+    check::<MlDsa65>();
+    check::<MlDsa87>();
+}
+
+#[test]
+fn malformed_keys() {
+    let kp = key_pair::<MlDsa44>();
+    let jwk = jwk(&kp);
+
+    let changes: [(&str, Value); 7] = [
+        ("kty", "OKP".into()),
+        ("alg", "ML-DSA-65".into()),
+        ("pub", "AAAA".into()),
+        ("pub", "not base64!".into()),
+        ("priv", "AAAA".into()),
+        ("priv", Base64UrlUnpadded::encode_string(&[1; 32]).into()),
+        ("pub", Value::Null),
+    ];
+    for (member, value) in changes {
+        let mut jwk = jwk.clone();
+        jwk[member] = value;
+        assert!(
+            KeyPair::<MlDsa44>::from_jwk(&jwk.to_string()).is_err(),
+            "{member}"
+        );
+    }
+
+    // The parameter set comes from the type, and has to match `alg`
+    assert!(VerifyingKey::<MlDsa65>::from_jwk(&kp.verifying_key().to_jwk()).is_err());
+    assert!(VerifyingKey::<MlDsa44>::from_jwk("not JSON").is_err());
+}
+
+#[test]
+fn signatures() {
+    fn check<P: JoseAlgorithm>(protected: &str) {
+        let kp = key_pair::<P>();
+        let jws = kp.signing_key().sign_jws_deterministic(PAYLOAD).unwrap();
+
+        // The protected header is `{"alg":"ML-DSA-..."}`, and the signature is of the signing
+        // input with an empty context
+        let input = format!("{protected}.{}", Base64UrlUnpadded::encode_string(PAYLOAD));
+        let signature = kp
+            .signing_key()
+            .sign_deterministic(input.as_bytes(), &[])
+            .unwrap();
+        let expected = format!(
+            "{input}.{}",
+            Base64UrlUnpadded::encode_string(&signature.encode())
+        );
+        assert_eq!(jws, expected);
+        assert_eq!(kp.verifying_key().verify_jws(&jws).unwrap(), PAYLOAD);
+
+        let mut rng = rand::rng();
+        let jws = kp
+            .signing_key()
+            .sign_jws_randomized(PAYLOAD, &mut rng)
+            .unwrap();
+        assert!(jws.starts_with(protected));
+        assert_eq!(kp.verifying_key().verify_jws(&jws).unwrap(), PAYLOAD);
+    }
+
+    check::<MlDsa44>("eyJhbGciOiJNTC1EU0EtNDQifQ");
+    check::<MlDsa65>("eyJhbGciOiJNTC1EU0EtNjUifQ");
+    check::<MlDsa87>("eyJhbGciOiJNTC1EU0EtODcifQ");
+}
+
+#[test]
+fn invalid_signatures() {
+    let kp = key_pair::<MlDsa44>();
+    let vk = kp.verifying_key();
+    let jws = kp.signing_key().sign_jws_deterministic(PAYLOAD).unwrap();
+    let parts: Vec<&str> = jws.split('.').collect();
+
+    // A JWS signed over another header, which is otherwise well formed
+    let sign = |header: &Value| {
+        let input = format!("{}.{}", encode(header), parts[1]);
+        let sig: Signature<MlDsa44> = kp
+            .signing_key()
+            .sign_deterministic(input.as_bytes(), &[])
+            .unwrap();
+        format!(
+            "{input}.{}",
+            Base64UrlUnpadded::encode_string(&sig.encode())
+        )
+    };
+    assert!(
+        vk.verify_jws(&sign(&json!({"alg": "ML-DSA-44", "kid": "1"})))
+            .is_ok()
+    );
+    for header in [
+        json!({"alg": "ML-DSA-65"}),
+        json!({"alg": "none"}),
+        json!({}),
+        json!({"alg": "ML-DSA-44", "crit": ["exp"], "exp": 0}),
+    ] {
+        assert!(vk.verify_jws(&sign(&header)).is_err(), "{header}");
+    }
+
+    let other = Base64UrlUnpadded::encode_string(b"another payload");
+    for jws in [
+        format!("{}.{other}.{}", parts[0], parts[2]),
+        format!("{}.{}", parts[0], parts[1]),
+        format!("{jws}.{}", parts[2]),
+        format!("{}.{}.{}", parts[0], parts[1], &parts[2][4..]),
+        format!("{}=.{}.{}", parts[0], parts[1], parts[2]),
+    ] {
+        assert!(vk.verify_jws(&jws).is_err());
+    }
+
+    // Another key
+    let other = KeyPair::<MlDsa44>::from_seed(&[1; 32].into());
+    assert!(other.verifying_key().verify_jws(&jws).is_err());
+}
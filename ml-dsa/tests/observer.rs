@@ -0,0 +1,122 @@
+// Phases are only timed with `std`
+#![cfg(feature = "std")]
+
+use hybrid_array::{Array, typenum::Unsigned};
+use ml_dsa::*;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Default)]
+struct Report {
+    backends: Vec<(&'static str, Role)>,
+    absorbed: [usize; 2],
+    squeezed: [usize; 2],
+    iterations: Vec<usize>,
+    phases: Vec<Phase>,
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Report>>);
+
+impl Recorder {
+    fn take(&self) -> Report {
+        core::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+fn index(role: Role) -> usize {
+    match role {
+        Role::G => 0,
+        Role::H => 1,
+    }
+}
+
+impl Observer for Recorder {
+    fn start(&self, backend: &'static str, role: Role) {
+        self.0.lock().unwrap().backends.push((backend, role));
+    }
+
+    fn absorb(&self, role: Role, bytes: usize) {
+        self.0.lock().unwrap().absorbed[index(role)] += bytes;
+    }
+
+    fn squeeze(&self, role: Role, bytes: usize) {
+        self.0.lock().unwrap().squeezed[index(role)] += bytes;
+    }
+
+    fn rejection_sampling(&self, iterations: usize) {
+        self.0.lock().unwrap().iterations.push(iterations);
+    }
+
+    fn phase(&self, phase: Phase, _elapsed: Duration) {
+        self.0.lock().unwrap().phases.push(phase);
+    }
+}
+
+fn observe<P: MlDsaParams, X: Xof>() {
+    let recorder = Recorder::default();
+    let xof = Observed::new(X::default(), recorder.clone());
+    let seed = Array([1u8; 32]);
+    let msg = b"observed message";
+
+    // Key generation
+    let kp = KeyPair::<P, Observed<X, Recorder>>::from_seed_with_xof(&seed, xof);
+    let report = recorder.take();
+    assert!(report.backends.iter().all(|(name, _)| *name == X::NAME));
+    assert_eq!(
+        report
+            .backends
+            .iter()
+            .filter(|(_, role)| *role == Role::G)
+            .count(),
+        P::K::USIZE * P::L::USIZE
+    );
+    assert!(report.absorbed[0] > 0 && report.squeezed[0] > 0);
+    assert!(report.absorbed[1] > 0 && report.squeezed[1] > 0);
+    assert_eq!(report.phases, [Phase::ExpandA, Phase::KeyGen]);
+    assert!(report.iterations.is_empty());
+
+    // Signing
+    let sig = kp.signing_key().sign_deterministic(msg, &[]).unwrap();
+    let report = recorder.take();
+    assert_eq!(report.phases, [Phase::Sign]);
+    assert_eq!(report.iterations.len(), 1);
+    assert!(report.iterations[0] >= 1);
+    assert!(report.backends.iter().all(|(_, role)| *role == Role::H));
+
+    // Verification
+    assert!(kp.verifying_key().verify_with_context(msg, &[], &sig));
+    let report = recorder.take();
+    assert_eq!(report.phases, [Phase::Verify]);
+    assert!(report.iterations.is_empty());
+
+    // Presigning, with one phase for each candidate and a sign phase for the message
+    let mut rng = rand::rng();
+    let sk = kp.signing_key();
+    let mut pool: Vec<_> = (0..32).map(|_| sk.presign(&mut rng).unwrap()).collect();
+    let report = recorder.take();
+    assert_eq!(report.phases, [Phase::Presign; 32]);
+    let presigned = sk
+        .sign_presigned(msg, &[], &mut core::iter::from_fn(|| pool.pop()))
+        .unwrap();
+    assert!(kp.verifying_key().verify_with_context(msg, &[], &presigned));
+    let report = recorder.take();
+    assert_eq!(report.phases, [Phase::Sign, Phase::Verify]);
+    assert_eq!(report.iterations, [32 - pool.len()]);
+
+    // Observation does not change the output
+    let plain = KeyPair::<P, X>::from_seed(&seed);
+    assert_eq!(plain.verifying_key().encode(), kp.verifying_key().encode());
+    assert_eq!(
+        plain.signing_key().sign_deterministic(msg, &[]).unwrap(),
+        sig
+    );
+}
+
+#[test]
+fn observer_reports() {
+    observe::<MlDsa44, ShakeXof>();
+    observe::<MlDsa65, AesXof>();
+    observe::<MlDsa87, Blake3Xof>();
+    observe::<MlDsa44, Blake3OptimizedXof>();
+}
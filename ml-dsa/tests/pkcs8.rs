@@ -1,51 +1,212 @@
 #![cfg(all(feature = "pkcs8", feature = "alloc"))]
 
 use core::ops::Deref;
-use ml_dsa::{KeyPair, MlDsa44, MlDsa65, MlDsa87, MlDsaParams, SigningKey, VerifyingKey};
+use ml_dsa::{
+    AesXof, Blake3Xof, CompactSigningKey, KeyPair, MlDsa44, MlDsa65, MlDsa87, MlDsaParams,
+    PrivateKeyFormat, ShakeXof, SigningKey, VerifyingKey,
+};
 use pkcs8::{
-    DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey,
-    der::{AnyRef, pem::LineEnding},
-    spki::AssociatedAlgorithmIdentifier,
+    DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey, PrivateKeyInfoRef,
+    der::{
+        AnyRef,
+        pem::{LineEnding, PemLabel},
+    },
+    spki::{self, AssociatedAlgorithmIdentifier, ObjectIdentifier, SignatureAlgorithmIdentifier},
 };
 
+struct Examples {
+    seed: &'static str,
+    expanded: &'static str,
+    both: &'static str,
+    legacy: &'static str,
+    public: &'static str,
+}
+
+macro_rules! examples {
+    ($name:literal) => {
+        Examples {
+            seed: include_str!(concat!("examples/", $name, "-seed.priv")),
+            expanded: include_str!(concat!("examples/", $name, "-expanded.priv")),
+            both: include_str!(concat!("examples/", $name, "-both.priv")),
+            legacy: include_str!(concat!("examples/", $name, ".priv")),
+            public: include_str!(concat!("examples/", $name, ".pub")),
+        }
+    };
+}
+
+fn to_pem<P>(kp: &KeyPair<P>, format: PrivateKeyFormat) -> String
+where
+    P: MlDsaParams,
+    P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+{
+    kp.to_pkcs8_der_with_format(format)
+        .expect("serialize private key")
+        .to_pem(PrivateKeyInfoRef::PEM_LABEL, LineEnding::LF)
+        .expect("serialize private key")
+        .deref()
+        .clone()
+}
+
 #[test]
 fn private_key_serialization() {
-    fn test_roundtrip<P>(private_bytes: &str, public_bytes: &str)
+    fn test_roundtrip<P>(examples: &Examples)
     where
         P: MlDsaParams,
         P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
     {
-        let sk = SigningKey::<P>::from_pkcs8_pem(private_bytes).expect("parse private key");
-        let kp = KeyPair::<P>::from_pkcs8_pem(private_bytes).expect("parse private key");
-        assert!(sk == *kp.signing_key());
+        let kp = KeyPair::<P>::from_pkcs8_pem(examples.seed).expect("parse private key");
         assert_eq!(
             kp.to_pkcs8_pem(LineEnding::LF)
                 .expect("serialize private seed")
                 .deref(),
-            private_bytes
+            examples.seed
+        );
+        assert_eq!(to_pem(&kp, PrivateKeyFormat::Seed), examples.seed);
+        assert_eq!(
+            to_pem(&kp, PrivateKeyFormat::ExpandedKey),
+            examples.expanded
         );
+        assert_eq!(to_pem(&kp, PrivateKeyFormat::Both), examples.both);
 
-        let pk = VerifyingKey::<P>::from_public_key_pem(public_bytes).expect("parse public key");
+        // Every form decodes to the same signing key
+        for pem in [examples.seed, examples.expanded, examples.both] {
+            let sk = SigningKey::<P>::from_pkcs8_pem(pem).expect("parse private key");
+            assert!(sk == *kp.signing_key());
+        }
+
+        // A key pair needs the seed
+        let both = KeyPair::<P>::from_pkcs8_pem(examples.both).expect("parse private key");
+        assert_eq!(
+            both.to_pkcs8_pem(LineEnding::LF).unwrap().deref(),
+            examples.seed
+        );
+        assert!(KeyPair::<P>::from_pkcs8_pem(examples.expanded).is_err());
+
+        // A signing key on its own is encoded in expanded form
+        assert_eq!(
+            kp.signing_key()
+                .to_pkcs8_pem(LineEnding::LF)
+                .expect("serialize expanded key")
+                .deref(),
+            examples.expanded
+        );
+
+        // A compact key needs the seed too, and is encoded in seed form
+        for pem in [examples.seed, examples.both] {
+            let compact = CompactSigningKey::<P>::from_pkcs8_pem(pem).expect("parse private key");
+            assert!(compact.expand() == *kp.signing_key());
+            assert_eq!(
+                compact.to_pkcs8_pem(LineEnding::LF).unwrap().deref(),
+                examples.seed
+            );
+        }
+        assert!(CompactSigningKey::<P>::from_pkcs8_pem(examples.expanded).is_err());
+
+        let pk = VerifyingKey::<P>::from_public_key_pem(examples.public).expect("parse public key");
         assert_eq!(
             pk.to_public_key_pem(LineEnding::LF)
                 .expect("serialize public key")
                 .deref(),
-            public_bytes
+            examples.public
         );
 
         assert_eq!(kp.verifying_key(), &pk);
     }
 
-    test_roundtrip::<MlDsa44>(
-        include_str!("examples/ML-DSA-44.priv"),
-        include_str!("examples/ML-DSA-44.pub"),
+    test_roundtrip::<MlDsa44>(&examples!("ML-DSA-44"));
+    test_roundtrip::<MlDsa65>(&examples!("ML-DSA-65"));
+    test_roundtrip::<MlDsa87>(&examples!("ML-DSA-87"));
+}
+
+#[test]
+fn legacy_private_key() {
+    fn test_legacy<P>(examples: &Examples)
+    where
+        P: MlDsaParams,
+        P: AssociatedAlgorithmIdentifier<Params = AnyRef<'static>>,
+    {
+        // Keys with a bare seed are still accepted, and re-encoded in the `seed` form
+        let kp = KeyPair::<P>::from_pkcs8_pem(examples.legacy).expect("parse private key");
+        let sk = SigningKey::<P>::from_pkcs8_pem(examples.legacy).expect("parse private key");
+        assert!(sk == *kp.signing_key());
+        assert_eq!(
+            kp.to_pkcs8_pem(LineEnding::LF).unwrap().deref(),
+            examples.seed
+        );
+    }
+
+    test_legacy::<MlDsa44>(&examples!("ML-DSA-44"));
+    test_legacy::<MlDsa65>(&examples!("ML-DSA-65"));
+    test_legacy::<MlDsa87>(&examples!("ML-DSA-87"));
+}
+
+#[test]
+fn inconsistent_private_key() {
+    let examples = examples!("ML-DSA-65");
+    let (_, doc) = pkcs8::SecretDocument::from_pem(examples.both).unwrap();
+
+    // Change the last byte of the expanded key, which is part of t0
+    let mut der = doc.as_bytes().to_vec();
+    *der.last_mut().unwrap() ^= 1;
+
+    assert!(KeyPair::<MlDsa65>::from_pkcs8_der(&der).is_err());
+    assert!(SigningKey::<MlDsa65>::from_pkcs8_der(&der).is_err());
+
+    // The same change is accepted in the expanded form, which has nothing to check against
+    let (_, doc) = pkcs8::SecretDocument::from_pem(examples.expanded).unwrap();
+    let mut der = doc.as_bytes().to_vec();
+    *der.last_mut().unwrap() ^= 1;
+    assert!(SigningKey::<MlDsa65>::from_pkcs8_der(&der).is_ok());
+
+    // Parameter sets are not interchangeable
+    assert!(SigningKey::<MlDsa44>::from_pkcs8_pem(examples.expanded).is_err());
+}
+
+#[test]
+fn backend_identifiers() {
+    let seed = Default::default();
+    let blake3 = KeyPair::<MlDsa65, Blake3Xof>::from_seed(&seed);
+    let oid = ObjectIdentifier::new_unwrap(
+        "1.2.840.113556.1.8000.2554.63952.18939.8846.16756.38443.782279.304911.1.1.18",
+    );
+    assert_eq!(
+        SigningKey::<MlDsa65, Blake3Xof>::SIGNATURE_ALGORITHM_IDENTIFIER.oid,
+        oid
+    );
+
+    // Keys are labeled with the arc of their backend
+    let private = blake3.to_pkcs8_der().unwrap();
+    let public = blake3.verifying_key().to_public_key_der().unwrap();
+    let info = PrivateKeyInfoRef::try_from(private.as_bytes()).unwrap();
+    assert_eq!(info.algorithm.oid, oid);
+
+    // They only decode with that backend
+    assert!(KeyPair::<MlDsa65, Blake3Xof>::from_pkcs8_der(private.as_bytes()).is_ok());
+    assert!(
+        VerifyingKey::<MlDsa65, Blake3Xof>::from_public_key_der(public.as_bytes()).unwrap()
+            == *blake3.verifying_key()
+    );
+    #[cfg(feature = "std")]
+    assert!(
+        KeyPair::<MlDsa65, ml_dsa::Blake3OptimizedXof>::from_pkcs8_der(private.as_bytes()).is_ok()
     );
-    test_roundtrip::<MlDsa65>(
-        include_str!("examples/ML-DSA-65.priv"),
-        include_str!("examples/ML-DSA-65.pub"),
+
+    let unknown = pkcs8::Error::PublicKey(spki::Error::OidUnknown { oid });
+    assert_eq!(
+        KeyPair::<MlDsa65>::from_pkcs8_der(private.as_bytes()).unwrap_err(),
+        unknown
+    );
+    assert_eq!(
+        SigningKey::<MlDsa65, AesXof>::from_pkcs8_der(private.as_bytes()).unwrap_err(),
+        unknown
     );
-    test_roundtrip::<MlDsa87>(
-        include_str!("examples/ML-DSA-87.priv"),
-        include_str!("examples/ML-DSA-87.pub"),
+    assert_eq!(
+        VerifyingKey::<MlDsa65, ShakeXof>::from_public_key_der(public.as_bytes()).unwrap_err(),
+        spki::Error::OidUnknown { oid }
     );
+
+    // Standard keys are not accepted by the other backends either
+    let standard = KeyPair::<MlDsa65>::from_seed(&seed).to_pkcs8_der().unwrap();
+    assert!(KeyPair::<MlDsa65, Blake3Xof>::from_pkcs8_der(standard.as_bytes()).is_err());
+    assert!(CompactSigningKey::<MlDsa65, AesXof>::from_pkcs8_der(standard.as_bytes()).is_err());
 }
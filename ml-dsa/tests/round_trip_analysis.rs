@@ -3,9 +3,8 @@ use hybrid_array::{Array, typenum::U4096};
 use ml_dsa::*;
 use std::time::Instant;
 
-#[test]
-fn analyze_key_gen_internal() {
-    println!("\nAnalyzing ML-DSA-44 Round Trip:");
+fn analyze_round_trip<X: Xof>(name: &str) {
+    println!("\nAnalyzing ML-DSA-44 Round Trip ({name}):");
     println!("----------------------------------");
 
     let round_trip_start = Instant::now();
@@ -15,7 +14,7 @@ fn analyze_key_gen_internal() {
 
     // key generation
     let keygen_start = Instant::now();
-    let kp = MlDsa44::key_gen_internal(&seed);
+    let kp = KeyPair::<MlDsa44, X>::from_seed(&seed);
     let sk = kp.signing_key();
     let vk = kp.verifying_key();
     let keygen_duration = keygen_start.elapsed();
@@ -42,3 +41,12 @@ fn analyze_key_gen_internal() {
     println!("Sign took: {:?}", sign_duration);
     println!("Verify took: {:?}", verify_duration);
 }
+
+#[test]
+fn analyze_key_gen_internal() {
+    analyze_round_trip::<ShakeXof>("SHAKE");
+    analyze_round_trip::<AesXof>("AES");
+    analyze_round_trip::<Blake3Xof>("BLAKE3 niave");
+    #[cfg(feature = "std")]
+    analyze_round_trip::<Blake3OptimizedXof>("BLAKE3 optimized");
+}
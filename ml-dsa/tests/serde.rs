@@ -0,0 +1,65 @@
+#![cfg(all(feature = "serde", feature = "alloc"))]
+
+use ml_dsa::{Blake3Xof, EncodedSigningKey, KeyGen, KeyPair, MlDsa65, Signature, VerifyingKey};
+
+#[test]
+fn human_readable() {
+    let kp = MlDsa65::key_gen_internal(&Default::default());
+    let vk = kp.verifying_key();
+    let sig = kp.signing_key().sign_deterministic(b"serde", &[]).unwrap();
+
+    // Human-readable formats use lower-case hex, the same as `Display`
+    let json = serde_json::to_string(&sig).unwrap();
+    assert_eq!(json, format!("\"{sig}\""));
+    assert_eq!(json, format!("\"{}\"", hex::encode(sig.encode())));
+    assert_eq!(
+        serde_json::from_str::<Signature<MlDsa65>>(&json).unwrap(),
+        sig
+    );
+
+    let json = serde_json::to_string(vk).unwrap();
+    assert_eq!(json, format!("\"{vk:x}\""));
+    assert!(serde_json::from_str::<VerifyingKey<MlDsa65>>(&json).unwrap() == *vk);
+
+    // Upper case is accepted too, but not the wrong length
+    let upper = format!("\"{vk:X}\"");
+    assert!(serde_json::from_str::<VerifyingKey<MlDsa65>>(&upper).unwrap() == *vk);
+    assert!(serde_json::from_str::<VerifyingKey<MlDsa65>>("\"00\"").is_err());
+
+    // `FromStr` is the inverse of `Display`
+    assert_eq!(sig.to_string().parse::<Signature<MlDsa65>>().unwrap(), sig);
+    assert!(vk.to_string().parse::<VerifyingKey<MlDsa65>>().unwrap() == *vk);
+}
+
+#[test]
+fn binary() {
+    let kp = KeyPair::<MlDsa65, Blake3Xof>::from_seed(&Default::default());
+    let vk = kp.verifying_key();
+    let sig = kp.signing_key().sign_deterministic(b"serde", &[]).unwrap();
+
+    // Binary formats carry the encoding as is, after a length prefix
+    let bytes = bincode::serialize(&sig).unwrap();
+    assert_eq!(&bytes[8..], sig.encode().as_slice());
+    assert_eq!(
+        bincode::deserialize::<Signature<MlDsa65>>(&bytes).unwrap(),
+        sig
+    );
+
+    let bytes = bincode::serialize(vk).unwrap();
+    assert_eq!(&bytes[8..], vk.encode().as_slice());
+    assert!(bincode::deserialize::<VerifyingKey<MlDsa65, Blake3Xof>>(&bytes).unwrap() == *vk);
+
+    // An encoded signing key is an array of bytes
+    let sk = kp.signing_key().encode();
+    let bytes = bincode::serialize(&sk).unwrap();
+    assert_eq!(bytes, sk.as_slice());
+    assert_eq!(
+        bincode::deserialize::<EncodedSigningKey<MlDsa65>>(&bytes).unwrap(),
+        sk
+    );
+
+    // A signature that does not decode is rejected
+    let mut bytes = bincode::serialize(&sig).unwrap();
+    *bytes.last_mut().unwrap() = 0xff;
+    assert!(bincode::deserialize::<Signature<MlDsa65>>(&bytes).is_err());
+}
@@ -0,0 +1,38 @@
+//! The stack usage of verification with a `CompactVerifyingKey`
+
+use hybrid_array::Array;
+use ml_dsa::{CompactVerifyingKey, KeyPair, MlDsa44, MlDsa65, MlDsa87, MlDsaParams};
+use std::thread;
+
+// The smallest stack that threads get on common platforms, which is also enough for compact
+// verification.  Verifying with a `VerifyingKey` decoded on the same stack overflows it, and does
+// so even with 256 KiB for ML-DSA-87.
+const STACK: usize = 16 * 1024;
+
+fn verify_on_small_stack<P: MlDsaParams>() {
+    let kp = KeyPair::<P>::from_seed(&Array([1u8; 32]));
+    let sig = kp
+        .signing_key()
+        .sign_deterministic(b"stack", &[])
+        .unwrap()
+        .encode();
+    let vk = CompactVerifyingKey::<P>::decode(&kp.verifying_key().encode());
+
+    // The key and the signature stay on the stack of the test, and an overflow aborts the test
+    let verified = thread::scope(|s| {
+        thread::Builder::new()
+            .stack_size(STACK)
+            .spawn_scoped(s, || vk.verify_with_context(b"stack", &[], &sig))
+            .unwrap()
+            .join()
+            .unwrap()
+    });
+    assert!(verified);
+}
+
+#[test]
+fn compact_verification_fits_a_small_stack() {
+    verify_on_small_stack::<MlDsa44>();
+    verify_on_small_stack::<MlDsa65>();
+    verify_on_small_stack::<MlDsa87>();
+}
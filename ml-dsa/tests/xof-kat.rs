@@ -0,0 +1,125 @@
+use ml_dsa::*;
+
+use hybrid_array::Array;
+use std::{fs::read_to_string, path::PathBuf};
+
+// Known answers for the non-standard hash backends.  These are frozen: if any of them changes,
+// signatures produced by earlier releases will no longer verify.
+#[test]
+fn xof_kat() {
+    // Load the JSON test file
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("tests/xof-kat.json");
+    let tv_json = read_to_string(p.as_path()).unwrap();
+
+    // Parse the test vectors
+    let tv: kat::TestVectorFile = serde_json::from_str(&tv_json).unwrap();
+
+    // Verify the test vectors
+    for tc in tv.tests {
+        match tc.backend {
+            kat::Backend::Blake3 => {
+                verify_parameter_set::<Blake3Xof>(&tc);
+                #[cfg(feature = "std")]
+                verify_parameter_set::<Blake3OptimizedXof>(&tc);
+            }
+            kat::Backend::Aes => verify_parameter_set::<AesXof>(&tc),
+        }
+    }
+}
+
+fn verify_parameter_set<X: Xof>(tc: &kat::TestCase) {
+    match tc.parameter_set {
+        kat::ParameterSet::MlDsa44 => verify::<MlDsa44, X>(tc),
+        kat::ParameterSet::MlDsa65 => verify::<MlDsa65, X>(tc),
+        kat::ParameterSet::MlDsa87 => verify::<MlDsa87, X>(tc),
+    }
+}
+
+fn verify<P: MlDsaParams, X: Xof>(tc: &kat::TestCase) {
+    // Import test data into the relevant array structures
+    let seed = Array::try_from(tc.seed.as_slice()).unwrap();
+    let vk_bytes = EncodedVerifyingKey::<P>::try_from(tc.pk.as_slice()).unwrap();
+    let sk_bytes = EncodedSigningKey::<P>::try_from(tc.sk.as_slice()).unwrap();
+    let sig_bytes = EncodedSignature::<P>::try_from(tc.signature.as_slice()).unwrap();
+
+    // Key generation
+    let kp = KeyPair::<P, X>::from_seed(&seed);
+    assert_eq!(kp.signing_key().encode(), sk_bytes);
+    assert_eq!(kp.verifying_key().encode(), vk_bytes);
+
+    // Deterministic signing
+    let sig = kp
+        .signing_key()
+        .sign_deterministic(&tc.message, &tc.context)
+        .unwrap();
+    assert_eq!(sig.encode(), sig_bytes);
+
+    // Verification of the stored signature under the stored key
+    let vk = VerifyingKey::<P, X>::decode(&vk_bytes);
+    let sig = Signature::<P>::decode(&sig_bytes).unwrap();
+    assert!(vk.verify_with_context(&tc.message, &tc.context, &sig));
+
+    // ... and not under the standard hash functions
+    let vk = VerifyingKey::<P>::decode(&vk_bytes);
+    assert!(!vk.verify_with_context(&tc.message, &tc.context, &sig));
+}
+
+mod kat {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Deserialize, Serialize)]
+    pub struct TestVectorFile {
+        pub tests: Vec<TestCase>,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    pub enum Backend {
+        #[serde(rename = "BLAKE3")]
+        Blake3,
+
+        #[serde(rename = "AES")]
+        Aes,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    pub enum ParameterSet {
+        #[serde(rename = "ML-DSA-44")]
+        MlDsa44,
+
+        #[serde(rename = "ML-DSA-65")]
+        MlDsa65,
+
+        #[serde(rename = "ML-DSA-87")]
+        MlDsa87,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    pub struct TestCase {
+        #[serde(rename = "tcId")]
+        pub id: usize,
+
+        pub backend: Backend,
+
+        #[serde(rename = "parameterSet")]
+        pub parameter_set: ParameterSet,
+
+        #[serde(with = "hex::serde")]
+        pub seed: Vec<u8>,
+
+        #[serde(with = "hex::serde")]
+        pub pk: Vec<u8>,
+
+        #[serde(with = "hex::serde")]
+        pub sk: Vec<u8>,
+
+        #[serde(with = "hex::serde")]
+        pub message: Vec<u8>,
+
+        #[serde(with = "hex::serde")]
+        pub context: Vec<u8>,
+
+        #[serde(with = "hex::serde")]
+        pub signature: Vec<u8>,
+    }
+}
@@ -0,0 +1,121 @@
+//! Check that key generation and signing wipe the heap buffers that held secrets
+#![cfg(all(feature = "zeroize", feature = "std"))]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use ml_dsa::typenum::{U32, U64};
+use ml_dsa::*;
+
+// The secrets to look for, by their first 16 bytes
+static NEEDLES: Mutex<Vec<(&str, [u8; 16])>> = Mutex::new(Vec::new());
+
+// Whether freed blocks are inspected, and a bit for each needle that was found in one
+static ARMED: AtomicBool = AtomicBool::new(false);
+static FOUND: AtomicU64 = AtomicU64::new(0);
+
+// An allocator that searches every block freed while armed for the needles
+struct Inspecting;
+
+unsafe impl GlobalAlloc for Inspecting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if ARMED.load(Ordering::SeqCst) {
+            // The needles are only locked to be changed, which happens while disarmed
+            if let Ok(needles) = NEEDLES.try_lock() {
+                // SAFETY: the block is still allocated, and `layout` is its size
+                let block = unsafe { std::slice::from_raw_parts(ptr, layout.size()) };
+                for (i, (_, needle)) in needles.iter().enumerate() {
+                    if block.windows(needle.len()).any(|w| w == needle) {
+                        FOUND.fetch_or(1 << i, Ordering::SeqCst);
+                    }
+                }
+            }
+        }
+
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Inspecting = Inspecting;
+
+fn set_needles(needles: &[(&'static str, &[u8])]) {
+    let mut guard = NEEDLES.lock().unwrap();
+    guard.clear();
+    for (name, secret) in needles {
+        guard.push((name, secret[..16].try_into().unwrap()));
+    }
+}
+
+// Run `f` with the allocator armed, and return the names of the needles found in freed memory
+fn inspect(f: impl FnOnce()) -> Vec<&'static str> {
+    FOUND.store(0, Ordering::SeqCst);
+    ARMED.store(true, Ordering::SeqCst);
+    f();
+    ARMED.store(false, Ordering::SeqCst);
+
+    let found = FOUND.load(Ordering::SeqCst);
+    let needles = NEEDLES.lock().unwrap();
+    needles
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| found & (1 << i) != 0)
+        .map(|(_, (name, _))| *name)
+        .collect()
+}
+
+#[allow(non_snake_case)]
+fn check<X: Xof>() {
+    let xi = B32::from_fn(|i| u8::try_from(i * 7 + 3).unwrap());
+    let msg = b"Hello world";
+    let ctx = b"zeroize";
+
+    // Compute the secrets from the outside, through the public XOF API
+    let xof = X::default();
+    let mut h = xof.h().absorb(&xi).absorb(&[4]).absorb(&[4]);
+    let _rho: B32 = h.squeeze_new();
+    let rhop = h.squeeze_new::<U64>();
+    let K = h.squeeze_new::<U32>();
+    drop(h);
+
+    let kp = KeyPair::<MlDsa44, X>::from_seed_with_xof(&xi, xof.clone());
+    let mut mu = MuBuilder::new(kp.verifying_key(), ctx).unwrap();
+    mu.update(msg);
+    let mu = mu.finalize();
+    let rhopp = xof
+        .h()
+        .absorb(&K)
+        .absorb(&[0; 32])
+        .absorb(&mu)
+        .squeeze_new::<U64>();
+    drop(kp);
+
+    set_needles(&[("xi", &xi), ("rhop", &rhop), ("K", &K), ("rhopp", &rhopp)]);
+    let found = inspect(|| {
+        let kp = KeyPair::<MlDsa44, X>::from_seed_with_xof(&xi, xof);
+        let sig = kp.signing_key().sign_deterministic(msg, ctx).unwrap();
+        assert!(kp.verifying_key().verify_with_context(msg, ctx, &sig));
+    });
+    assert!(
+        found.is_empty(),
+        "{}: {found:?} left in freed memory",
+        X::NAME
+    );
+}
+
+#[test]
+fn freed_buffers_are_wiped() {
+    // The allocator sees a secret that is not wiped
+    let secret = [0x5a; 32];
+    set_needles(&[("secret", &secret)]);
+    let found = inspect(|| drop(std::hint::black_box(secret.to_vec())));
+    assert_eq!(found, ["secret"]);
+
+    check::<Blake3OptimizedXof>();
+    check::<AesXof>();
+}
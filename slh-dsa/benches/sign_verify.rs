@@ -55,10 +55,23 @@ criterion_group!(name = sha2_verify_benches;
               verify_benchmark<Sha2_128f>, verify_benchmark<Sha2_192f>, verify_benchmark<Sha2_256f>,
 );
 
+// BLAKE3 benchmarks
+criterion_group!(name = blake3_sign_benches;
+    config = Criterion::default().sample_size(10);
+    targets = sign_benchmark<Blake3_128s>, sign_benchmark<Blake3_192s>, sign_benchmark<Blake3_256s>,
+              sign_benchmark<Blake3_128f>, sign_benchmark<Blake3_192f>, sign_benchmark<Blake3_256f>,
+);
+
+criterion_group!(name = blake3_verify_benches;
+    config = Criterion::default().sample_size(10);
+    targets = verify_benchmark<Blake3_128s>, verify_benchmark<Blake3_192s>, verify_benchmark<Blake3_256s>,
+              verify_benchmark<Blake3_128f>, verify_benchmark<Blake3_192f>, verify_benchmark<Blake3_256f>,
+);
+
 // Quick test benchmark
 criterion_group!(name = quick_bench;
-    config = Criterion::default().sample_size(3).measurement_time(std::time::Duration::from_secs(1));
+    config = Criterion::default().sample_size(10).measurement_time(Duration::from_secs(1));
     targets = sign_benchmark<Blake3_128s>,
 );
 
-criterion_main!(quick_bench);
+criterion_main!(quick_bench, blake3_sign_benches, blake3_verify_benches);
@@ -12,21 +12,55 @@ use crate::{PkSeed, SkPrf, SkSeed};
 use crate::{
     signature_encoding::SignatureLen, signing_key::SigningKeyLen, verifying_key::VerifyingKeyLen,
 };
-use const_oid::db::fips205;
-use hybrid_array::sizes::{U7856, U17088};
+use hybrid_array::sizes::{U7856, U16224, U17088, U29792, U35664, U49856};
 use hybrid_array::{Array, ArraySize};
-use typenum::{Diff, Sum, U, U16, U30, U32, U34, U64};
+use typenum::{Diff, Sum, U, U16, U24, U30, U32, U34, U39, U42, U47, U49, U64};
 
-/// Implementation of the component hash functions using BLAKE3 at Security Category 1
+// The object identifier of a BLAKE3 parameter set, ending in `last_arc`.  The prefix is
+// `ml_dsa::PRIVATE_ARC` with its last arc, `1` for ML-DSA, replaced by `2`; `tests/pkcs8.rs`
+// checks that they agree.
+macro_rules! blake3_oid {
+    ($last_arc:literal) => {
+        pkcs8::ObjectIdentifier::new_unwrap(concat!(
+            "1.2.840.113556.1.8000.2554.63952.18939.8846.16756.38443.782279.304911.2.1.",
+            $last_arc
+        ))
+    };
+}
+
+/// Implementation of the component hash functions using BLAKE3
+///
+/// Follows a similar pattern to SHA2 implementation in section 10.2 of FIPS-205, with the
+/// same construction at every security category:
+/// - `F`, `H`, `T` and `PRF` are BLAKE3 keyed with `PK.seed`, zero-padded to the 32-byte BLAKE3
+///   key, over `toByte(0, 64 - n) || ADRSc || M`
+/// - `PRF_msg` is BLAKE3 keyed with `SK.prf`, zero-padded to 32 bytes, over `opt_rand || M`
+/// - `H_msg` is the first `m` bytes of the BLAKE3 XOF over `R || PK.seed || PK.root || M`
+///
+/// The `n`-byte outputs are the first `n` bytes of the 32-byte BLAKE3 hash: 16 bytes for the
+/// 128-bit sets, 24 bytes for the 192-bit sets and the full hash for the 256-bit sets.
+///
+/// Only the 128-bit sets claim a NIST security category, category 1.  The 192-bit and 256-bit
+/// sets are not claimed to meet categories 3 and 5: every message block passes through BLAKE3's
+/// 256-bit chaining value, and the multi-target attacks that led FIPS 205 to use SHA-512 for `H`,
+/// `T` and `PRF_msg` at those categories apply to it as they do to SHA-256.  They offer larger
+/// hash outputs and signatures, not a stronger security claim.
 ///
-/// Follows a similar pattern to SHA2 implementation in section 10.2 of FIPS-205
+/// Keys are identified by arc `2.1` under the private arc of `ml-dsa`
+/// (`1.2.840.113556.1.8000.2554.63952.18939.8846.16756.38443.782279.304911`), followed by the last
+/// arc FIPS 205 gives the SHA2 set with the same parameters.  These identifiers are not standard,
+/// and keys for one parameter set are rejected by every other.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Blake3L1<N, M> {
+pub struct Blake3<N, M> {
     _n: core::marker::PhantomData<N>,
     _m: core::marker::PhantomData<M>,
 }
 
-impl<N: ArraySize, M: ArraySize> HashSuite for Blake3L1<N, M>
+/// Former name of [`Blake3`], from when it only had the 128-bit parameter sets
+#[deprecated(note = "use `Blake3`, which also has 192-bit and 256-bit parameter sets")]
+pub type Blake3L1<N, M> = Blake3<N, M>;
+
+impl<N: ArraySize, M: ArraySize> HashSuite for Blake3<N, M>
 where
     N: core::ops::Add<N>,
     Sum<N, N>: ArraySize,
@@ -51,7 +85,6 @@ where
         hasher.update(opt_rand.as_slice());
         msg.iter().copied().flatten().for_each(|msg_part| {
             hasher.update(msg_part.as_ref());
-            ();
         });
         let output = hasher.finalize();
         Array::clone_from_slice(&output.as_bytes()[..Self::N::USIZE])
@@ -69,7 +102,6 @@ where
         hasher.update(pk_root.as_slice());
         msg.iter().copied().flatten().for_each(|msg_part| {
             hasher.update(msg_part.as_ref());
-            ();
         });
         let mut result = Array::<u8, Self::M>::default();
         let mut xof = hasher.finalize_xof();
@@ -106,7 +138,6 @@ where
         hasher.update(adrs.compressed().as_slice());
         m.iter().for_each(|x| {
             hasher.update(x.as_slice());
-            ();
         });
         let output = hasher.finalize();
         Array::clone_from_slice(&output.as_bytes()[..Self::N::USIZE])
@@ -148,7 +179,7 @@ where
 }
 
 /// BLAKE3 at L1 security with small signatures
-pub type Blake3_128s = Blake3L1<U16, U30>;
+pub type Blake3_128s = Blake3<U16, U30>;
 impl WotsParams for Blake3_128s {
     type WotsMsgLen = U<32>;
     type WotsSigLen = U<35>;
@@ -167,8 +198,7 @@ impl ForsParams for Blake3_128s {
 }
 impl ParameterSet for Blake3_128s {
     const NAME: &'static str = "SLH-DSA-BLAKE3-128s";
-    // TODO: Need to define proper OID for BLAKE3 variants
-    const ALGORITHM_OID: pkcs8::ObjectIdentifier = fips205::ID_SLH_DSA_SHA_2_128_S;
+    const ALGORITHM_OID: pkcs8::ObjectIdentifier = blake3_oid!("20");
 }
 
 impl SignatureLen for Blake3_128s {
@@ -184,7 +214,7 @@ impl VerifyingKeyLen for Blake3_128s {
 }
 
 /// BLAKE3 at L1 security with fast signatures
-pub type Blake3_128f = Blake3L1<U16, U34>;
+pub type Blake3_128f = Blake3<U16, U34>;
 impl WotsParams for Blake3_128f {
     type WotsMsgLen = U<32>;
     type WotsSigLen = U<35>;
@@ -203,8 +233,7 @@ impl ForsParams for Blake3_128f {
 }
 impl ParameterSet for Blake3_128f {
     const NAME: &'static str = "SLH-DSA-BLAKE3-128f";
-    // TODO: Need to define proper OID for BLAKE3 variants
-    const ALGORITHM_OID: pkcs8::ObjectIdentifier = fips205::ID_SLH_DSA_SHA_2_128_F;
+    const ALGORITHM_OID: pkcs8::ObjectIdentifier = blake3_oid!("21");
 }
 
 impl SignatureLen for Blake3_128f {
@@ -219,8 +248,145 @@ impl VerifyingKeyLen for Blake3_128f {
     type VkLen = U<32>; // Same as SHA2L1<U16, M>
 }
 
-// Note: We'll start with L1 security level implementations first
-// L3 and L5 implementations can be added later following the same pattern
+/// BLAKE3 with 192-bit hashes and small signatures
+pub type Blake3_192s = Blake3<U24, U39>;
+impl WotsParams for Blake3_192s {
+    type WotsMsgLen = U<{ 24 * 2 }>;
+    type WotsSigLen = U<{ 24 * 2 + 3 }>;
+}
+impl XmssParams for Blake3_192s {
+    type HPrime = U<9>;
+}
+impl HypertreeParams for Blake3_192s {
+    type D = U<7>;
+    type H = U<63>;
+}
+impl ForsParams for Blake3_192s {
+    type K = U<17>;
+    type A = U<14>;
+    type MD = U<{ (14 * 17usize).div_ceil(8) }>;
+}
+impl ParameterSet for Blake3_192s {
+    const NAME: &'static str = "SLH-DSA-BLAKE3-192s";
+    const ALGORITHM_OID: pkcs8::ObjectIdentifier = blake3_oid!("22");
+}
+
+impl SignatureLen for Blake3_192s {
+    type SigLen = U16224; // Same as SHA2-192s
+}
+
+impl SigningKeyLen for Blake3_192s {
+    type SkLen = U<{ 4 * 24 }>; // Same as Sha2L35<U24, M>
+}
+
+impl VerifyingKeyLen for Blake3_192s {
+    type VkLen = U<48>; // Same as Sha2L35<U24, M>
+}
+
+/// BLAKE3 with 192-bit hashes and fast signatures
+pub type Blake3_192f = Blake3<U24, U42>;
+impl WotsParams for Blake3_192f {
+    type WotsMsgLen = U<{ 24 * 2 }>;
+    type WotsSigLen = U<{ 24 * 2 + 3 }>;
+}
+impl XmssParams for Blake3_192f {
+    type HPrime = U<3>;
+}
+impl HypertreeParams for Blake3_192f {
+    type D = U<22>;
+    type H = U<66>;
+}
+impl ForsParams for Blake3_192f {
+    type K = U<33>;
+    type A = U<8>;
+    type MD = U<{ (8 * 33usize).div_ceil(8) }>;
+}
+impl ParameterSet for Blake3_192f {
+    const NAME: &'static str = "SLH-DSA-BLAKE3-192f";
+    const ALGORITHM_OID: pkcs8::ObjectIdentifier = blake3_oid!("23");
+}
+
+impl SignatureLen for Blake3_192f {
+    type SigLen = U35664; // Same as SHA2-192f
+}
+
+impl SigningKeyLen for Blake3_192f {
+    type SkLen = U<{ 4 * 24 }>; // Same as Sha2L35<U24, M>
+}
+
+impl VerifyingKeyLen for Blake3_192f {
+    type VkLen = U<48>; // Same as Sha2L35<U24, M>
+}
+
+/// BLAKE3 with 256-bit hashes and small signatures
+pub type Blake3_256s = Blake3<U32, U47>;
+impl WotsParams for Blake3_256s {
+    type WotsMsgLen = U<{ 32 * 2 }>;
+    type WotsSigLen = U<{ 32 * 2 + 3 }>;
+}
+impl XmssParams for Blake3_256s {
+    type HPrime = U<8>;
+}
+impl HypertreeParams for Blake3_256s {
+    type D = U<8>;
+    type H = U<64>;
+}
+impl ForsParams for Blake3_256s {
+    type K = U<22>;
+    type A = U<14>;
+    type MD = U<{ (14 * 22usize).div_ceil(8) }>;
+}
+impl ParameterSet for Blake3_256s {
+    const NAME: &'static str = "SLH-DSA-BLAKE3-256s";
+    const ALGORITHM_OID: pkcs8::ObjectIdentifier = blake3_oid!("24");
+}
+
+impl SignatureLen for Blake3_256s {
+    type SigLen = U29792; // Same as SHA2-256s
+}
+
+impl SigningKeyLen for Blake3_256s {
+    type SkLen = U<{ 4 * 32 }>; // Same as Sha2L35<U32, M>
+}
+
+impl VerifyingKeyLen for Blake3_256s {
+    type VkLen = U<64>; // Same as Sha2L35<U32, M>
+}
+
+/// BLAKE3 with 256-bit hashes and fast signatures
+pub type Blake3_256f = Blake3<U32, U49>;
+impl WotsParams for Blake3_256f {
+    type WotsMsgLen = U<{ 32 * 2 }>;
+    type WotsSigLen = U<{ 32 * 2 + 3 }>;
+}
+impl XmssParams for Blake3_256f {
+    type HPrime = U<4>;
+}
+impl HypertreeParams for Blake3_256f {
+    type D = U<17>;
+    type H = U<68>;
+}
+impl ForsParams for Blake3_256f {
+    type K = U<35>;
+    type A = U<9>;
+    type MD = U<{ (9 * 35usize).div_ceil(8) }>;
+}
+impl ParameterSet for Blake3_256f {
+    const NAME: &'static str = "SLH-DSA-BLAKE3-256f";
+    const ALGORITHM_OID: pkcs8::ObjectIdentifier = blake3_oid!("25");
+}
+
+impl SignatureLen for Blake3_256f {
+    type SigLen = U49856; // Same as SHA2-256f
+}
+
+impl SigningKeyLen for Blake3_256f {
+    type SkLen = U<{ 4 * 32 }>; // Same as Sha2L35<U32, M>
+}
+
+impl VerifyingKeyLen for Blake3_256f {
+    type VkLen = U<64>; // Same as Sha2L35<U32, M>
+}
 
 #[cfg(test)]
 mod tests {
@@ -287,4 +453,30 @@ mod tests {
             13, 56, 25, 45, 30, 55, 107, 51, 51, 87, 242, 132, 225, 64, 215, 106,
         ]);
     }
+
+    #[test]
+    fn prf_msg_blake3_192s() {
+        prf_msg::<Blake3_192s>(&hex!("24eba996cf24a281cfc2bc06db5f61f77c0daa1d937e0869"));
+    }
+
+    #[test]
+    fn prf_msg_blake3_256s() {
+        prf_msg::<Blake3_256s>(&hex!(
+            "408f4f12a0f5040e3d76a4a38b173560da9f2f15163e9cadecd40049cae3c145"
+        ));
+    }
+
+    #[test]
+    fn h_msg_blake3_192s() {
+        h_msg::<Blake3_192s>(&hex!(
+            "5177a49852640d363b4426f3d864a33c25b8ed14ef5425ea2c078f3d855909a54358f700b434c7"
+        ));
+    }
+
+    #[test]
+    fn h_msg_blake3_256f() {
+        h_msg::<Blake3_256f>(&hex!(
+            "f7cb3b648ea8b580fb31e8e43e013a1c5d515eed43e29c3db289c9e1b5b4f82efaba916e5fe2138fe22acf1c46e8d54c0e"
+        ));
+    }
 }
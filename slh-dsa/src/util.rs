@@ -79,6 +79,13 @@ pub mod macros {
             crate::gen_test!($name, Sha2_192s);
             crate::gen_test!($name, Sha2_256f);
             crate::gen_test!($name, Sha2_256s);
+
+            crate::gen_test!($name, Blake3_128f);
+            crate::gen_test!($name, Blake3_128s);
+            crate::gen_test!($name, Blake3_192f);
+            crate::gen_test!($name, Blake3_192s);
+            crate::gen_test!($name, Blake3_256f);
+            crate::gen_test!($name, Blake3_256s);
         };
     }
 
@@ -93,12 +100,12 @@ mod tests {
     use typenum::U;
 
     fn test_base_2b<OutLen: ArraySize, B: Unsigned>(x: &[u8]) {
-        if x.len() < (OutLen::USIZE * B::USIZE + 7) / 8 {
+        if x.len() < (OutLen::USIZE * B::USIZE).div_ceil(8) {
             return; // TODO: enforce this at the prop level
         }
 
         let a = base_2b::<OutLen, B>(x);
-        let mut b = BigUint::from_bytes_be(&x[..((OutLen::USIZE * B::USIZE + 7) / 8)]);
+        let mut b = BigUint::from_bytes_be(&x[..((OutLen::USIZE * B::USIZE).div_ceil(8))]);
 
         if (B::USIZE * OutLen::USIZE) % 8 != 0 {
             // Clear lower bits of b
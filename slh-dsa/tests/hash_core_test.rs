@@ -103,9 +103,9 @@ fn compare_core_operations() {
     let start = Instant::now();
     for _ in 0..iterations {
         let mut hasher = sha2::Sha256::new();
-        hasher.update(&msg1);
-        hasher.update(&msg2);
-        hasher.update(&msg3);
+        hasher.update(msg1);
+        hasher.update(msg2);
+        hasher.update(msg3);
         let result = hasher.finalize();
         let mut output = [0u8; 34];
         output[..32].copy_from_slice(&result);
@@ -1,5 +1,4 @@
 use hmac::{Hmac, KeyInit, Mac};
-use sha2::Digest;
 use std::time::Instant;
 
 #[test]
@@ -229,3 +229,39 @@ fn test_kat_shake_256s() {
 fn test_kat_shake_256f() {
     test_kat::<Shake256f>("be37b5222c98b3a1f0d2d3d69bc32205ed17e93c6a4da684c76ee1ca29ec28ef");
 }
+
+/*
+There is no reference implementation of the BLAKE3 parameter sets, so their KATs were generated by
+this crate with the same procedure as above. They guard the construction documented on `Blake3`
+against accidental changes.
+*/
+
+#[test]
+fn test_kat_blake3_128s() {
+    test_kat::<Blake3_128s>("07c57a027b4996a018d25a5f8138be04709e89293f484c02615c575727106002");
+}
+
+#[test]
+fn test_kat_blake3_128f() {
+    test_kat::<Blake3_128f>("3f3b86c302e4a8832ac5a17e3d163febefedf080c5c89daf4578d08e9a08879b");
+}
+
+#[test]
+fn test_kat_blake3_192s() {
+    test_kat::<Blake3_192s>("2cc658e7659ad4b0a6596b440b6bafb0bc1cc95ec003b60c485f02dd129e1286");
+}
+
+#[test]
+fn test_kat_blake3_192f() {
+    test_kat::<Blake3_192f>("3aa975d899dd1c84a5afc1f9e1f038c395ed2e9c25db3069708d213a772435ff");
+}
+
+#[test]
+fn test_kat_blake3_256s() {
+    test_kat::<Blake3_256s>("faae2409add8d6f5941995d04d1a0886283c070c6d245339dc6037462dbf106b");
+}
+
+#[test]
+fn test_kat_blake3_256f() {
+    test_kat::<Blake3_256f>("423de043e4bad344f3a8cd0c40c5904b0b2031930ffb37eb28bfaae17e5d2e18");
+}
@@ -1,4 +1,4 @@
-use signature::{Keypair, Signer};
+use signature::Signer;
 use slh_dsa::*;
 use std::time::Instant;
 
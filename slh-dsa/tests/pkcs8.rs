@@ -1,8 +1,11 @@
 #![cfg(feature = "alloc")]
 
 use hex_literal::hex;
-use pkcs8::{DecodePrivateKey, EncodePrivateKey, EncodePublicKey, LineEnding};
-use slh_dsa::{Sha2_128s, SigningKey, VerifyingKey};
+use pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey, LineEnding};
+use slh_dsa::{
+    Blake3_128f, Blake3_128s, Blake3_192f, Blake3_192s, Blake3_256f, Blake3_256s, ParameterSet,
+    Sha2_128f, Sha2_128s, Sha2_192f, Sha2_192s, Sha2_256f, Sha2_256s, SigningKey, VerifyingKey,
+};
 use std::ops::Deref;
 
 // Serialization of the SLH-DSA keys is still a draft
@@ -42,3 +45,47 @@ tJQ=
 "#
     );
 }
+
+#[test]
+fn blake3_oid_mismatch() {
+    let mut rng = rand::rng();
+    let blake3 = SigningKey::<Blake3_128s>::new(&mut rng);
+    let der = blake3.to_pkcs8_der().unwrap();
+    assert!(SigningKey::<Sha2_128s>::from_pkcs8_der(der.as_bytes()).is_err());
+    assert!(SigningKey::<Blake3_128f>::from_pkcs8_der(der.as_bytes()).is_err());
+    assert_eq!(
+        SigningKey::<Blake3_128s>::from_pkcs8_der(der.as_bytes()).unwrap(),
+        blake3
+    );
+
+    let der = blake3.as_ref().to_public_key_der().unwrap();
+    assert!(VerifyingKey::<Sha2_128s>::from_public_key_der(der.as_bytes()).is_err());
+
+    let sha2 = SigningKey::<Sha2_128s>::new(&mut rng);
+    let der = sha2.to_pkcs8_der().unwrap();
+    assert!(SigningKey::<Blake3_128s>::from_pkcs8_der(der.as_bytes()).is_err());
+}
+
+#[test]
+fn blake3_oids() {
+    // The arc of `ml-dsa` ends in `1` for ML-DSA, and the BLAKE3 sets are `2.1` under the same
+    // prefix, followed by the last arc of the SHA2 set with the same parameters
+    let prefix = ml_dsa::PRIVATE_ARC.strip_suffix(".1").unwrap();
+    fn check<B: ParameterSet, S: ParameterSet>(prefix: &str) {
+        let sha2 = S::ALGORITHM_OID.to_string();
+        let last = sha2.rsplit('.').next().unwrap();
+        assert_eq!(
+            B::ALGORITHM_OID.to_string(),
+            format!("{prefix}.2.1.{last}"),
+            "{}",
+            B::NAME
+        );
+    }
+
+    check::<Blake3_128s, Sha2_128s>(prefix);
+    check::<Blake3_128f, Sha2_128f>(prefix);
+    check::<Blake3_192s, Sha2_192s>(prefix);
+    check::<Blake3_192f, Sha2_192f>(prefix);
+    check::<Blake3_256s, Sha2_256s>(prefix);
+    check::<Blake3_256f, Sha2_256f>(prefix);
+}